@@ -1,5 +1,5 @@
 use crate::queries::{
-    BetaRow, InviteDistRow, InviteRow, OverviewStats, Registration, UserRow, WorkoutRow,
+    BetaRow, InviteDistRow, InviteRow, JobRow, OverviewStats, Registration, UserRow, WorkoutRow,
 };
 
 pub fn overview(stats: &OverviewStats, registrations: &[Registration], workouts: &[WorkoutRow]) {
@@ -158,6 +158,56 @@ pub fn activity(workouts: &[WorkoutRow], days: u32) {
     }
 }
 
+pub fn jobs(rows: &[JobRow]) {
+    println!("BACKGROUND JOBS");
+    if rows.is_empty() {
+        println!("  (none registered — has the server run since upgrading?)");
+        return;
+    }
+    println!(
+        "  {:<22} {:>8} {:<20} {:<20} {:<7} {:>6} {:>5}",
+        "JOB", "EVERY", "NEXT RUN", "LAST RUN", "STATUS", "RUNS", "FAIL"
+    );
+    println!("  {}", "─".repeat(94));
+    for j in rows {
+        let every = if j.interval_secs > 0 {
+            format_interval(j.interval_secs)
+        } else {
+            "manual".to_string()
+        };
+        let next = if j.run_requested {
+            "requested".to_string()
+        } else if j.interval_secs > 0 {
+            j.next_run_at.clone()
+        } else {
+            "—".to_string()
+        };
+        println!(
+            "  {:<22} {:>8} {:<20} {:<20} {:<7} {:>6} {:>5}",
+            j.name,
+            every,
+            next,
+            j.last_finished_at.as_deref().unwrap_or("never"),
+            j.last_status.as_deref().unwrap_or("—"),
+            j.run_count,
+            j.failure_count,
+        );
+        if let Some(message) = &j.last_message {
+            let took = j.last_duration_ms.map(|ms| format!(" ({ms}ms)")).unwrap_or_default();
+            println!("    {message}{took}");
+        }
+    }
+}
+
+fn format_interval(secs: i64) -> String {
+    match secs {
+        s if s % 86400 == 0 => format!("{}d", s / 86400),
+        s if s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s % 60 == 0 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
 fn format_date(datetime_str: &str) -> String {
     // Timestamps are stored as "YYYY-MM-DD HH:MM:SS" — extract just the date
     datetime_str.split(' ').next().unwrap_or(datetime_str).to_string()
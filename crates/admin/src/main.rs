@@ -32,14 +32,33 @@ enum Commands {
     },
     /// Beta signups
     Beta,
+    /// Background job schedule and last results
+    Jobs {
+        #[command(subcommand)]
+        action: Option<JobsAction>,
+    },
 }
 
-fn open_db(cli: &Cli) -> Result<Connection, String> {
-    let path = cli
-        .db
+#[derive(Subcommand)]
+enum JobsAction {
+    /// List jobs (default)
+    List,
+    /// Ask the running server to run a job on its next poll
+    Run {
+        /// Job name, as shown by `lw-admin jobs`
+        name: String,
+    },
+}
+
+fn db_path(cli: &Cli) -> Result<String, String> {
+    cli.db
         .clone()
         .or_else(|| env::var("LW_DB_PATH").ok())
-        .ok_or("No database path. Use --db <path> or set LW_DB_PATH")?;
+        .ok_or_else(|| "No database path. Use --db <path> or set LW_DB_PATH".to_string())
+}
+
+fn open_db(cli: &Cli) -> Result<Connection, String> {
+    let path = db_path(cli)?;
 
     let conn = Connection::open_with_flags(
         &path,
@@ -53,9 +72,24 @@ fn open_db(cli: &Cli) -> Result<Connection, String> {
     Ok(conn)
 }
 
+/// Read-write connection, only for commands that change server state.
+fn open_db_writable(cli: &Cli) -> Result<Connection, String> {
+    let path = db_path(cli)?;
+    let conn = Connection::open_with_flags(&path, rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|e| format!("Cannot open database at {path}: {e}"))?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| format!("Failed to set busy timeout: {e}"))?;
+    Ok(conn)
+}
+
 fn main() {
     let cli = Cli::parse();
-    let conn = match open_db(&cli) {
+    let writable = matches!(
+        cli.command,
+        Some(Commands::Jobs { action: Some(JobsAction::Run { .. }) })
+    );
+    let opened = if writable { open_db_writable(&cli) } else { open_db(&cli) };
+    let conn = match opened {
         Ok(c) => c,
         Err(e) => {
             eprintln!("Error: {e}");
@@ -69,6 +103,10 @@ fn main() {
         Commands::Invites => run_invites(&conn),
         Commands::Activity { days } => run_activity(&conn, days),
         Commands::Beta => run_beta(&conn),
+        Commands::Jobs { action } => match action.unwrap_or(JobsAction::List) {
+            JobsAction::List => run_jobs(&conn),
+            JobsAction::Run { name } => run_job_now(&conn, &name),
+        },
     };
 
     if let Err(e) = result {
@@ -109,3 +147,17 @@ fn run_beta(conn: &Connection) -> Result<(), String> {
     display::beta(&signups);
     Ok(())
 }
+
+fn run_jobs(conn: &Connection) -> Result<(), String> {
+    let jobs = queries::jobs(conn)?;
+    display::jobs(&jobs);
+    Ok(())
+}
+
+fn run_job_now(conn: &Connection, name: &str) -> Result<(), String> {
+    if !queries::request_job_run(conn, name)? {
+        return Err(format!("No job named '{name}'. Run `lw-admin jobs` to list them."));
+    }
+    println!("Requested {name}; the server runs it within jobs.poll_secs.");
+    Ok(())
+}
@@ -46,6 +46,19 @@ pub struct WorkoutRow {
     pub set_count: i64,
 }
 
+pub struct JobRow {
+    pub name: String,
+    pub interval_secs: i64,
+    pub next_run_at: String,
+    pub last_finished_at: Option<String>,
+    pub last_status: Option<String>,
+    pub last_message: Option<String>,
+    pub last_duration_ms: Option<i64>,
+    pub run_count: i64,
+    pub failure_count: i64,
+    pub run_requested: bool,
+}
+
 fn map_err(e: rusqlite::Error) -> String {
     format!("Query failed: {e}")
 }
@@ -278,3 +291,41 @@ pub fn recent_activity(conn: &Connection, days: u32) -> Result<Vec<WorkoutRow>,
 
     rows.collect::<Result<Vec<_>, _>>().map_err(map_err)
 }
+
+pub fn jobs(conn: &Connection) -> Result<Vec<JobRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name, interval_secs, next_run_at, last_finished_at, last_status,
+                    last_message, last_duration_ms, run_count, failure_count, run_requested
+             FROM jobs ORDER BY name",
+        )
+        .map_err(map_err)?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(JobRow {
+                name: row.get(0)?,
+                interval_secs: row.get(1)?,
+                next_run_at: row.get(2)?,
+                last_finished_at: row.get(3)?,
+                last_status: row.get(4)?,
+                last_message: row.get(5)?,
+                last_duration_ms: row.get(6)?,
+                run_count: row.get(7)?,
+                failure_count: row.get(8)?,
+                run_requested: row.get(9)?,
+            })
+        })
+        .map_err(map_err)?;
+
+    rows.collect::<Result<Vec<_>, _>>().map_err(map_err)
+}
+
+/// Flag a job for the running server to pick up on its next poll.
+/// Returns false if no such job is registered.
+pub fn request_job_run(conn: &Connection, name: &str) -> Result<bool, String> {
+    let updated = conn
+        .execute("UPDATE jobs SET run_requested = 1 WHERE name = ?1", [name])
+        .map_err(map_err)?;
+    Ok(updated > 0)
+}
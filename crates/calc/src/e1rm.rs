@@ -58,6 +58,41 @@ pub fn pct_change(current: f64, previous: f64) -> Option<f64> {
     Some(((current - previous) / previous) * 100.0)
 }
 
+/// Highest RIR [`load_for`] prescribes at (RPE 5).
+pub const MAX_RIR: i64 = 5;
+
+/// Rep counts in a rep-max table: 1RM to 15RM.
+pub const REP_MAX_TABLE_REPS: i64 = 15;
+
+/// Heaviest load for `reps` at this e1RM: the inverse of [`e1rm`], so a set
+/// of `reps` at the result scores exactly `e1rm_kg`. None for a non-positive
+/// e1RM or reps outside 1..=30, the same range as [`reps_to_beat`].
+pub fn rep_max(e1rm_kg: f64, reps: i64) -> Option<f64> {
+    if e1rm_kg <= 0.0 || !(1..=30).contains(&reps) {
+        return None;
+    }
+    Some(e1rm_kg / (1.0 + reps as f64 / 30.0))
+}
+
+/// Load for `reps` leaving `rir` in the tank, as an RPE chart reads it
+/// (RPE = 10 − RIR): the rep max for `reps + rir`. This prescribes forward
+/// from a known e1RM; e1RMs themselves are still estimated from raw reps
+/// only. None when [`rep_max`] is, or `rir` is outside 0..=[`MAX_RIR`].
+pub fn load_for(e1rm_kg: f64, reps: i64, rir: i64) -> Option<f64> {
+    if !(0..=MAX_RIR).contains(&rir) || reps < 1 {
+        return None;
+    }
+    rep_max(e1rm_kg, reps + rir)
+}
+
+/// `(reps, load)` for 1 to [`REP_MAX_TABLE_REPS`] reps at `rir`. Empty when
+/// [`load_for`] has no answer.
+pub fn rep_max_table(e1rm_kg: f64, rir: i64) -> Vec<(i64, f64)> {
+    (1..=REP_MAX_TABLE_REPS)
+        .filter_map(|reps| load_for(e1rm_kg, reps, rir).map(|load| (reps, load)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,4 +346,56 @@ mod tests {
     fn pct_change_both_zero() {
         assert_eq!(pct_change(0.0, 0.0), None);
     }
+
+    // ---------------------------------------------------------------
+    // rep_max / load_for — inverse Epley
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn rep_max_inverts_e1rm() {
+        for reps in 1..=30 {
+            let load = rep_max(120.0, reps).unwrap();
+            assert!((e1rm(load, reps) - 120.0).abs() < 1e-9, "{} reps", reps);
+        }
+        // 100 × 5 → 116.67; its 5RM is 100 again.
+        assert!((rep_max(e1rm(100.0, 5), 5).unwrap() - 100.0).abs() < 1e-9);
+        assert_eq!(rep_max(120.0, 10), Some(90.0));
+    }
+
+    #[test]
+    fn rep_max_out_of_domain() {
+        assert_eq!(rep_max(120.0, 0), None);
+        assert_eq!(rep_max(120.0, 31), None);
+        assert_eq!(rep_max(0.0, 5), None);
+        assert_eq!(rep_max(-10.0, 5), None);
+    }
+
+    #[test]
+    fn load_for_adds_rir_to_reps() {
+        // 8 reps at RIR 2 is a 10RM.
+        assert_eq!(load_for(120.0, 8, 2), rep_max(120.0, 10));
+        assert_eq!(load_for(120.0, 5, 0), rep_max(120.0, 5));
+        assert!(load_for(120.0, 5, 3).unwrap() < load_for(120.0, 5, 1).unwrap());
+    }
+
+    #[test]
+    fn load_for_out_of_domain() {
+        assert_eq!(load_for(120.0, 8, -1), None);
+        assert_eq!(load_for(120.0, 8, MAX_RIR + 1), None);
+        assert_eq!(load_for(120.0, 0, 2), None);
+        assert_eq!(load_for(120.0, 28, 3), None); // 31 reps to failure
+        assert_eq!(load_for(120.0, 27, 3), rep_max(120.0, 30));
+    }
+
+    #[test]
+    fn rep_max_table_rows() {
+        let table = rep_max_table(120.0, 0);
+        assert_eq!(table.len(), REP_MAX_TABLE_REPS as usize);
+        assert_eq!(table[0].0, 1);
+        assert_eq!(table[9], (10, 90.0));
+        assert!(table.windows(2).all(|w| w[1].1 < w[0].1));
+        assert_eq!(rep_max_table(120.0, 2)[0], (1, rep_max(120.0, 3).unwrap()));
+        assert!(rep_max_table(0.0, 0).is_empty());
+        assert!(rep_max_table(120.0, 9).is_empty());
+    }
 }
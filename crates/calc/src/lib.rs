@@ -1,5 +1,8 @@
 pub mod e1rm;
+pub mod plateau;
+pub mod plates;
 pub mod pr;
+pub mod relative_strength;
 pub mod trend;
 pub mod validation;
 pub mod vectors;
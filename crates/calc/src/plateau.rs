@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+
+use crate::trend::{self, Trend, TrendPoint};
+
+/// One session of an exercise, in chronological order.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+    /// Day number on any fixed origin; only differences matter.
+    pub day: f64,
+    pub best_e1rm: f64,
+    /// Mean effort of the session's strength sets as RPE (10 − RIR); None
+    /// when no set had RIR logged.
+    pub effort: Option<f64>,
+}
+
+/// What to change about a stalled lift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Intervention {
+    /// Fatigue is masking fitness: back off for a week, then rebuild.
+    Deload,
+    /// Same load and reps have gone stale: move the rep target.
+    RepRange,
+    /// A long stall through rep changes: swap to a close variation.
+    VariationSwap,
+}
+
+impl Intervention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Intervention::Deload => "deload",
+            Intervention::RepRange => "rep_range",
+            Intervention::VariationSwap => "variation_swap",
+        }
+    }
+}
+
+/// A stalled lift and the evidence for it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plateau {
+    /// Index into the exposures of the session that set the standing best.
+    pub best_index: usize,
+    /// Sessions since the standing best, not counting it.
+    pub exposures_since_best: usize,
+    /// Fitted e1RM slope over the best and everything after, kg per week.
+    pub slope_per_week: f64,
+    /// Mean effort of the later half of the stall minus the earlier half,
+    /// in RPE points; None without effort on both halves.
+    pub effort_change: Option<f64>,
+    pub intervention: Intervention,
+}
+
+/// Effort climbing by this many RPE points across a stall counts as rising.
+pub const EFFORT_RISE: f64 = 0.5;
+
+/// Flag a plateau in an exercise's exposures (chronological). A lift is
+/// stalled when both hold:
+///
+/// - no exposure has beaten the best e1RM for `stall_after` exposures,
+/// - the trend fitted from that best onwards, outliers dropped, is not Up.
+///
+/// Rising effort doesn't decide whether a lift has stalled, only what to do
+/// about it: the same e1RM at a higher RPE is accumulated fatigue.
+///
+/// The intervention follows fixed rules, first match wins:
+///
+/// 1. effort rising by [`EFFORT_RISE`] or more, or the trend Down → Deload
+/// 2. stalled for twice `stall_after` or longer → VariationSwap
+/// 3. otherwise → RepRange
+///
+/// Returns None when the lift is still progressing or `stall_after` is 0.
+pub fn detect_plateau(exposures: &[Exposure], stall_after: usize) -> Option<Plateau> {
+    if stall_after == 0 {
+        return None;
+    }
+    // The standing best: the first exposure that reached the maximum.
+    let mut best_index = 0;
+    for (i, e) in exposures.iter().enumerate() {
+        if e.best_e1rm > exposures[best_index].best_e1rm {
+            best_index = i;
+        }
+    }
+    let since = exposures.len().checked_sub(best_index + 1)?;
+    if since < stall_after {
+        return None;
+    }
+
+    let window = &exposures[best_index..];
+    let points: Vec<TrendPoint> = window.iter().map(|e| TrendPoint { day: e.day, e1rm: e.best_e1rm }).collect();
+    // One day's worth of exposures has no slope; treat it as flat.
+    let fit = trend::fit_trend(&trend::filter_outliers(&points));
+    let (slope, direction) = fit.map_or((0.0, Trend::Flat), |f| (f.slope_per_week, f.direction));
+    if direction == Trend::Up {
+        return None;
+    }
+
+    let effort_change = effort_change(window);
+    let intervention = if effort_change.is_some_and(|c| c >= EFFORT_RISE) || direction == Trend::Down {
+        Intervention::Deload
+    } else if since >= 2 * stall_after {
+        Intervention::VariationSwap
+    } else {
+        Intervention::RepRange
+    };
+
+    Some(Plateau {
+        best_index,
+        exposures_since_best: since,
+        slope_per_week: slope,
+        effort_change,
+        intervention,
+    })
+}
+
+/// Mean effort of the later half minus the earlier half; the middle
+/// exposure of an odd window sits out.
+fn effort_change(window: &[Exposure]) -> Option<f64> {
+    let half = window.len() / 2;
+    let mean = |part: &[Exposure]| {
+        let efforts: Vec<f64> = part.iter().filter_map(|e| e.effort).collect();
+        if efforts.is_empty() {
+            None
+        } else {
+            Some(efforts.iter().sum::<f64>() / efforts.len() as f64)
+        }
+    };
+    Some(mean(&window[window.len() - half..])? - mean(&window[..half])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Weekly exposures with no effort logged.
+    fn weekly(e1rms: &[f64]) -> Vec<Exposure> {
+        e1rms
+            .iter()
+            .enumerate()
+            .map(|(i, &best_e1rm)| Exposure { day: 7.0 * i as f64, best_e1rm, effort: None })
+            .collect()
+    }
+
+    fn with_effort(mut exposures: Vec<Exposure>, efforts: &[f64]) -> Vec<Exposure> {
+        for (e, &rpe) in exposures.iter_mut().zip(efforts) {
+            e.effort = Some(rpe);
+        }
+        exposures
+    }
+
+    #[test]
+    fn progressing_lift_is_not_a_plateau() {
+        assert_eq!(detect_plateau(&weekly(&[100.0, 102.0, 104.0, 106.0, 108.0]), 4), None);
+    }
+
+    #[test]
+    fn recent_best_is_not_a_plateau() {
+        // Best three exposures ago, threshold four.
+        assert_eq!(detect_plateau(&weekly(&[100.0, 110.0, 108.0, 109.0, 108.5]), 4), None);
+    }
+
+    #[test]
+    fn flat_stall_suggests_rep_range() {
+        let p = detect_plateau(&weekly(&[100.0, 110.0, 109.0, 110.0, 108.5, 109.5]), 4).unwrap();
+        assert_eq!(p.best_index, 1);
+        assert_eq!(p.exposures_since_best, 4);
+        assert_eq!(p.effort_change, None);
+        assert_eq!(p.intervention, Intervention::RepRange);
+    }
+
+    #[test]
+    fn tie_does_not_reset_the_stall() {
+        // 110 again is a match, not a new best.
+        let p = detect_plateau(&weekly(&[110.0, 108.0, 110.0, 109.0, 110.0]), 4).unwrap();
+        assert_eq!(p.best_index, 0);
+        assert_eq!(p.exposures_since_best, 4);
+    }
+
+    #[test]
+    fn rising_effort_suggests_deload() {
+        let exposures = with_effort(weekly(&[110.0, 109.0, 110.0, 109.5, 109.0]), &[8.0, 8.0, 8.5, 9.0, 9.5]);
+        let p = detect_plateau(&exposures, 4).unwrap();
+        // Later half (9.0, 9.5) against earlier half (8.0, 8.0).
+        assert_eq!(p.effort_change, Some(1.25));
+        assert_eq!(p.intervention, Intervention::Deload);
+    }
+
+    #[test]
+    fn steady_effort_does_not_force_deload() {
+        let exposures = with_effort(weekly(&[110.0, 109.0, 110.0, 109.5, 109.0]), &[8.0, 8.5, 8.0, 8.0, 8.5]);
+        let p = detect_plateau(&exposures, 4).unwrap();
+        assert_eq!(p.intervention, Intervention::RepRange);
+    }
+
+    #[test]
+    fn declining_lift_suggests_deload() {
+        let p = detect_plateau(&weekly(&[120.0, 117.0, 114.5, 112.0, 109.0]), 4).unwrap();
+        assert!(p.slope_per_week < 0.0);
+        assert_eq!(p.intervention, Intervention::Deload);
+    }
+
+    #[test]
+    fn long_stall_suggests_variation_swap() {
+        let p = detect_plateau(
+            &weekly(&[110.0, 108.0, 109.5, 108.5, 109.0, 108.0, 109.5, 108.5, 109.0]),
+            4,
+        )
+        .unwrap();
+        assert_eq!(p.exposures_since_best, 8);
+        assert_eq!(p.intervention, Intervention::VariationSwap);
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        assert_eq!(detect_plateau(&[], 4), None);
+        assert_eq!(detect_plateau(&weekly(&[100.0, 100.0, 100.0]), 0), None);
+    }
+
+    #[test]
+    fn intervention_as_str() {
+        assert_eq!(Intervention::Deload.as_str(), "deload");
+        assert_eq!(Intervention::RepRange.as_str(), "rep_range");
+        assert_eq!(Intervention::VariationSwap.as_str(), "variation_swap");
+    }
+}
@@ -0,0 +1,254 @@
+//! Achievable loads: how to build a target weight from the plates or
+//! dumbbells actually on hand, or the nearest weight that can be built.
+//!
+//! Weights are matched in whole grams so that sums like 3 × 1.25 don't
+//! drift off the plate they should equal.
+
+use serde::{Deserialize, Serialize};
+
+/// Plate weights a kit may list are whole multiples of this. Finer plates
+/// would shrink the step the loading table counts in.
+pub const PLATE_UNIT_KG: f64 = 0.25;
+
+/// Largest per-side sum, in steps, that [`load_barbell`] will search.
+const MAX_STEPS: usize = 20_000;
+/// Largest loading table, plates × steps: 8 MB of cells.
+const MAX_CELLS: usize = 2_000_000;
+
+/// Plates of one weight, counted in pairs since a bar is loaded evenly.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PlateStock {
+    pub weight_kg: f64,
+    pub pairs: u32,
+}
+
+/// A bar loading: the plates for one side, heaviest first, and the total
+/// they make with the bar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BarbellLoad {
+    pub total_kg: f64,
+    pub per_side: Vec<f64>,
+}
+
+/// Whether `weight_kg` is a positive whole multiple of [`PLATE_UNIT_KG`].
+pub fn is_plate_weight(weight_kg: f64) -> bool {
+    let units = weight_kg / PLATE_UNIT_KG;
+    weight_kg > 0.0 && units.is_finite() && (units - units.round()).abs() < 1e-9
+}
+
+fn grams(kg: f64) -> u64 {
+    (kg * 1000.0).round() as u64
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Load the bar as close to `target_kg` as the plates allow, using the
+/// fewest plates for that weight and, among those, the heaviest. A tie
+/// between a lighter and a heavier loading goes to the lighter one. A
+/// target at or under the bar gives the empty bar. None for a non-positive
+/// target or a negative bar, and when the plates and target would need a
+/// table past [`MAX_STEPS`] or [`MAX_CELLS`] to search.
+pub fn load_barbell(target_kg: f64, bar_kg: f64, plates: &[PlateStock]) -> Option<BarbellLoad> {
+    if !target_kg.is_finite() || !bar_kg.is_finite() || target_kg <= 0.0 || bar_kg < 0.0 {
+        return None;
+    }
+    if plates.iter().map(|p| p.pairs as usize).sum::<usize>() > MAX_CELLS {
+        return None;
+    }
+    // One entry per plate for a side, heaviest first.
+    let mut items: Vec<u64> = plates
+        .iter()
+        .filter(|p| p.weight_kg.is_finite() && p.weight_kg > 0.0)
+        .flat_map(|p| std::iter::repeat_n(grams(p.weight_kg), p.pairs as usize))
+        .filter(|&g| g > 0)
+        .collect();
+    items.sort_unstable_by(|a, b| b.cmp(a));
+
+    let side_target = ((target_kg - bar_kg) / 2.0).max(0.0);
+    let step = items.iter().fold(0, |acc, &g| gcd(acc, g));
+    if items.is_empty() || side_target == 0.0 {
+        return Some(BarbellLoad { total_kg: bar_kg, per_side: Vec::new() });
+    }
+
+    // Sums past the target by more than the heaviest plate are never the
+    // nearest: dropping a plate gets closer and still clears the target.
+    let target_units = grams(side_target) as f64 / step as f64;
+    let total_units: u64 = items.iter().map(|&g| g / step).sum();
+    let limit = total_units.min((target_units.ceil() as u64).saturating_add(items[0] / step)) as usize;
+    // Odd plate weights shrink the step, and lots of plates with a big
+    // target stretch the sum; either can make the table too big to build.
+    if limit > MAX_STEPS || items.len().saturating_mul(limit + 1) > MAX_CELLS {
+        return None;
+    }
+
+    // 0/1 knapsack, filled from the lightest plate up: `fewest[i][s]` is the
+    // fewest of plates i.. that make sum s, NONE if they can't.
+    const NONE: u32 = u32::MAX;
+    let mut fewest = vec![vec![NONE; limit + 1]; items.len() + 1];
+    fewest[items.len()][0] = 0;
+    for i in (0..items.len()).rev() {
+        let w = (items[i] / step) as usize;
+        let (this, rest) = fewest.split_at_mut(i + 1);
+        let (row, next) = (&mut this[i], &rest[0]);
+        for s in 0..=limit {
+            row[s] = next[s];
+            if s >= w && next[s - w] != NONE {
+                row[s] = row[s].min(next[s - w] + 1);
+            }
+        }
+    }
+
+    let nearest = (0..=limit)
+        .filter(|&s| fewest[0][s] != NONE)
+        .min_by(|&a, &b| {
+            let da = (a as f64 - target_units).abs();
+            let db = (b as f64 - target_units).abs();
+            da.total_cmp(&db).then(a.cmp(&b))
+        })?;
+
+    // Walk heaviest first, taking a plate whenever the rest can still finish
+    // the sum in the fewest plates, so 25 + 15 beats 20 + 20.
+    let mut per_side = Vec::new();
+    let mut s = nearest;
+    for (i, &g) in items.iter().enumerate() {
+        let w = (g / step) as usize;
+        if s >= w && fewest[i + 1][s - w] != NONE && fewest[i + 1][s - w] + 1 == fewest[i][s] {
+            per_side.push(g as f64 / 1000.0);
+            s -= w;
+        }
+    }
+
+    let side_grams = nearest as u64 * step;
+    let total_kg = (grams(bar_kg) + 2 * side_grams) as f64 / 1000.0;
+    Some(BarbellLoad { total_kg, per_side })
+}
+
+/// The available dumbbell nearest `target_kg`; a tie goes to the lighter
+/// one. None for a non-positive target or no dumbbells.
+pub fn nearest_dumbbell(target_kg: f64, dumbbells_kg: &[f64]) -> Option<f64> {
+    if !target_kg.is_finite() || target_kg <= 0.0 {
+        return None;
+    }
+    dumbbells_kg
+        .iter()
+        .copied()
+        .filter(|w| w.is_finite() && *w > 0.0)
+        .min_by(|a, b| (a - target_kg).abs().total_cmp(&(b - target_kg).abs()).then(a.total_cmp(b)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stock(pairs: &[(f64, u32)]) -> Vec<PlateStock> {
+        pairs.iter().map(|&(weight_kg, pairs)| PlateStock { weight_kg, pairs }).collect()
+    }
+
+    fn gym() -> Vec<PlateStock> {
+        stock(&[(25.0, 4), (20.0, 2), (15.0, 2), (10.0, 2), (5.0, 2), (2.5, 2), (1.25, 2)])
+    }
+
+    #[test]
+    fn exact_loading_uses_fewest_plates() {
+        let load = load_barbell(102.5, 20.0, &gym()).unwrap();
+        assert_eq!(load.total_kg, 102.5);
+        assert_eq!(load.per_side, vec![25.0, 15.0, 1.25]);
+
+        let load = load_barbell(140.0, 20.0, &gym()).unwrap();
+        assert_eq!(load.per_side, vec![25.0, 25.0, 10.0]);
+    }
+
+    #[test]
+    fn unreachable_target_rounds_to_nearest() {
+        // 101 kg needs 40.5 a side; 40.0 is closer than 41.25.
+        let load = load_barbell(101.0, 20.0, &gym()).unwrap();
+        assert_eq!(load.total_kg, 100.0);
+        assert_eq!(load.per_side, vec![25.0, 15.0]);
+
+        // Halfway between 100 and 102.5 goes to the lighter.
+        assert_eq!(load_barbell(101.25, 20.0, &gym()).unwrap().total_kg, 100.0);
+    }
+
+    #[test]
+    fn limited_stock_is_respected() {
+        // One pair of 20s and one of 5s: 20 + 5 is the most a side takes.
+        let plates = stock(&[(20.0, 1), (5.0, 1)]);
+        let load = load_barbell(100.0, 20.0, &plates).unwrap();
+        assert_eq!(load.total_kg, 70.0);
+        assert_eq!(load.per_side, vec![20.0, 5.0]);
+        // 15 a side would take a 10 and a 5; of what's there, 20 is nearest.
+        assert_eq!(load_barbell(50.0, 20.0, &plates).unwrap().per_side, vec![20.0]);
+    }
+
+    #[test]
+    fn greedy_would_miss() {
+        // 30 a side: greedy takes 25 and can't finish; 15 + 15 is exact.
+        let plates = stock(&[(25.0, 1), (15.0, 2)]);
+        let load = load_barbell(80.0, 20.0, &plates).unwrap();
+        assert_eq!(load.total_kg, 80.0);
+        assert_eq!(load.per_side, vec![15.0, 15.0]);
+    }
+
+    #[test]
+    fn fractional_plates_sum_exactly() {
+        let plates = stock(&[(1.25, 3), (0.5, 2)]);
+        let load = load_barbell(28.5, 20.0, &plates).unwrap();
+        assert_eq!(load.total_kg, 28.5);
+        assert_eq!(load.per_side, vec![1.25, 1.25, 1.25, 0.5]);
+    }
+
+    #[test]
+    fn light_targets_give_the_bar() {
+        assert_eq!(load_barbell(15.0, 20.0, &gym()).unwrap(), BarbellLoad { total_kg: 20.0, per_side: vec![] });
+        assert_eq!(load_barbell(60.0, 20.0, &[]).unwrap().total_kg, 20.0);
+    }
+
+    #[test]
+    fn invalid_barbell_inputs() {
+        assert_eq!(load_barbell(0.0, 20.0, &gym()), None);
+        assert_eq!(load_barbell(f64::NAN, 20.0, &gym()), None);
+        assert_eq!(load_barbell(100.0, -1.0, &gym()), None);
+    }
+
+    #[test]
+    fn oversized_tables_are_refused() {
+        // A 1 g plate makes the step 1 g: 90 kg a side is 90 000 steps.
+        let plates = stock(&[(50.0, 20), (0.001, 1)]);
+        assert_eq!(load_barbell(200.0, 20.0, &plates), None);
+        // Fine plates, but a full kit and a huge logged set.
+        let plates: Vec<PlateStock> =
+            (1..=16).map(|i| PlateStock { weight_kg: 50.0 - 0.25 * i as f64, pairs: 20 }).collect();
+        assert_eq!(load_barbell(30_000.0, 20.0, &plates), None);
+        assert_eq!(load_barbell(100.0, 20.0, &[PlateStock { weight_kg: 20.0, pairs: u32::MAX }]), None);
+        // The same kit still loads an ordinary target.
+        assert_eq!(load_barbell(211.5, 20.0, &plates).unwrap().per_side, vec![49.75, 46.0]);
+    }
+
+    #[test]
+    fn plate_weights_are_whole_units() {
+        for w in [0.25, 0.5, 1.25, 2.5, 20.0, 50.0] {
+            assert!(is_plate_weight(w), "{}", w);
+        }
+        for w in [0.0, -1.25, 0.001, 0.1, 1.1, f64::NAN, f64::INFINITY] {
+            assert!(!is_plate_weight(w), "{}", w);
+        }
+    }
+
+    #[test]
+    fn dumbbells_round_to_nearest() {
+        let rack = [10.0, 12.5, 15.0, 17.5, 20.0, 22.5];
+        assert_eq!(nearest_dumbbell(16.0, &rack), Some(15.0));
+        assert_eq!(nearest_dumbbell(16.5, &rack), Some(17.5));
+        assert_eq!(nearest_dumbbell(13.75, &rack), Some(12.5));
+        assert_eq!(nearest_dumbbell(40.0, &rack), Some(22.5));
+        assert_eq!(nearest_dumbbell(4.0, &rack), Some(10.0));
+        assert_eq!(nearest_dumbbell(16.0, &[]), None);
+        assert_eq!(nearest_dumbbell(0.0, &rack), None);
+    }
+}
@@ -0,0 +1,224 @@
+//! Relative-strength scores: a lifted load scaled by bodyweight so lifters,
+//! or one lifter across a bulk or cut, compare on one scale.
+//!
+//! Wilks (2020 revision) and DOTS are one curve per sex. IPF GL also depends
+//! on equipment and on the event, a full powerlifting total or bench press
+//! alone. Coefficients are the published ones, as OpenPowerlifting uses them.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+/// Raw is "classic" in IPF terms: no supportive suits or shirts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Equipment {
+    #[default]
+    Raw,
+    Equipped,
+}
+
+/// What the load is: a squat + bench + deadlift total, or a bench press alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    #[default]
+    Total,
+    Bench,
+}
+
+impl Sex {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Sex::Male => "male",
+            Sex::Female => "female",
+        }
+    }
+}
+
+impl FromStr for Sex {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "male" => Ok(Sex::Male),
+            "female" => Ok(Sex::Female),
+            _ => Err(format!("unknown sex: '{}'", s)),
+        }
+    }
+}
+
+impl Equipment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Equipment::Raw => "raw",
+            Equipment::Equipped => "equipped",
+        }
+    }
+}
+
+impl FromStr for Equipment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(Equipment::Raw),
+            "equipped" => Ok(Equipment::Equipped),
+            _ => Err(format!("unknown equipment: '{}'", s)),
+        }
+    }
+}
+
+fn valid(bodyweight_kg: f64, load_kg: f64) -> bool {
+    bodyweight_kg.is_finite() && load_kg.is_finite() && bodyweight_kg > 0.0 && load_kg > 0.0
+}
+
+fn polynomial(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+const WILKS2020_MALE: [f64; 6] = [
+    47.46178854, 8.472061379, 0.07369410346, -0.001395833811, 7.07665973070743e-06, -1.20804336482315e-08,
+];
+const WILKS2020_FEMALE: [f64; 6] = [
+    -125.4255398, 13.71219419, -0.03307250631, -0.001050400051, 9.38773881462799e-06, -2.3334613884954e-08,
+];
+
+/// Wilks score, 2020 revision: load × 600 / a fifth-degree polynomial in
+/// bodyweight. Bodyweight is clamped to 40–200.95 kg for men and 40–150.95 kg
+/// for women, the range the curve was fitted on. None for a non-positive
+/// bodyweight or load.
+pub fn wilks2020(sex: Sex, bodyweight_kg: f64, load_kg: f64) -> Option<f64> {
+    if !valid(bodyweight_kg, load_kg) {
+        return None;
+    }
+    let (coefficients, max_bw) = match sex {
+        Sex::Male => (&WILKS2020_MALE, 200.95),
+        Sex::Female => (&WILKS2020_FEMALE, 150.95),
+    };
+    Some(load_kg * 600.0 / polynomial(coefficients, bodyweight_kg.clamp(40.0, max_bw)))
+}
+
+const DOTS_MALE: [f64; 5] = [-307.75076, 24.0900756, -0.1918759221, 0.0007391293, -0.000001093];
+const DOTS_FEMALE: [f64; 5] = [-57.96288, 13.6175032, -0.1126655495, 0.0005158568, -0.0000010706];
+
+/// DOTS score: load × 500 / a fourth-degree polynomial in bodyweight, clamped
+/// to 40–210 kg for men and 40–150 kg for women. None for a non-positive
+/// bodyweight or load.
+pub fn dots(sex: Sex, bodyweight_kg: f64, load_kg: f64) -> Option<f64> {
+    if !valid(bodyweight_kg, load_kg) {
+        return None;
+    }
+    let (coefficients, max_bw) = match sex {
+        Sex::Male => (&DOTS_MALE, 210.0),
+        Sex::Female => (&DOTS_FEMALE, 150.0),
+    };
+    Some(load_kg * 500.0 / polynomial(coefficients, bodyweight_kg.clamp(40.0, max_bw)))
+}
+
+/// IPF GL (A, B, C) parameters.
+fn ipf_gl_parameters(sex: Sex, equipment: Equipment, event: Event) -> (f64, f64, f64) {
+    match (sex, equipment, event) {
+        (Sex::Male, Equipment::Raw, Event::Total) => (1199.72839, 1025.18162, 0.00921),
+        (Sex::Male, Equipment::Equipped, Event::Total) => (1236.25115, 1449.21864, 0.01644),
+        (Sex::Male, Equipment::Raw, Event::Bench) => (320.98041, 281.40258, 0.01008),
+        (Sex::Male, Equipment::Equipped, Event::Bench) => (381.22073, 733.79378, 0.02398),
+        (Sex::Female, Equipment::Raw, Event::Total) => (610.32796, 1045.59282, 0.03048),
+        (Sex::Female, Equipment::Equipped, Event::Total) => (758.63878, 949.31382, 0.02435),
+        (Sex::Female, Equipment::Raw, Event::Bench) => (142.40398, 442.52671, 0.04724),
+        (Sex::Female, Equipment::Equipped, Event::Bench) => (221.82209, 357.00377, 0.02937),
+    }
+}
+
+/// IPF GL points: load × 100 / (A − B·e^(−C·bodyweight)), with A, B and C
+/// set by sex, equipment and event. None for a non-positive load or a
+/// bodyweight under 35 kg, below which the curve isn't defined.
+pub fn ipf_gl(sex: Sex, equipment: Equipment, event: Event, bodyweight_kg: f64, load_kg: f64) -> Option<f64> {
+    if !valid(bodyweight_kg, load_kg) || bodyweight_kg < 35.0 {
+        return None;
+    }
+    let (a, b, c) = ipf_gl_parameters(sex, equipment, event);
+    Some(load_kg * 100.0 / (a - b * (-c * bodyweight_kg).exp()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("score");
+        assert!((actual - expected).abs() < 0.01, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn wilks2020_reference() {
+        close(wilks2020(Sex::Male, 90.0, 700.0), 536.90);
+        close(wilks2020(Sex::Female, 60.0, 400.0), 527.62);
+    }
+
+    #[test]
+    fn dots_reference() {
+        close(dots(Sex::Male, 90.0, 700.0), 452.62);
+        close(dots(Sex::Female, 60.0, 400.0), 443.42);
+    }
+
+    #[test]
+    fn ipf_gl_reference() {
+        close(ipf_gl(Sex::Male, Equipment::Raw, Event::Total, 90.0, 700.0), 93.06);
+        close(ipf_gl(Sex::Female, Equipment::Raw, Event::Total, 60.0, 400.0), 90.42);
+        close(ipf_gl(Sex::Male, Equipment::Raw, Event::Bench, 90.0, 160.0), 77.15);
+    }
+
+    #[test]
+    fn equipment_only_moves_ipf_gl() {
+        let raw = ipf_gl(Sex::Male, Equipment::Raw, Event::Total, 90.0, 700.0).unwrap();
+        let equipped = ipf_gl(Sex::Male, Equipment::Equipped, Event::Total, 90.0, 700.0).unwrap();
+        assert!(equipped < raw, "the same total is worth less in equipment");
+    }
+
+    #[test]
+    fn bodyweight_is_clamped() {
+        assert_eq!(wilks2020(Sex::Male, 250.0, 900.0), wilks2020(Sex::Male, 200.95, 900.0));
+        assert_eq!(wilks2020(Sex::Female, 30.0, 200.0), wilks2020(Sex::Female, 40.0, 200.0));
+        assert_eq!(dots(Sex::Male, 230.0, 900.0), dots(Sex::Male, 210.0, 900.0));
+        assert_eq!(dots(Sex::Female, 170.0, 400.0), dots(Sex::Female, 150.0, 400.0));
+    }
+
+    #[test]
+    fn heavier_lifter_same_total_scores_lower() {
+        for score in [
+            |bw| wilks2020(Sex::Male, bw, 600.0),
+            |bw| dots(Sex::Male, bw, 600.0),
+            |bw| ipf_gl(Sex::Male, Equipment::Raw, Event::Total, bw, 600.0),
+        ] {
+            assert!(score(75.0).unwrap() > score(105.0).unwrap());
+        }
+    }
+
+    #[test]
+    fn names_round_trip() {
+        for sex in [Sex::Male, Sex::Female] {
+            assert_eq!(sex.as_str().parse::<Sex>(), Ok(sex));
+        }
+        for equipment in [Equipment::Raw, Equipment::Equipped] {
+            assert_eq!(equipment.as_str().parse::<Equipment>(), Ok(equipment));
+        }
+        assert!("Male".parse::<Sex>().is_err());
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        assert_eq!(wilks2020(Sex::Male, 0.0, 500.0), None);
+        assert_eq!(dots(Sex::Female, 60.0, 0.0), None);
+        assert_eq!(dots(Sex::Female, f64::NAN, 300.0), None);
+        assert_eq!(ipf_gl(Sex::Male, Equipment::Raw, Event::Total, 34.9, 300.0), None);
+        assert_eq!(ipf_gl(Sex::Male, Equipment::Raw, Event::Total, 90.0, -1.0), None);
+    }
+}
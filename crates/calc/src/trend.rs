@@ -1,6 +1,6 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Trend {
     Up,
@@ -21,7 +21,8 @@ impl Trend {
 /// Filter out deload sessions from an e1RM series (ordered most recent first).
 /// A session is considered a deload if its e1RM is <85% of the series max,
 /// indicating an intentional light day rather than genuine regression.
-/// Returns at most 4 values after filtering.
+/// Returns at most 4 values after filtering. The analytics summary uses
+/// [`filter_outliers`] instead.
 pub fn filter_deloads(e1rms: &[f64]) -> Vec<f64> {
     if e1rms.len() < 3 {
         return e1rms.to_vec();
@@ -42,7 +43,8 @@ pub fn filter_deloads(e1rms: &[f64]) -> Vec<f64> {
 
 /// Compute trend direction from session e1RMs (ordered most recent first).
 /// Requires at least 3 data points. Compares avg of last 2 vs avg of prior sessions.
-/// Returns Up (>2%), Down (<-2%), or Flat.
+/// Returns Up (>2%), Down (<-2%), or Flat. The analytics summary uses
+/// [`fit_trend`] instead.
 pub fn compute_trend(e1rms: &[f64]) -> Option<Trend> {
     if e1rms.len() < 3 {
         return None;
@@ -65,6 +67,141 @@ pub fn compute_trend(e1rms: &[f64]) -> Option<Trend> {
     })
 }
 
+/// One session's best e1RM on a day axis. `day` is any day count with a
+/// fixed origin (e.g. a Julian day number); only differences matter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub day: f64,
+    pub e1rm: f64,
+}
+
+/// A fitted e1RM trend. Slopes are kg of e1RM per week; `slope_low` and
+/// `slope_high` bound the 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrendFit {
+    pub slope_per_week: f64,
+    pub slope_low: f64,
+    pub slope_high: f64,
+    pub direction: Trend,
+}
+
+/// Modified z-score above which a point is an outlier (Iglewicz & Hoaglin).
+const OUTLIER_Z: f64 = 3.5;
+
+/// Age in days at which a point carries half the weight of the latest one.
+const HALF_LIFE_DAYS: f64 = 28.0;
+
+/// Two-sided 95% Student's t critical values for 1..=30 degrees of freedom.
+const T_975: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Drop outlying sessions (deloads, mis-logged weights) by median absolute
+/// deviation: a point goes if its modified z-score, 0.6745 × |x − median| / MAD,
+/// exceeds 3.5. Scales with the lifter's own session-to-session noise instead
+/// of a fixed percentage. Order is preserved. Series under 3 points, series
+/// with no spread (MAD of 0), and filters that would leave fewer than 3 points
+/// come back unchanged.
+pub fn filter_outliers(points: &[TrendPoint]) -> Vec<TrendPoint> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let values: Vec<f64> = points.iter().map(|p| p.e1rm).collect();
+    let med = median(&values);
+    let deviations: Vec<f64> = values.iter().map(|v| (v - med).abs()).collect();
+    let mad = median(&deviations);
+    if mad == 0.0 {
+        return points.to_vec();
+    }
+
+    let kept: Vec<TrendPoint> = points
+        .iter()
+        .copied()
+        .filter(|p| 0.6745 * (p.e1rm - med).abs() / mad <= OUTLIER_Z)
+        .collect();
+    if kept.len() < 3 {
+        points.to_vec()
+    } else {
+        kept
+    }
+}
+
+/// Fit a time-weighted least-squares line through session e1RMs (any order).
+/// Each point is weighted by 0.5^(age / 28 days), age counted back from the
+/// latest point, so recent sessions dominate without older ones vanishing.
+///
+/// The direction comes from the 95% confidence interval of the slope: Up or
+/// Down only when the whole interval is on one side of zero, otherwise Flat.
+/// Returns None with fewer than 3 points or when every point falls on one day.
+pub fn fit_trend(points: &[TrendPoint]) -> Option<TrendFit> {
+    let n = points.len();
+    if n < 3 {
+        return None;
+    }
+    let latest = points.iter().map(|p| p.day).fold(f64::NEG_INFINITY, f64::max);
+    // Weeks before the latest point, so the slope comes out per week.
+    let xs: Vec<f64> = points.iter().map(|p| (p.day - latest) / 7.0).collect();
+    let raw: Vec<f64> = points.iter().map(|p| 0.5f64.powf((latest - p.day) / HALF_LIFE_DAYS)).collect();
+    // Normalise the weights to sum to n, so residual variance keeps n − 2
+    // degrees of freedom.
+    let total: f64 = raw.iter().sum();
+    let ws: Vec<f64> = raw.iter().map(|w| w * n as f64 / total).collect();
+
+    let mean_x = ws.iter().zip(&xs).map(|(w, x)| w * x).sum::<f64>() / n as f64;
+    let mean_y = ws.iter().zip(points).map(|(w, p)| w * p.e1rm).sum::<f64>() / n as f64;
+    let mut sxx = 0.0;
+    let mut sxy = 0.0;
+    for ((w, x), p) in ws.iter().zip(&xs).zip(points) {
+        sxx += w * (x - mean_x) * (x - mean_x);
+        sxy += w * (x - mean_x) * (p.e1rm - mean_y);
+    }
+    if sxx <= 0.0 {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let sse: f64 = ws
+        .iter()
+        .zip(&xs)
+        .zip(points)
+        .map(|((w, x), p)| {
+            let r = p.e1rm - (intercept + slope * x);
+            w * r * r
+        })
+        .sum();
+    let df = n - 2;
+    let std_err = (sse / df as f64 / sxx).sqrt();
+    let t = T_975.get(df - 1).copied().unwrap_or(1.960);
+    let (low, high) = (slope - t * std_err, slope + t * std_err);
+
+    Some(TrendFit {
+        slope_per_week: slope,
+        slope_low: low,
+        slope_high: high,
+        direction: if low > 0.0 {
+            Trend::Up
+        } else if high < 0.0 {
+            Trend::Down
+        } else {
+            Trend::Flat
+        },
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,4 +430,112 @@ mod tests {
         let result = filter_deloads(&[100.0, 50.0, 90.0]);
         assert_eq!(result, vec![100.0, 50.0, 90.0]);
     }
+
+    // ---------------------------------------------------------------
+    // filter_outliers
+    // ---------------------------------------------------------------
+
+    fn pts(values: &[(f64, f64)]) -> Vec<TrendPoint> {
+        values.iter().map(|&(day, e1rm)| TrendPoint { day, e1rm }).collect()
+    }
+
+    #[test]
+    fn outliers_drop_deload() {
+        // median 100, MAD 1 → 80 scores 0.6745 × 20 = 13.5
+        let input = pts(&[(0.0, 99.0), (7.0, 100.0), (14.0, 80.0), (21.0, 101.0), (28.0, 100.0)]);
+        let result = filter_outliers(&input);
+        assert_eq!(result, pts(&[(0.0, 99.0), (7.0, 100.0), (21.0, 101.0), (28.0, 100.0)]));
+    }
+
+    #[test]
+    fn outliers_scale_with_noise() {
+        // A noisy lifter: 85 is within their usual spread, unlike a fixed 85%
+        // cut it is kept.
+        let input = pts(&[(0.0, 92.0), (7.0, 100.0), (14.0, 85.0), (21.0, 104.0), (28.0, 96.0)]);
+        assert_eq!(filter_outliers(&input), input);
+    }
+
+    #[test]
+    fn outliers_drop_high_mislog() {
+        let input = pts(&[(0.0, 100.0), (7.0, 101.0), (14.0, 1010.0), (21.0, 102.0)]);
+        let result = filter_outliers(&input);
+        assert_eq!(result, pts(&[(0.0, 100.0), (7.0, 101.0), (21.0, 102.0)]));
+    }
+
+    #[test]
+    fn outliers_no_spread_keeps_all() {
+        let input = pts(&[(0.0, 100.0), (7.0, 100.0), (14.0, 100.0), (21.0, 105.0)]);
+        assert_eq!(filter_outliers(&input), input);
+    }
+
+    #[test]
+    fn outliers_short_series_unchanged() {
+        let input = pts(&[(0.0, 100.0), (7.0, 50.0)]);
+        assert_eq!(filter_outliers(&input), input);
+    }
+
+    // ---------------------------------------------------------------
+    // fit_trend
+    // ---------------------------------------------------------------
+
+    #[test]
+    fn fit_perfect_line() {
+        // +1 kg every 3.5 days = +2 kg/week, no residual → zero-width interval
+        let fit = fit_trend(&pts(&[(0.0, 100.0), (3.5, 101.0), (7.0, 102.0), (10.5, 103.0)])).unwrap();
+        assert!((fit.slope_per_week - 2.0).abs() < 1e-9);
+        assert!((fit.slope_low - 2.0).abs() < 1e-9);
+        assert!((fit.slope_high - 2.0).abs() < 1e-9);
+        assert_eq!(fit.direction, Trend::Up);
+    }
+
+    #[test]
+    fn fit_flat_series() {
+        let fit = fit_trend(&pts(&[(0.0, 100.0), (7.0, 100.0), (14.0, 100.0)])).unwrap();
+        assert_eq!(fit.slope_per_week, 0.0);
+        assert_eq!(fit.direction, Trend::Flat);
+    }
+
+    #[test]
+    fn fit_noise_is_flat() {
+        // Slope is positive but the interval straddles zero.
+        let fit = fit_trend(&pts(&[(0.0, 100.0), (7.0, 104.0), (14.0, 98.0), (21.0, 103.0), (28.0, 101.0)])).unwrap();
+        assert!(fit.slope_low < 0.0 && fit.slope_high > 0.0);
+        assert_eq!(fit.direction, Trend::Flat);
+    }
+
+    #[test]
+    fn fit_steady_decline_is_down() {
+        let fit = fit_trend(&pts(&[(0.0, 110.0), (7.0, 108.5), (14.0, 106.0), (21.0, 105.0), (28.0, 102.5)])).unwrap();
+        assert!(fit.slope_per_week < 0.0);
+        assert!(fit.slope_high < 0.0);
+        assert_eq!(fit.direction, Trend::Down);
+    }
+
+    #[test]
+    fn fit_order_independent() {
+        let forward = fit_trend(&pts(&[(0.0, 100.0), (7.0, 103.0), (14.0, 104.0), (21.0, 108.0)])).unwrap();
+        let backward = fit_trend(&pts(&[(21.0, 108.0), (14.0, 104.0), (7.0, 103.0), (0.0, 100.0)])).unwrap();
+        assert!((forward.slope_per_week - backward.slope_per_week).abs() < 1e-9);
+        assert!((forward.slope_low - backward.slope_low).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fit_recent_points_weigh_more() {
+        // Old sessions fell, recent ones climb. Unweighted, the slope is
+        // −0.18 kg/week; weighting by recency tips it positive.
+        let input = pts(&[(0.0, 110.0), (14.0, 104.0), (28.0, 100.0), (98.0, 100.0), (105.0, 103.0), (112.0, 106.0)]);
+        let fit = fit_trend(&input).unwrap();
+        assert!(fit.slope_per_week > 0.0, "slope {}", fit.slope_per_week);
+    }
+
+    #[test]
+    fn fit_insufficient_data() {
+        assert_eq!(fit_trend(&pts(&[(0.0, 100.0), (7.0, 105.0)])), None);
+        assert_eq!(fit_trend(&[]), None);
+    }
+
+    #[test]
+    fn fit_single_day() {
+        assert_eq!(fit_trend(&pts(&[(5.0, 100.0), (5.0, 102.0), (5.0, 101.0)])), None);
+    }
 }
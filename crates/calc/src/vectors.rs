@@ -21,6 +21,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::relative_strength::{Equipment, Event, Sex};
+use crate::trend::TrendFit;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct E1rmCase {
     pub weight_kg: f64,
@@ -56,6 +59,43 @@ pub struct PctChangeCase {
     pub expected: Option<f64>,
 }
 
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct LoadForCase {
+    pub e1rm: f64,
+    pub reps: i64,
+    pub rir: i64,
+    /// None = outside 1..=30 reps to failure, RIR outside 0..=5, or e1rm <= 0
+    pub expected: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct OutlierCase {
+    /// (day, e1rm) pairs
+    pub points: Vec<(f64, f64)>,
+    pub expected: Vec<(f64, f64)>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TrendFitCase {
+    /// (day, e1rm) pairs
+    pub points: Vec<(f64, f64)>,
+    /// None = fewer than 3 points, or all on one day
+    pub expected: Option<TrendFit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RelativeStrengthCase {
+    pub sex: Sex,
+    pub equipment: Equipment,
+    pub event: Event,
+    pub bodyweight_kg: f64,
+    pub load_kg: f64,
+    /// Wilks and DOTS ignore equipment and event
+    pub wilks2020: Option<f64>,
+    pub dots: Option<f64>,
+    pub ipf_gl: Option<f64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Vectors {
     pub policy: String,
@@ -65,11 +105,18 @@ pub struct Vectors {
     pub reps_to_beat: Vec<RepsToBeatCase>,
     pub round: Vec<RoundCase>,
     pub pct_change: Vec<PctChangeCase>,
+    /// rir 0 is the plain rep max
+    pub load_for: Vec<LoadForCase>,
+    pub outliers: Vec<OutlierCase>,
+    pub trend_fit: Vec<TrendFitCase>,
+    pub relative_strength: Vec<RelativeStrengthCase>,
 }
 
 /// Build the canonical vector set from the live implementation.
 pub fn generate() -> Vectors {
-    use crate::e1rm::{best, e1rm, pct_change, reps_to_beat, round, SetData};
+    use crate::e1rm::{best, e1rm, load_for, pct_change, reps_to_beat, round, SetData};
+    use crate::relative_strength::{dots, ipf_gl, wilks2020};
+    use crate::trend::{filter_outliers, fit_trend, TrendPoint};
 
     // -- e1rm: valid-domain grid + named regression cases ------------------
     let mut e1rm_cases: Vec<E1rmCase> = Vec::new();
@@ -154,14 +201,101 @@ pub fn generate() -> Vectors {
         .map(|&(c, p)| PctChangeCase { current: c, previous: p, expected: pct_change(c, p) })
         .collect();
 
+    // -- load_for: rep-max table rows, RPE chart rows, domain edges ------------
+    let mut load_inputs: Vec<(f64, i64, i64)> = Vec::new();
+    for reps in 1..=15 {
+        load_inputs.push((120.0, reps, 0)); // the rep-max table
+    }
+    load_inputs.extend([
+        (e1rm(100.0, 5), 5, 0),   // round trip → 100
+        (e1rm(65.0, 11), 11, 0),  // grinder round trip → 65
+        (140.0, 8, 2),            // 8 @ RPE 8 = 10RM
+        (140.0, 5, 1),
+        (140.0, 3, 5),            // RPE 5, the lightest prescribed
+        (140.0, 27, 3),           // exactly 30 to failure
+        (140.0, 28, 3),           // 31 → None
+        (140.0, 5, 6),            // RIR over 5 → None
+        (140.0, 5, -1),           // negative RIR → None
+        (140.0, 0, 0),            // zero reps → None
+        (0.0, 5, 0),              // no e1RM → None
+    ]);
+    let load_for_cases = load_inputs
+        .iter()
+        .map(|&(x, reps, rir)| LoadForCase { e1rm: x, reps, rir, expected: load_for(x, reps, rir) })
+        .collect();
+
+    // -- trend: MAD outlier filter, then weighted least-squares fit ------------
+    let to_points = |pairs: &[(f64, f64)]| -> Vec<TrendPoint> {
+        pairs.iter().map(|&(day, e1rm)| TrendPoint { day, e1rm }).collect()
+    };
+    let series: Vec<Vec<(f64, f64)>> = vec![
+        vec![(0.0, 99.0), (7.0, 100.0), (14.0, 80.0), (21.0, 101.0), (28.0, 100.0)], // deload dropped
+        vec![(0.0, 92.0), (7.0, 100.0), (14.0, 85.0), (21.0, 104.0), (28.0, 96.0)], // noisy, all kept
+        vec![(0.0, 100.0), (7.0, 101.0), (14.0, 1010.0), (21.0, 102.0)],            // mis-log dropped
+        vec![(0.0, 100.0), (7.0, 100.0), (14.0, 100.0), (21.0, 105.0)],             // MAD 0 → unchanged
+        vec![(0.0, 100.0), (3.5, 101.0), (7.0, 102.0), (10.5, 103.0)],              // perfect +2/week
+        vec![(0.0, 100.0), (7.0, 104.0), (14.0, 98.0), (21.0, 103.0), (28.0, 101.0)], // flat within noise
+        vec![(0.0, 110.0), (7.0, 108.5), (14.0, 106.0), (21.0, 105.0), (28.0, 102.5)], // down
+        vec![(0.0, 110.0), (14.0, 104.0), (28.0, 100.0), (98.0, 100.0), (105.0, 103.0), (112.0, 106.0)], // recency weighting
+        vec![(2461000.0, 140.0), (2461004.0, 141.5), (2461011.0, 141.0), (2461014.0, 143.5)], // Julian days
+        vec![(5.0, 100.0), (5.0, 102.0), (5.0, 101.0)],                             // one day → None
+        vec![(0.0, 100.0), (7.0, 50.0)],                                            // too short
+    ];
+    let outlier_cases = series
+        .iter()
+        .map(|pairs| OutlierCase {
+            points: pairs.clone(),
+            expected: filter_outliers(&to_points(pairs)).iter().map(|p| (p.day, p.e1rm)).collect(),
+        })
+        .collect();
+    let trend_fit_cases = series
+        .iter()
+        .map(|pairs| TrendFitCase { points: pairs.clone(), expected: fit_trend(&to_points(pairs)) })
+        .collect();
+
+    // -- relative strength: every sex/equipment/event combination, plus clamps -
+    let mut relative_strength_cases = Vec::new();
+    let mut relative_inputs: Vec<(Sex, Equipment, Event, f64, f64)> = Vec::new();
+    for sex in [Sex::Male, Sex::Female] {
+        for equipment in [Equipment::Raw, Equipment::Equipped] {
+            relative_inputs.push((sex, equipment, Event::Total, 90.0, 700.0));
+            relative_inputs.push((sex, equipment, Event::Total, 60.0, 400.0));
+            relative_inputs.push((sex, equipment, Event::Bench, 82.5, 150.0));
+        }
+    }
+    relative_inputs.extend([
+        (Sex::Male, Equipment::Raw, Event::Total, 230.0, 1000.0),  // above both male clamps
+        (Sex::Female, Equipment::Raw, Event::Total, 160.0, 450.0), // above both female clamps
+        (Sex::Female, Equipment::Raw, Event::Total, 38.0, 200.0),  // below the 40 kg clamp, GL still defined
+        (Sex::Male, Equipment::Raw, Event::Total, 34.0, 200.0),    // GL undefined under 35 kg → null
+        (Sex::Male, Equipment::Raw, Event::Total, 90.0, 0.0),      // no load → all null
+        (Sex::Female, Equipment::Raw, Event::Bench, 0.0, 80.0),    // no bodyweight → all null
+    ]);
+    for (sex, equipment, event, bw, load) in relative_inputs {
+        relative_strength_cases.push(RelativeStrengthCase {
+            sex,
+            equipment,
+            event,
+            bodyweight_kg: bw,
+            load_kg: load,
+            wilks2020: wilks2020(sex, bw, load),
+            dots: dots(sex, bw, load),
+            ipf_gl: ipf_gl(sex, equipment, event, bw, load),
+        });
+    }
+
     Vectors {
-        policy: "e1rm = weight_kg * (1 + reps/30); RAW REPS ONLY — RIR is context, never folded into PR/nudge/target math (decision 2026-07-13). reps_to_beat: smallest reps at weight whose e1RM STRICTLY beats target; null when invalid or > 30 reps.".to_string(),
+        policy: "e1rm = weight_kg * (1 + reps/30); RAW REPS ONLY — RIR is context, never folded into PR/nudge/target math (decision 2026-07-13). reps_to_beat: smallest reps at weight whose e1RM STRICTLY beats target; null when invalid or > 30 reps. load_for: inverse Epley at reps + rir, e1rm / (1 + (reps + rir)/30); null outside 1..=30 total reps, rir 0..=5, or e1rm <= 0. trend: drop points whose MAD modified z-score exceeds 3.5, then fit least squares weighted 0.5^(age/28 days); slope in kg/week with a 95% t interval, direction up/down only when the interval excludes zero. relative_strength: Wilks 2020 and DOTS clamp bodyweight to the fitted range; IPF GL is null under 35 kg bodyweight; all scores null for non-positive bodyweight or load.".to_string(),
         tolerance: 1e-9,
         e1rm: e1rm_cases,
         best: best_cases,
         reps_to_beat: reps_to_beat_cases,
         round: round_cases,
         pct_change: pct_change_cases,
+        load_for: load_for_cases,
+        outliers: outlier_cases,
+        trend_fit: trend_fit_cases,
+        relative_strength: relative_strength_cases,
     }
 }
 
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,6 +65,31 @@ impl Client {
     }
 }
 
+/// Human-readable error for a non-2xx response. Prefers the server's structured
+/// `{"error": {"code", "message"}}` body; falls back to status + raw text.
+pub async fn error_message(resp: reqwest::Response) -> String {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    let body: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+    match (body["error"]["message"].as_str(), body["error"]["code"].as_str()) {
+        // Unparseable input: serde's reason says which field and why.
+        (Some(message), Some(code)) => match body["error"]["details"]["reason"].as_str() {
+            Some(reason) => format!("{}: {} ({})", message, reason, code),
+            None => format!("{} ({})", message, code),
+        },
+        _ => format!("{} - {}", status, text),
+    }
+}
+
+/// Decode a JSON response, surfacing the server's error message on non-2xx
+/// instead of an opaque "error decoding response body".
+pub async fn parse_json<T: DeserializeOwned>(resp: reqwest::Response) -> Result<T, String> {
+    if !resp.status().is_success() {
+        return Err(error_message(resp).await);
+    }
+    resp.json().await.map_err(|e| format!("Parse error: {}", e))
+}
+
 fn config_path() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -1,6 +1,6 @@
 use clap::Subcommand;
 
-use crate::client::Client;
+use crate::client::{Client, error_message, parse_json};
 
 #[derive(Subcommand)]
 pub enum AnalyticsCommands {
@@ -37,6 +37,15 @@ pub enum AnalyticsCommands {
         #[arg(long, help = "End date (YYYY-MM-DD)")]
         until: Option<String>,
     },
+    /// Estimated 1–15 rep maxes from an exercise's current e1RM
+    RepMaxes {
+        #[arg(long)]
+        exercise_id: Option<i64>,
+        #[arg(long, help = "Exercise name (fuzzy match)")]
+        exercise: Option<String>,
+        #[arg(long, default_value = "0", help = "Reps in reserve to prescribe at (0-5)")]
+        rir: i64,
+    },
     /// Biggest e1RM gainers/losers
     Movers {
         #[arg(long, default_value = "30")]
@@ -67,6 +76,11 @@ pub enum AnalyticsCommands {
         #[arg(long, default_value = "30")]
         days: i64,
     },
+    /// Stalled lifts with a suggested deload, rep-range change or variation swap
+    Plateaus {
+        #[arg(long, default_value = "4", help = "Sessions without a new best before a lift counts as stalled")]
+        exposures: i64,
+    },
 }
 
 pub async fn handle(client: &Client, cmd: AnalyticsCommands) -> Result<(), String> {
@@ -110,7 +124,7 @@ pub async fn handle(client: &Client, cmd: AnalyticsCommands) -> Result<(), Strin
             let resp = client.http.get(client.url("/exercises"))
                 .header("Authorization", &auth)
                 .send().await.map_err(|e| format!("Request failed: {}", e))?;
-            let exercises: Vec<serde_json::Value> = resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+            let exercises: Vec<serde_json::Value> = parse_json(resp).await?;
 
             println!("Watched exercises:");
             for id in &ids {
@@ -134,6 +148,10 @@ pub async fn handle(client: &Client, cmd: AnalyticsCommands) -> Result<(), Strin
             let params = date_params(since, until);
             (client.url(&format!("/analytics/e1rm/{}", id)), params)
         }
+        AnalyticsCommands::RepMaxes { exercise_id, exercise, rir } => {
+            let id = resolve_exercise(client, &auth, *exercise_id, exercise.as_deref()).await?;
+            (client.url(&format!("/analytics/rep-maxes/{}", id)), vec![("rir", rir.to_string())])
+        }
         AnalyticsCommands::Movers { days } => {
             (client.url("/analytics/e1rm-movers"), vec![("days", days.to_string())])
         }
@@ -151,6 +169,9 @@ pub async fn handle(client: &Client, cmd: AnalyticsCommands) -> Result<(), Strin
         AnalyticsCommands::Stale { days } => {
             (client.url("/analytics/stale-exercises"), vec![("days", days.to_string())])
         }
+        AnalyticsCommands::Plateaus { exposures } => {
+            (client.url("/analytics/plateaus"), vec![("exposures", exposures.to_string())])
+        }
         // Watch/Unwatch/Watchlist handled above
         _ => unreachable!(),
     };
@@ -165,15 +186,10 @@ pub async fn handle(client: &Client, cmd: AnalyticsCommands) -> Result<(), Strin
         .map_err(|e| format!("Request failed: {}", e))?;
 
     if !resp.status().is_success() {
-        let status = resp.status();
-        let text = resp.text().await.unwrap_or_default();
-        return Err(format!("Failed: {} - {}", status, text));
+        return Err(format!("Failed: {}", error_message(resp).await));
     }
 
-    let data: serde_json::Value = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let data: serde_json::Value = parse_json(resp).await?;
 
     println!(
         "{}",
@@ -236,10 +252,7 @@ async fn lookup_exercise_by_name(client: &Client, auth: &str, query: &str) -> Re
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
-    let exercises: Vec<serde_json::Value> = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let exercises: Vec<serde_json::Value> = parse_json(resp).await?;
 
     let query_tokens: Vec<String> = query.to_lowercase().split_whitespace().map(String::from).collect();
     let matches: Vec<&serde_json::Value> = exercises
@@ -281,10 +294,7 @@ async fn lookup_exercise_name(client: &Client, auth: &str, id: i64) -> Result<St
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
 
-    let exercises: Vec<serde_json::Value> = resp
-        .json()
-        .await
-        .map_err(|e| format!("Parse error: {}", e))?;
+    let exercises: Vec<serde_json::Value> = parse_json(resp).await?;
 
     exercises.iter()
         .find(|e| e["id"].as_i64() == Some(id))
@@ -308,7 +318,7 @@ async fn get_watched_ids(client: &Client, auth: &str) -> Result<Vec<i64>, String
         return Ok(vec![]);
     }
 
-    let body: serde_json::Value = resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+    let body: serde_json::Value = parse_json(resp).await?;
     let value_str = body["value"].as_str().unwrap_or("[]");
     serde_json::from_str(value_str).map_err(|e| format!("Parse error: {}", e))
 }
@@ -328,6 +338,6 @@ async fn set_watched_ids(client: &Client, auth: &str, ids: &[i64]) -> Result<(),
     if resp.status().is_success() {
         Ok(())
     } else {
-        Err(format!("Failed to update watchlist: {}", resp.status()))
+        Err(format!("Failed to update watchlist: {}", error_message(resp).await))
     }
 }
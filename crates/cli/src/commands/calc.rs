@@ -0,0 +1,260 @@
+use clap::{Args, Subcommand};
+
+use lightweight_calc::e1rm;
+use lightweight_calc::plates::{self, PlateStock};
+
+/// A full metric set: what `lw calc plates` assumes without `--plates`.
+const STANDARD_PLATES: &str = "25x4,20x2,15x2,10x2,5x2,2.5x2,1.25x2";
+
+/// Offline calculators: these run against the calc crate directly and need
+/// no server or login.
+#[derive(Subcommand)]
+pub enum CalcCommands {
+    /// Estimated 1RM from a set
+    E1rm {
+        #[arg(long)]
+        weight: f64,
+        #[arg(long)]
+        reps: i64,
+    },
+    /// Estimated 1–15 rep maxes, or loads at a given RIR
+    RepMaxes {
+        #[command(flatten)]
+        from: E1rmSource,
+        #[arg(long, default_value = "0", help = "Reps in reserve to prescribe at (0-5)")]
+        rir: i64,
+        #[command(flatten)]
+        kit: Kit,
+    },
+    /// Load for a rep target at a given RIR, or a percentage of e1RM
+    Load {
+        #[command(flatten)]
+        from: E1rmSource,
+        #[arg(long, required_unless_present = "percent")]
+        reps: Option<i64>,
+        #[arg(long, default_value = "0", help = "Reps in reserve (0-5)")]
+        rir: i64,
+        #[arg(long, conflicts_with = "rir", help = "Percentage of e1RM")]
+        percent: Option<f64>,
+        #[command(flatten)]
+        kit: Kit,
+    },
+    /// Plates for each side of the bar, or the nearest weight they can make
+    Plates {
+        #[arg(long, help = "Target weight (kg)")]
+        target: f64,
+        #[arg(long, default_value = "20", help = "Bar weight (kg)")]
+        bar: f64,
+        #[arg(long, default_value = STANDARD_PLATES, help = "Plates on hand as WEIGHTxPAIRS, comma-separated")]
+        plates: String,
+    },
+    /// The nearest dumbbell to a target weight
+    Dumbbell {
+        #[arg(long, help = "Target weight per dumbbell (kg)")]
+        target: f64,
+        #[arg(long, value_delimiter = ',', required = true, help = "Dumbbells on hand (kg), comma-separated")]
+        dumbbells: Vec<f64>,
+    },
+}
+
+/// Equipment to round prescribed loads to; without it they stay as the
+/// formula gives them.
+#[derive(Args)]
+pub struct Kit {
+    #[arg(long, default_value = "20", help = "Bar weight (kg), with --plates")]
+    bar: f64,
+    #[arg(long, help = "Round to a bar loaded from these plates: WEIGHTxPAIRS, comma-separated, e.g. 20x4,10x1,2.5x2")]
+    plates: Option<String>,
+    #[arg(long, value_delimiter = ',', conflicts_with = "plates", help = "Round to these dumbbells (kg), comma-separated")]
+    dumbbells: Vec<f64>,
+}
+
+impl Kit {
+    /// The load to use for `target_kg` and, on a bar, the plates per side.
+    fn round(&self, target_kg: f64) -> Result<(f64, Option<Vec<f64>>), String> {
+        if let Some(list) = &self.plates {
+            let load = plates::load_barbell(target_kg, self.bar, &parse_plates(list)?)
+                .ok_or_else(|| format!("Can't load a {} kg bar to {} kg with those plates", self.bar, e1rm::round(target_kg)))?;
+            return Ok((load.total_kg, Some(load.per_side)));
+        }
+        if !self.dumbbells.is_empty() {
+            let weight = plates::nearest_dumbbell(target_kg, &self.dumbbells).ok_or("No usable dumbbells")?;
+            return Ok((weight, None));
+        }
+        Ok((target_kg, None))
+    }
+}
+
+fn parse_plates(list: &str) -> Result<Vec<PlateStock>, String> {
+    list.split(',')
+        .map(|entry| {
+            let invalid = || format!("Invalid plates '{}': expected WEIGHTxPAIRS, e.g. 20x4", entry.trim());
+            let (weight, pairs) = entry.split_once(['x', 'X']).ok_or_else(invalid)?;
+            let weight_kg: f64 = weight.trim().parse().map_err(|_| invalid())?;
+            let pairs: u32 = pairs.trim().parse().map_err(|_| invalid())?;
+            if !plates::is_plate_weight(weight_kg) {
+                return Err(format!(
+                    "Invalid plates '{}': weights must be multiples of {} kg",
+                    entry.trim(),
+                    plates::PLATE_UNIT_KG
+                ));
+            }
+            Ok(PlateStock { weight_kg, pairs })
+        })
+        .collect()
+}
+
+fn format_plates(per_side: &[f64]) -> String {
+    if per_side.is_empty() {
+        "empty bar".into()
+    } else {
+        per_side.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(" + ")
+    }
+}
+
+/// The e1RM to work from: given directly, or estimated from a set.
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct E1rmSource {
+    #[arg(long, help = "Known e1RM (kg)")]
+    e1rm: Option<f64>,
+    #[arg(long, help = "A set as WEIGHTxREPS, e.g. 100x5")]
+    set: Option<String>,
+}
+
+impl E1rmSource {
+    fn resolve(&self) -> Result<f64, String> {
+        let value = match (&self.e1rm, &self.set) {
+            (Some(e1rm), _) => *e1rm,
+            (None, Some(set)) => {
+                let (weight, reps) = parse_set(set)?;
+                e1rm::e1rm(weight, reps)
+            }
+            (None, None) => return Err("Provide --e1rm or --set".into()),
+        };
+        if value.is_finite() && value > 0.0 {
+            Ok(value)
+        } else {
+            Err(format!("e1RM must be positive, got {}", value))
+        }
+    }
+}
+
+fn parse_set(set: &str) -> Result<(f64, i64), String> {
+    let invalid = || format!("Invalid set '{}': expected WEIGHTxREPS, e.g. 100x5", set);
+    let (weight, reps) = set.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let weight: f64 = weight.trim().parse().map_err(|_| invalid())?;
+    let reps: i64 = reps.trim().parse().map_err(|_| invalid())?;
+    if weight <= 0.0 || reps < 1 {
+        return Err(invalid());
+    }
+    Ok((weight, reps))
+}
+
+fn check_rir(rir: i64) -> Result<(), String> {
+    if (0..=e1rm::MAX_RIR).contains(&rir) {
+        Ok(())
+    } else {
+        Err(format!("--rir must be between 0 and {}, got {}", e1rm::MAX_RIR, rir))
+    }
+}
+
+pub fn handle(cmd: CalcCommands) -> Result<(), String> {
+    match cmd {
+        CalcCommands::E1rm { weight, reps } => {
+            if weight <= 0.0 || reps < 1 {
+                return Err("--weight and --reps must be positive".into());
+            }
+            println!("e1RM: {} kg", e1rm::round(e1rm::e1rm(weight, reps)));
+        }
+        CalcCommands::RepMaxes { from, rir, kit } => {
+            check_rir(rir)?;
+            let base = from.resolve()?;
+            println!("e1RM: {} kg{}", e1rm::round(base), rir_label(rir));
+            println!("{:>4}  {:>8}  {:>6}", "Reps", "kg", "%1RM");
+            for (reps, estimate) in e1rm::rep_max_table(base, rir) {
+                let (load, per_side) = kit.round(estimate)?;
+                let plates = per_side.map(|p| format!("  per side: {}", format_plates(&p))).unwrap_or_default();
+                println!("{:>4}  {:>8.1}  {:>5.1}%{}", reps, e1rm::round(load), load / base * 100.0, plates);
+            }
+        }
+        CalcCommands::Plates { target, bar, plates } => {
+            let load = plates::load_barbell(target, bar, &parse_plates(&plates)?)
+                .ok_or("--target must be positive and --bar not negative, with no more plates than can be searched")?;
+            if (load.total_kg - target).abs() >= 0.001 {
+                println!("{} kg can't be made; nearest is {} kg", target, load.total_kg);
+            }
+            println!("{} kg: {} per side", load.total_kg, format_plates(&load.per_side));
+        }
+        CalcCommands::Dumbbell { target, dumbbells } => {
+            let weight = plates::nearest_dumbbell(target, &dumbbells)
+                .ok_or("--target must be positive, with at least one usable dumbbell")?;
+            println!("{} kg", weight);
+        }
+        CalcCommands::Load { from, reps, rir, percent, kit } => {
+            let base = from.resolve()?;
+            match (percent, reps) {
+                (Some(percent), _) => {
+                    if !(percent > 0.0 && percent <= 100.0) {
+                        return Err(format!("--percent must be between 0 and 100, got {}", percent));
+                    }
+                    let load = base * percent / 100.0;
+                    println!("{}% of {} kg: {} kg", percent, e1rm::round(base), e1rm::round(load));
+                    print_rounded(&kit, load)?;
+                    // Invert e1rm() for the reps that load is good for; the
+                    // epsilon keeps 75% from landing on 9.999… reps.
+                    let max_reps = (30.0 * (100.0 / percent - 1.0) + 1e-9).floor() as i64;
+                    match reps {
+                        Some(reps) if reps > max_reps => {
+                            println!("{} reps is past the estimated max of {} at that load", reps, max_reps)
+                        }
+                        Some(reps) if max_reps - reps > e1rm::MAX_RIR => {
+                            println!("{} reps leaves more than {} in reserve", reps, e1rm::MAX_RIR)
+                        }
+                        Some(reps) => {
+                            let rir = max_reps - reps;
+                            println!("{} reps leaves about {} in reserve (RPE {})", reps, rir, 10 - rir)
+                        }
+                        None if max_reps >= 1 => println!("Estimated max reps at that load: {}", max_reps),
+                        None => {}
+                    }
+                }
+                (None, Some(reps)) => {
+                    check_rir(rir)?;
+                    let load = e1rm::load_for(base, reps, rir)
+                        .ok_or_else(|| format!("No estimate for {} reps at {} RIR", reps, rir))?;
+                    println!(
+                        "{} reps{} from e1RM {} kg: {} kg ({:.1}%)",
+                        reps,
+                        rir_label(rir),
+                        e1rm::round(base),
+                        e1rm::round(load),
+                        load / base * 100.0
+                    );
+                    print_rounded(&kit, load)?;
+                }
+                (None, None) => return Err("Provide --reps or --percent".into()),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The achievable load for `load` when a kit is given and it differs.
+fn print_rounded(kit: &Kit, load: f64) -> Result<(), String> {
+    let (rounded, per_side) = kit.round(load)?;
+    match per_side {
+        Some(p) => println!("Load: {} kg ({} per side)", rounded, format_plates(&p)),
+        None if (rounded - load).abs() >= 0.001 => println!("Nearest dumbbell: {} kg", rounded),
+        None => {}
+    }
+    Ok(())
+}
+
+fn rir_label(rir: i64) -> String {
+    if rir == 0 {
+        String::new()
+    } else {
+        format!(" at {} RIR (RPE {})", rir, 10 - rir)
+    }
+}
@@ -1,6 +1,6 @@
 use clap::Subcommand;
 
-use crate::client::Client;
+use crate::client::{Client, error_message, parse_json};
 
 #[derive(Subcommand)]
 pub enum ExerciseCommands {
@@ -30,7 +30,7 @@ pub async fn handle(client: &Client, cmd: ExerciseCommands) -> Result<(), String
                 .map_err(|e| format!("Request failed: {}", e))?;
 
             let exercises: Vec<serde_json::Value> =
-                resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                parse_json(resp).await?;
 
             for ex in exercises {
                 let name = ex["name"].as_str().unwrap_or("?");
@@ -66,7 +66,7 @@ pub async fn handle(client: &Client, cmd: ExerciseCommands) -> Result<(), String
                 println!("Exercise '{}' created.", name);
                 Ok(())
             } else {
-                Err(format!("Failed: {}", resp.status()))
+                Err(format!("Failed: {}", error_message(resp).await))
             }
         }
     }
@@ -1,4 +1,4 @@
-use crate::client::Client;
+use crate::client::{Client, error_message, parse_json};
 
 pub async fn handle(client: &Client, file: &str) -> Result<(), String> {
     let auth = client.auth_header().ok_or("Not logged in")?;
@@ -23,10 +23,7 @@ pub async fn handle(client: &Client, file: &str) -> Result<(), String> {
         .map_err(|e| format!("Request failed: {}", e))?;
 
     if resp.status().is_success() {
-        let result: serde_json::Value = resp
-            .json()
-            .await
-            .map_err(|e| format!("Invalid response: {}", e))?;
+        let result: serde_json::Value = parse_json(resp).await?;
 
         let session_count = result["sessions"].as_array().map(|a| a.len()).unwrap_or(0);
         println!("Imported {} session(s).", session_count);
@@ -48,9 +45,7 @@ pub async fn handle(client: &Client, file: &str) -> Result<(), String> {
             }
         }
     } else {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Import failed ({}): {}", status, body));
+        return Err(format!("Import failed: {}", error_message(resp).await));
     }
 
     Ok(())
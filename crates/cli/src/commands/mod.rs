@@ -1,10 +1,11 @@
 pub mod analytics;
+pub mod calc;
 pub mod exercises;
 pub mod import;
 pub mod sessions;
 pub mod templates;
 
-use crate::client::Client;
+use crate::client::{Client, error_message, parse_json};
 
 pub async fn login(client: &Client) -> Result<(), String> {
     use std::io::Write;
@@ -34,12 +35,12 @@ pub async fn login(client: &Client) -> Result<(), String> {
         .map_err(|e| format!("Request failed: {}", e))?;
 
     if resp.status().is_success() {
-        let body: serde_json::Value = resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+        let body: serde_json::Value = parse_json(resp).await?;
         let token = body["token"].as_str().ok_or("No token in response")?;
         client.save_token(token)?;
         println!("Logged in successfully. Token saved.");
         Ok(())
     } else {
-        Err(format!("Login failed: {}", resp.status()))
+        Err(format!("Login failed: {}", error_message(resp).await))
     }
 }
@@ -1,6 +1,6 @@
 use clap::Subcommand;
 
-use crate::client::Client;
+use crate::client::{Client, error_message, parse_json};
 
 #[derive(Subcommand)]
 pub enum SessionCommands {
@@ -38,11 +38,22 @@ pub enum SessionCommands {
         weight: Option<f64>,
         #[arg(short, long)]
         reps: i32,
-        #[arg(long, help = "Set type (e.g. warmup, working, drop)")]
+        #[arg(
+            long,
+            help = "Set type",
+            value_parser = ["warmup", "working", "drop", "failure", "amrap", "backoff", "cluster", "myorep"]
+        )]
         set_type: Option<String>,
     },
     /// End the active session
     End,
+    /// Post-workout report: PRs, misses, volume, adherence and anomalies
+    Report {
+        #[arg(help = "Session ID (defaults to the most recent session)")]
+        id: Option<i64>,
+        #[arg(long, help = "Print the raw JSON")]
+        json: bool,
+    },
 }
 
 pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String> {
@@ -59,7 +70,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                 .map_err(|e| format!("Request failed: {}", e))?;
 
             let sessions: Vec<serde_json::Value> =
-                resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                parse_json(resp).await?;
 
             for s in sessions {
                 let name = s["template_name"]
@@ -92,7 +103,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                     .map_err(|e| format!("Request failed: {}", e))?;
 
                 let templates: Vec<serde_json::Value> =
-                    resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                    parse_json(resp).await?;
 
                 let t = templates
                     .iter()
@@ -127,14 +138,12 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
 
             if resp.status().is_success() {
                 let session: serde_json::Value =
-                    resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                    parse_json(resp).await?;
                 let status_msg = if completed { "completed" } else { "started" };
                 println!("Workout {} (ID: {})", status_msg, session["id"]);
                 Ok(())
             } else {
-                let status = resp.status();
-                let text = resp.text().await.unwrap_or_default();
-                Err(format!("Failed: {} - {}", status, text))
+                Err(format!("Failed: {}", error_message(resp).await))
             }
         }
         SessionCommands::AddExercise {
@@ -157,7 +166,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                     .map_err(|e| format!("Request failed: {}", e))?;
 
                 let active: serde_json::Value =
-                    resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                    parse_json(resp).await?;
 
                 if active.is_null() {
                     return Err("No active session. Use --session <ID> or start a session first.".to_string());
@@ -180,7 +189,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                     .map_err(|e| format!("Request failed: {}", e))?;
 
                 let exercises: Vec<serde_json::Value> =
-                    resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                    parse_json(resp).await?;
 
                 let ex = exercises
                     .iter()
@@ -216,7 +225,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
 
             if resp.status().is_success() {
                 let se: serde_json::Value =
-                    resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                    parse_json(resp).await?;
                 println!(
                     "Added exercise: {} (session exercise ID: {})",
                     se["exercise_name"].as_str().unwrap_or("?"),
@@ -224,9 +233,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                 );
                 Ok(())
             } else {
-                let status = resp.status();
-                let text = resp.text().await.unwrap_or_default();
-                Err(format!("Failed: {} - {}", status, text))
+                Err(format!("Failed: {}", error_message(resp).await))
             }
         }
         SessionCommands::Log {
@@ -245,7 +252,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                 .map_err(|e| format!("Request failed: {}", e))?;
 
             let session: serde_json::Value =
-                resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                parse_json(resp).await?;
 
             if session.is_null() {
                 return Err("No active session".to_string());
@@ -292,7 +299,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
 
             if resp.status().is_success() {
                 let set: serde_json::Value =
-                    resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                    parse_json(resp).await?;
                 let w = weight.map_or("BW".to_string(), |w| format!("{}kg", w));
                 println!(
                     "Set {} logged: {} × {}",
@@ -300,7 +307,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                 );
                 Ok(())
             } else {
-                Err(format!("Failed: {}", resp.status()))
+                Err(format!("Failed: {}", error_message(resp).await))
             }
         }
         SessionCommands::End => {
@@ -313,7 +320,7 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                 .map_err(|e| format!("Request failed: {}", e))?;
 
             let session: serde_json::Value =
-                resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                parse_json(resp).await?;
 
             if session.is_null() {
                 return Err("No active session".to_string());
@@ -334,8 +341,133 @@ pub async fn handle(client: &Client, cmd: SessionCommands) -> Result<(), String>
                 println!("Workout ended!");
                 Ok(())
             } else {
-                Err(format!("Failed: {}", resp.status()))
+                Err(format!("Failed: {}", error_message(resp).await))
+            }
+        }
+        SessionCommands::Report { id, json } => {
+            let session_id = match id {
+                Some(id) => id,
+                None => {
+                    let resp = client
+                        .http
+                        .get(client.url("/sessions"))
+                        .query(&[("limit", "1")])
+                        .header("Authorization", &auth)
+                        .send()
+                        .await
+                        .map_err(|e| format!("Request failed: {}", e))?;
+                    let sessions: Vec<serde_json::Value> = parse_json(resp).await?;
+                    sessions
+                        .first()
+                        .and_then(|s| s["id"].as_i64())
+                        .ok_or("No sessions yet")?
+                }
+            };
+
+            let resp = client
+                .http
+                .get(client.url(&format!("/sessions/{}/report", session_id)))
+                .header("Authorization", &auth)
+                .send()
+                .await
+                .map_err(|e| format!("Request failed: {}", e))?;
+            let report: serde_json::Value = parse_json(resp).await?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+            } else {
+                print_report(&report);
             }
+            Ok(())
+        }
+    }
+}
+
+fn kg(value: &serde_json::Value) -> String {
+    value.as_f64().map_or("-".to_string(), |v| format!("{}kg", v))
+}
+
+fn print_report(report: &serde_json::Value) {
+    let name = report["template_name"]
+        .as_str()
+        .or(report["name"].as_str())
+        .unwrap_or("Freeform");
+    println!(
+        "{} - {} ({})",
+        name,
+        report["started_at"].as_str().unwrap_or("?"),
+        report["status"].as_str().unwrap_or("?")
+    );
+    print!("{} working sets, {} volume", report["working_sets"], kg(&report["volume_kg"]));
+    if let Some(change) = report["volume_change"].as_object() {
+        let delta = change["delta_kg"].as_f64().unwrap_or(0.0);
+        let pct = change["delta_pct"]
+            .as_f64()
+            .map_or(String::new(), |p| format!(", {:+}%", p));
+        print!(" ({:+}kg vs {}{})", delta, change["previous_started_at"].as_str().unwrap_or("last time"), pct);
+    }
+    println!();
+
+    let prs = report["prs"].as_array().cloned().unwrap_or_default();
+    if !prs.is_empty() {
+        println!("\nPRs:");
+        for pr in &prs {
+            println!(
+                "  {:<25} {} x {}  e1RM {} (was {})",
+                pr["exercise_name"].as_str().unwrap_or("?"),
+                kg(&pr["weight_kg"]),
+                pr["reps"],
+                kg(&pr["e1rm"]),
+                kg(&pr["previous_best"])
+            );
+        }
+    }
+
+    println!("\nExercises:");
+    for ex in report["exercises"].as_array().into_iter().flatten() {
+        let mut line = format!(
+            "  {:<25} {} sets  {:>9}  e1RM {}",
+            ex["exercise_name"].as_str().unwrap_or("?"),
+            ex["working_sets"],
+            kg(&ex["volume_kg"]),
+            kg(&ex["best_e1rm"])
+        );
+        if ex["pr"].as_bool() == Some(true) {
+            line.push_str("  PR");
+        } else if let Some(gap) = ex["e1rm_gap"].as_f64() {
+            line.push_str(&format!("  {}kg short", gap));
+            if let Some(reps) = ex["reps_to_beat"].as_i64() {
+                line.push_str(&format!(", {} reps at {} beats it", reps, kg(&ex["top_weight_kg"])));
+            }
+        }
+        println!("{}", line);
+    }
+
+    if let Some(adherence) = report["adherence"].as_object() {
+        let score = adherence["score"]
+            .as_f64()
+            .map_or("-".to_string(), |s| format!("{}%", s));
+        println!(
+            "\nAdherence: {}  ({}/{} exercises, {}/{} sets, {}/{} sets in rep range)",
+            score,
+            adherence["completed_exercises"],
+            adherence["planned_exercises"],
+            adherence["completed_sets"],
+            adherence["planned_sets"],
+            adherence["sets_in_range"],
+            adherence["sets_with_range"]
+        );
+    }
+
+    let anomalies = report["anomalies"].as_array().cloned().unwrap_or_default();
+    if !anomalies.is_empty() {
+        println!("\nWorth a look:");
+        for anomaly in &anomalies {
+            println!(
+                "  {}: {}",
+                anomaly["exercise_name"].as_str().unwrap_or("?"),
+                anomaly["message"].as_str().unwrap_or("")
+            );
         }
     }
 }
@@ -1,6 +1,6 @@
 use clap::Subcommand;
 
-use crate::client::Client;
+use crate::client::{Client, parse_json};
 
 #[derive(Subcommand)]
 pub enum TemplateCommands {
@@ -24,7 +24,7 @@ pub async fn handle(client: &Client, cmd: TemplateCommands) -> Result<(), String
                 .map_err(|e| format!("Request failed: {}", e))?;
 
             let templates: Vec<serde_json::Value> =
-                resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                parse_json(resp).await?;
 
             for t in templates {
                 let name = t["name"].as_str().unwrap_or("?");
@@ -43,7 +43,7 @@ pub async fn handle(client: &Client, cmd: TemplateCommands) -> Result<(), String
                 .map_err(|e| format!("Request failed: {}", e))?;
 
             let templates: Vec<serde_json::Value> =
-                resp.json().await.map_err(|e| format!("Parse error: {}", e))?;
+                parse_json(resp).await?;
 
             let template = templates
                 .iter()
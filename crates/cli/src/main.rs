@@ -34,6 +34,11 @@ enum Commands {
         #[command(subcommand)]
         command: commands::analytics::AnalyticsCommands,
     },
+    /// Offline calculators: e1RM, rep maxes, loads (no server needed)
+    Calc {
+        #[command(subcommand)]
+        command: commands::calc::CalcCommands,
+    },
     /// Import workout data
     Import {
         #[arg(long)]
@@ -44,6 +49,13 @@ enum Commands {
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    if let Commands::Calc { command } = cli.command {
+        if let Err(e) = commands::calc::handle(command) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
     let client = match client::Client::from_config() {
         Ok(c) => c,
         Err(e) => {
@@ -60,6 +72,7 @@ async fn main() {
         Commands::Templates { command } => commands::templates::handle(&client, command).await,
         Commands::Sessions { command } => commands::sessions::handle(&client, command).await,
         Commands::Import { file } => commands::import::handle(&client, &file).await,
+        Commands::Calc { .. } => unreachable!(),
     };
 
     if let Err(e) = result {
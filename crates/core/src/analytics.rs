@@ -1,36 +1,44 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use std::collections::HashMap;
 
 use lightweight_calc::e1rm;
+use lightweight_calc::plateau;
 use lightweight_calc::pr;
+use lightweight_calc::relative_strength::{self, Equipment, Event, Sex};
 use lightweight_calc::trend;
 
+use crate::bodyweight;
+use crate::calendar::Calendar;
 use crate::db::DbPool;
 use crate::error::AppError;
+use crate::models::SetType;
+use crate::preferences::{self, CompetitionLifts};
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DayActivity {
     pub date: String,
     pub set_count: i64,
 }
 
-/// Returns set counts per day for the last N days, only days with activity.
+/// Returns set counts per local day for the last N days, only days with activity.
 pub fn activity_heatmap(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayActivity>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
     let mut stmt = conn.prepare(
-        "SELECT date(s2.completed_at) as day, COUNT(*) as set_count
+        "SELECT local_date(s2.completed_at, ?3) as day, COUNT(*) as set_count
          FROM sets s2
          JOIN session_exercises se ON se.id = s2.session_exercise_id
          JOIN sessions s ON s.id = se.session_id
          WHERE s.user_id = ?1
-           AND s2.completed_at >= date('now', ?2)
-           AND s2.set_type = 'working'
+           AND local_date(s2.completed_at, ?3) >= ?2
+           AND counts_for_volume(s2.set_type)
          GROUP BY day
          ORDER BY day"
     )?;
 
-    let days_param = format!("-{} days", days);
-    let rows = stmt.query_map(rusqlite::params![user_id, days_param], |row| {
+    let from = days_ago(&calendar, days);
+    let rows = stmt.query_map(rusqlite::params![user_id, from, calendar.tz_name()], |row| {
         Ok(DayActivity {
             date: row.get(0)?,
             set_count: row.get(1)?,
@@ -42,7 +50,7 @@ pub fn activity_heatmap(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayA
     Ok(rows)
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct E1rmDataPoint {
     pub date: String,
     pub e1rm: f64,
@@ -51,14 +59,14 @@ pub struct E1rmDataPoint {
     pub rir: Option<i64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PersonalRecord {
     pub value: f64,
     pub date: String,
     pub detail: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExerciseE1rm {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -68,7 +76,7 @@ pub struct ExerciseE1rm {
     pub all_time_prs: Option<ExercisePRs>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExercisePRs {
     pub best_e1rm: Option<PersonalRecord>,
     pub heaviest_weight: Option<PersonalRecord>,
@@ -100,15 +108,17 @@ pub fn e1rm_progression(
     })?;
 
     // Get all working sets for this exercise across all sessions, best e1rm per session
+    let calendar = Calendar::load(&conn, user_id)?;
     let mut stmt = conn.prepare(
-        "SELECT date(s.started_at) as session_date,
+        "SELECT local_date(s.started_at, ?3) as session_date,
                 st.weight_kg, st.reps, st.rir
          FROM sets st
          JOIN session_exercises se ON se.id = st.session_exercise_id
          JOIN sessions s ON s.id = se.session_id
          WHERE s.user_id = ?1
+           AND s.status = 'completed'
            AND se.exercise_id = ?2
-           AND st.set_type = 'working'
+           AND counts_for_e1rm(st.set_type)
            AND st.weight_kg IS NOT NULL
            AND st.weight_kg > 0
            AND st.reps > 0
@@ -116,7 +126,7 @@ pub fn e1rm_progression(
     )?;
 
     let all_sets: Vec<(String, f64, i64, Option<i64>)> = stmt.query_map(
-        rusqlite::params![user_id, exercise_id],
+        rusqlite::params![user_id, exercise_id, calendar.tz_name()],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
     )?
         .filter_map(|r| r.ok())
@@ -240,7 +250,7 @@ pub fn e1rm_progression(
     })
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct E1rmSpiderPoint {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -259,6 +269,7 @@ pub fn e1rm_spider(
     weeks: i64,
 ) -> Result<Vec<E1rmSpiderPoint>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
 
     let mut results = Vec::new();
 
@@ -275,14 +286,14 @@ pub fn e1rm_spider(
 
         // Get best e1RM in the current span (last N weeks)
         let current_e1rm = best_e1rm_in_range(
-            &conn, user_id, exercise_id,
-            &format!("-{} days", weeks * 7), "0 days",
+            &conn, &calendar, user_id, exercise_id,
+            weeks * 7, 0,
         )?;
 
         // Get best e1RM in the previous span (N to 2N weeks ago)
         let previous_e1rm = best_e1rm_in_range(
-            &conn, user_id, exercise_id,
-            &format!("-{} days", weeks * 7 * 2), &format!("-{} days", weeks * 7),
+            &conn, &calendar, user_id, exercise_id,
+            weeks * 7 * 2, weeks * 7,
         )?;
 
         let pct_change = match (current_e1rm, previous_e1rm) {
@@ -302,12 +313,20 @@ pub fn e1rm_spider(
     Ok(results)
 }
 
+/// Local date `days` before today, as `YYYY-MM-DD`.
+fn days_ago(calendar: &Calendar, days: i64) -> String {
+    (calendar.today() - chrono::Duration::days(days)).to_string()
+}
+
+/// Best e1RM over sessions on local days from `from_days_ago` up to, but not
+/// including, `to_days_ago`.
 fn best_e1rm_in_range(
     conn: &rusqlite::Connection,
+    calendar: &Calendar,
     user_id: i64,
     exercise_id: i64,
-    from_offset: &str,
-    to_offset: &str,
+    from_days_ago: i64,
+    to_days_ago: i64,
 ) -> Result<Option<f64>, AppError> {
     let mut stmt = conn.prepare(
         "SELECT st.weight_kg, st.reps
@@ -315,17 +334,24 @@ fn best_e1rm_in_range(
          JOIN session_exercises se ON se.id = st.session_exercise_id
          JOIN sessions s ON s.id = se.session_id
          WHERE s.user_id = ?1
+           AND s.status = 'completed'
            AND se.exercise_id = ?2
-           AND st.set_type = 'working'
+           AND counts_for_e1rm(st.set_type)
            AND st.weight_kg IS NOT NULL
            AND st.weight_kg > 0
            AND st.reps > 0
-           AND date(s.started_at) >= date('now', ?3)
-           AND date(s.started_at) < date('now', ?4)"
+           AND local_date(s.started_at, ?5) >= ?3
+           AND local_date(s.started_at, ?5) < ?4"
     )?;
 
     let sets: Vec<(f64, i64)> = stmt.query_map(
-        rusqlite::params![user_id, exercise_id, from_offset, to_offset],
+        rusqlite::params![
+            user_id,
+            exercise_id,
+            days_ago(calendar, from_days_ago),
+            days_ago(calendar, to_days_ago),
+            calendar.tz_name()
+        ],
         |row| Ok((row.get(0)?, row.get(1)?)),
     )?
         .filter_map(|r| r.ok())
@@ -339,7 +365,7 @@ fn best_e1rm_in_range(
     Ok(e1rm::best(&set_data))
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct E1rmMover {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -363,7 +389,8 @@ pub fn e1rm_movers(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<E1rmMover
          JOIN sessions s ON s.id = se.session_id
          JOIN sets st ON st.session_exercise_id = se.id
          WHERE s.user_id = ?1
-           AND st.set_type = 'working'
+           AND s.status = 'completed'
+           AND counts_for_e1rm(st.set_type)
            AND st.weight_kg IS NOT NULL
            AND st.weight_kg > 0
            AND st.reps > 0"
@@ -376,15 +403,12 @@ pub fn e1rm_movers(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<E1rmMover
         .filter_map(|r| r.ok())
         .collect();
 
-    let current_from = format!("-{} days", days);
-    let previous_from = format!("-{} days", days * 2);
-    let previous_to = format!("-{} days", days);
-
+    let calendar = Calendar::load(&conn, user_id)?;
     let mut movers = Vec::new();
 
     for (exercise_id, name, muscle_group) in exercises {
-        let current = best_e1rm_in_range(&conn, user_id, exercise_id, &current_from, "0 days")?;
-        let previous = best_e1rm_in_range(&conn, user_id, exercise_id, &previous_from, &previous_to)?;
+        let current = best_e1rm_in_range(&conn, &calendar, user_id, exercise_id, days, 0)?;
+        let previous = best_e1rm_in_range(&conn, &calendar, user_id, exercise_id, days * 2, days)?;
 
         if let (Some(curr), Some(prev)) = (current, previous) {
             if let Some(pct) = e1rm::pct_change(curr, prev) {
@@ -406,7 +430,7 @@ pub fn e1rm_movers(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<E1rmMover
     Ok(movers)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StaleExercise {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -421,17 +445,18 @@ pub struct StaleExercise {
 /// Sorted by most stale first.
 pub fn stale_exercises(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<StaleExercise>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
     let mut stmt = conn.prepare(
         "SELECT e.id, e.name, e.muscle_group,
-                MAX(date(s.started_at)) as last_date,
-                CAST(julianday('now') - julianday(MAX(date(s.started_at))) AS INTEGER) as days_ago,
+                MAX(local_date(s.started_at, ?3)) as last_date,
+                CAST(julianday(?4) - julianday(MAX(local_date(s.started_at, ?3))) AS INTEGER) as days_ago,
                 COUNT(*) as total_sets
          FROM exercises e
          JOIN session_exercises se ON se.exercise_id = e.id
          JOIN sessions s ON s.id = se.session_id
          JOIN sets st ON st.session_exercise_id = se.id
          WHERE s.user_id = ?1
-           AND st.set_type = 'working'
+           AND counts_for_volume(st.set_type)
            AND e.archived = 0
            AND e.id IN (
                SELECT te.exercise_id FROM template_exercises te
@@ -443,7 +468,8 @@ pub fn stale_exercises(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<Stale
          ORDER BY days_ago DESC"
     )?;
 
-    let rows = stmt.query_map(rusqlite::params![user_id, days], |row| {
+    let today = calendar.today().to_string();
+    let rows = stmt.query_map(rusqlite::params![user_id, days, calendar.tz_name(), today], |row| {
         Ok(StaleExercise {
             exercise_id: row.get(0)?,
             exercise_name: row.get(1)?,
@@ -459,7 +485,7 @@ pub fn stale_exercises(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<Stale
     Ok(rows)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DayTemplateActivity {
     pub date: String,
     pub template_id: Option<i64>,
@@ -467,12 +493,13 @@ pub struct DayTemplateActivity {
     pub set_count: i64,
 }
 
-/// Returns set counts per day per template for the last N days.
+/// Returns set counts per local day per template for the last N days.
 /// Each row is a (date, template) combination. Freeform sessions have null template.
 pub fn activity_heatmap_by_template(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayTemplateActivity>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
     let mut stmt = conn.prepare(
-        "SELECT date(s2.completed_at) as day,
+        "SELECT local_date(s2.completed_at, ?3) as day,
                 s.template_id,
                 t.name as template_name,
                 COUNT(*) as set_count
@@ -481,14 +508,14 @@ pub fn activity_heatmap_by_template(db: &DbPool, user_id: i64, days: i64) -> Res
          JOIN sessions s ON s.id = se.session_id
          LEFT JOIN templates t ON t.id = s.template_id
          WHERE s.user_id = ?1
-           AND s2.completed_at >= date('now', ?2)
-           AND s2.set_type = 'working'
+           AND local_date(s2.completed_at, ?3) >= ?2
+           AND counts_for_volume(s2.set_type)
          GROUP BY day, s.template_id
          ORDER BY day, set_count DESC"
     )?;
 
-    let days_param = format!("-{} days", days);
-    let rows = stmt.query_map(rusqlite::params![user_id, days_param], |row| {
+    let from = days_ago(&calendar, days);
+    let rows = stmt.query_map(rusqlite::params![user_id, from, calendar.tz_name()], |row| {
         Ok(DayTemplateActivity {
             date: row.get(0)?,
             template_id: row.get(1)?,
@@ -503,7 +530,7 @@ pub fn activity_heatmap_by_template(db: &DbPool, user_id: i64, days: i64) -> Res
 }
 
 /// Returns list of exercises that the user has actually logged sets for (for the exercise picker).
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExerciseSummary {
     pub id: i64,
     pub name: String,
@@ -521,7 +548,7 @@ pub fn exercises_with_data(db: &DbPool, user_id: i64) -> Result<Vec<ExerciseSumm
          JOIN sessions s ON s.id = se.session_id
          JOIN sets st ON st.session_exercise_id = se.id
          WHERE s.user_id = ?1
-           AND st.set_type = 'working'
+           AND counts_for_volume(st.set_type)
            AND st.weight_kg IS NOT NULL
            AND st.weight_kg > 0
          GROUP BY e.id
@@ -542,7 +569,7 @@ pub fn exercises_with_data(db: &DbPool, user_id: i64) -> Result<Vec<ExerciseSumm
     Ok(rows)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WeeklyVolume {
     pub week: String,
     pub muscle_group: String,
@@ -550,8 +577,8 @@ pub struct WeeklyVolume {
 }
 
 /// Returns working sets per week broken down by muscle group.
-/// Week is the Monday date of that ISO week.
-/// Optional since/until filter to a date range (YYYY-MM-DD).
+/// Week is the first day of the user's local week.
+/// Optional since/until filter to a local date range (YYYY-MM-DD).
 pub fn weekly_volume(
     db: &DbPool,
     user_id: i64,
@@ -559,9 +586,10 @@ pub fn weekly_volume(
     until: Option<&str>,
 ) -> Result<Vec<WeeklyVolume>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
 
     let mut sql = String::from(
-        "SELECT date(st.completed_at, '+1 day', 'weekday 1', '-7 days') as week_start,
+        "SELECT local_week(st.completed_at, ?2, ?3) as week_start,
                 COALESCE(e.muscle_group, 'Other') as mg,
                 COUNT(*) as set_count
          FROM sets st
@@ -569,17 +597,21 @@ pub fn weekly_volume(
          JOIN sessions s ON s.id = se.session_id
          JOIN exercises e ON e.id = se.exercise_id
          WHERE s.user_id = ?1
-           AND st.set_type = 'working'"
+           AND counts_for_volume(st.set_type)"
     );
 
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(user_id)];
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
+        Box::new(user_id),
+        Box::new(calendar.tz_name()),
+        Box::new(calendar.week_start_day()),
+    ];
     if let Some(s) = since {
         params.push(Box::new(s.to_string()));
-        sql.push_str(&format!(" AND date(s.started_at) >= ?{}", params.len()));
+        sql.push_str(&format!(" AND local_date(s.started_at, ?2) >= ?{}", params.len()));
     }
     if let Some(u) = until {
         params.push(Box::new(u.to_string()));
-        sql.push_str(&format!(" AND date(s.started_at) <= ?{}", params.len()));
+        sql.push_str(&format!(" AND local_date(s.started_at, ?2) <= ?{}", params.len()));
     }
     sql.push_str(" GROUP BY week_start, mg ORDER BY week_start, mg");
 
@@ -598,18 +630,19 @@ pub fn weekly_volume(
     Ok(rows)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WeeklyFrequency {
     pub week: String,
     pub session_count: i64,
 }
 
 /// Returns number of completed sessions per week.
-/// Week is the Monday date of that ISO week.
+/// Week is the first day of the user's local week.
 pub fn session_frequency(db: &DbPool, user_id: i64) -> Result<Vec<WeeklyFrequency>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
     let mut stmt = conn.prepare(
-        "SELECT date(s.started_at, '+1 day', 'weekday 1', '-7 days') as week_start,
+        "SELECT local_week(s.started_at, ?2, ?3) as week_start,
                 COUNT(*) as session_count
          FROM sessions s
          WHERE s.user_id = ?1
@@ -618,7 +651,8 @@ pub fn session_frequency(db: &DbPool, user_id: i64) -> Result<Vec<WeeklyFrequenc
          ORDER BY week_start"
     )?;
 
-    let rows = stmt.query_map(rusqlite::params![user_id], |row| {
+    let params = rusqlite::params![user_id, calendar.tz_name(), calendar.week_start_day()];
+    let rows = stmt.query_map(params, |row| {
         Ok(WeeklyFrequency {
             week: row.get(0)?,
             session_count: row.get(1)?,
@@ -630,14 +664,14 @@ pub fn session_frequency(db: &DbPool, user_id: i64) -> Result<Vec<WeeklyFrequenc
     Ok(rows)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExercisePRData {
     pub exercise_id: i64,
     pub best_e1rm_ever: Option<f64>,
     pub best_e1rm_by_position: HashMap<i32, f64>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DayPR {
     pub date: String,
     pub has_absolute_pr: bool,
@@ -650,11 +684,12 @@ pub struct DayPR {
 /// Single-pass O(n) over all sets, maintaining running maximums.
 pub fn heatmap_prs(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayPR>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
 
     // Fetch ALL working sets for this user ordered chronologically.
     // We need full history to build running bests, but only report PRs within the date window.
     let mut stmt = conn.prepare(
-        "SELECT date(st.completed_at) as day,
+        "SELECT local_date(st.completed_at, ?2) as day,
                 se.exercise_id,
                 st.set_number,
                 st.weight_kg,
@@ -664,7 +699,7 @@ pub fn heatmap_prs(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayPR>, A
          JOIN sessions s ON s.id = se.session_id
          WHERE s.user_id = ?1
            AND s.status = 'completed'
-           AND st.set_type = 'working'
+           AND counts_for_e1rm(st.set_type)
            AND st.weight_kg IS NOT NULL
            AND st.weight_kg > 0
            AND st.reps > 0
@@ -672,18 +707,14 @@ pub fn heatmap_prs(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayPR>, A
     )?;
 
     let sets: Vec<(String, i64, i32, f64, i64)> = stmt.query_map(
-        rusqlite::params![user_id],
+        rusqlite::params![user_id, calendar.tz_name()],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
     )?
         .filter_map(|r| r.ok())
         .collect();
 
     // Compute the cutoff date for reporting (we still process all sets for running bests)
-    let cutoff: String = conn.query_row(
-        "SELECT date('now', ?1)",
-        rusqlite::params![format!("-{} days", days)],
-        |row| row.get(0),
-    )?;
+    let cutoff = days_ago(&calendar, days);
 
     let timed_sets: Vec<pr::TimedSet> = sets
         .into_iter()
@@ -710,7 +741,7 @@ pub fn heatmap_prs(db: &DbPool, user_id: i64, days: i64) -> Result<Vec<DayPR>, A
     Ok(result)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExerciseWeeklyVolume {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -722,8 +753,8 @@ pub struct ExerciseWeeklyVolume {
 
 /// Returns per-exercise weekly breakdown: sets, total reps, and tonnage (weight × reps).
 /// When exercise_id is Some, filters to that exercise. When None, returns all exercises.
-/// Week is the Monday date of that ISO week.
-/// Optional since/until filter to a date range (YYYY-MM-DD).
+/// Week is the first day of the user's local week.
+/// Optional since/until filter to a local date range (YYYY-MM-DD).
 pub fn exercise_volume(
     db: &DbPool,
     user_id: i64,
@@ -732,10 +763,11 @@ pub fn exercise_volume(
     until: Option<&str>,
 ) -> Result<Vec<ExerciseWeeklyVolume>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
 
     let mut sql = String::from(
         "SELECT se.exercise_id, e.name,
-                date(st.completed_at, '+1 day', 'weekday 1', '-7 days') as week_start,
+                local_week(st.completed_at, ?2, ?3) as week_start,
                 COUNT(*) as sets,
                 SUM(st.reps) as reps,
                 SUM(st.weight_kg * st.reps) as tonnage
@@ -744,24 +776,28 @@ pub fn exercise_volume(
          JOIN sessions s ON s.id = se.session_id
          JOIN exercises e ON e.id = se.exercise_id
          WHERE s.user_id = ?1
-           AND st.set_type = 'working'
+           AND counts_for_volume(st.set_type)
            AND st.weight_kg IS NOT NULL
            AND st.weight_kg > 0
            AND st.reps > 0"
     );
 
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(user_id)];
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![
+        Box::new(user_id),
+        Box::new(calendar.tz_name()),
+        Box::new(calendar.week_start_day()),
+    ];
     if let Some(eid) = exercise_id {
         params.push(Box::new(eid));
         sql.push_str(&format!(" AND se.exercise_id = ?{}", params.len()));
     }
     if let Some(s) = since {
         params.push(Box::new(s.to_string()));
-        sql.push_str(&format!(" AND date(s.started_at) >= ?{}", params.len()));
+        sql.push_str(&format!(" AND local_date(s.started_at, ?2) >= ?{}", params.len()));
     }
     if let Some(u) = until {
         params.push(Box::new(u.to_string()));
-        sql.push_str(&format!(" AND date(s.started_at) <= ?{}", params.len()));
+        sql.push_str(&format!(" AND local_date(s.started_at, ?2) <= ?{}", params.len()));
     }
     sql.push_str(" GROUP BY se.exercise_id, week_start ORDER BY week_start, e.name");
 
@@ -783,7 +819,7 @@ pub fn exercise_volume(
     Ok(rows)
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AnalyticsSummary {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -791,13 +827,22 @@ pub struct AnalyticsSummary {
     pub session_count: i64,
     pub last_trained: Option<String>,
     pub current_e1rm: Option<f64>,
+    /// up, down or flat: up or down only when the slope's 95% confidence
+    /// interval excludes zero
     pub trend: Option<String>,
+    /// Fitted e1RM change in kg per week, recent sessions weighted most
+    pub trend_slope: Option<f64>,
+    /// Lower bound of the slope's 95% confidence interval, kg per week
+    pub trend_slope_low: Option<f64>,
+    /// Upper bound of the slope's 95% confidence interval, kg per week
+    pub trend_slope_high: Option<f64>,
 }
 
 /// One-shot orientation: all exercises with session count, last trained date, current best e1RM,
-/// and trend direction (up/flat/down) based on last 4 sessions.
+/// and an e1RM trend fitted over the last 8 training days with outlying sessions dropped.
 pub fn summary(db: &DbPool, user_id: i64) -> Result<Vec<AnalyticsSummary>, AppError> {
     let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
 
     // Main stats query
     let mut stmt = conn.prepare(
@@ -806,13 +851,14 @@ pub fn summary(db: &DbPool, user_id: i64) -> Result<Vec<AnalyticsSummary>, AppEr
                    e.name as exercise_name,
                    e.muscle_group,
                    COUNT(DISTINCT s.id) as session_count,
-                   MAX(date(s.started_at)) as last_trained
+                   MAX(local_date(s.started_at, ?2)) as last_trained
             FROM exercises e
             JOIN session_exercises se ON se.exercise_id = e.id
             JOIN sessions s ON s.id = se.session_id
             JOIN sets st ON st.session_exercise_id = se.id
             WHERE s.user_id = ?1
-              AND st.set_type = 'working'
+              AND s.status = 'completed'
+              AND counts_for_volume(st.set_type)
               AND st.weight_kg IS NOT NULL
               AND st.weight_kg > 0
               AND st.reps > 0
@@ -825,17 +871,19 @@ pub fn summary(db: &DbPool, user_id: i64) -> Result<Vec<AnalyticsSummary>, AppEr
             JOIN session_exercises se ON se.id = st.session_exercise_id
             JOIN sessions s ON s.id = se.session_id
             WHERE s.user_id = ?1
-              AND st.set_type = 'working'
+              AND s.status = 'completed'
+              AND counts_for_e1rm(st.set_type)
               AND st.weight_kg IS NOT NULL
               AND st.weight_kg > 0
               AND st.reps > 0
-              AND date(s.started_at) = (
-                  SELECT MAX(date(s2.started_at))
+              AND local_date(s.started_at, ?2) = (
+                  SELECT MAX(local_date(s2.started_at, ?2))
                   FROM sessions s2
                   JOIN session_exercises se2 ON se2.session_id = s2.id
                   JOIN sets st2 ON st2.session_exercise_id = se2.id
                   WHERE s2.user_id = ?1 AND se2.exercise_id = se.exercise_id
-                    AND st2.set_type = 'working'
+                    AND s2.status = 'completed'
+                    AND counts_for_e1rm(st2.set_type)
                     AND st2.weight_kg IS NOT NULL
                     AND st2.weight_kg > 0
                     AND st2.reps > 0
@@ -850,7 +898,7 @@ pub fn summary(db: &DbPool, user_id: i64) -> Result<Vec<AnalyticsSummary>, AppEr
         ORDER BY es.session_count DESC, es.exercise_name"
     )?;
 
-    let mut rows: Vec<AnalyticsSummary> = stmt.query_map(rusqlite::params![user_id], |row| {
+    let mut rows: Vec<AnalyticsSummary> = stmt.query_map(rusqlite::params![user_id, calendar.tz_name()], |row| {
         Ok(AnalyticsSummary {
             exercise_id: row.get(0)?,
             exercise_name: row.get(1)?,
@@ -860,73 +908,426 @@ pub fn summary(db: &DbPool, user_id: i64) -> Result<Vec<AnalyticsSummary>, AppEr
             current_e1rm: row.get::<_, Option<f64>>(5)?
                 .map(e1rm::round),
             trend: None,
+            trend_slope: None,
+            trend_slope_low: None,
+            trend_slope_high: None,
         })
     })?
         .filter_map(|r| r.ok())
         .collect();
 
-    // Compute trends: best e1RM per session per exercise, last 8 sessions each
-    // (fetch extra to have enough after filtering deloads)
+    // Compute trends: best e1RM per session per exercise over the last 8
+    // training days, on a day axis in the user's calendar
     let mut trend_stmt = conn.prepare(
         "WITH ranked AS (
             SELECT se.exercise_id,
+                   julianday(local_date(s.started_at, ?2)) as day,
                    MAX(st.weight_kg * (1.0 + st.reps / 30.0)) as best_e1rm,
-                   DENSE_RANK() OVER (PARTITION BY se.exercise_id ORDER BY date(s.started_at) DESC) as rn
+                   DENSE_RANK() OVER (PARTITION BY se.exercise_id ORDER BY local_date(s.started_at, ?2) DESC) as rn
             FROM sets st
             JOIN session_exercises se ON se.id = st.session_exercise_id
             JOIN sessions s ON s.id = se.session_id
             WHERE s.user_id = ?1
-              AND st.set_type = 'working'
+              AND s.status = 'completed'
+              AND counts_for_e1rm(st.set_type)
               AND st.weight_kg IS NOT NULL
               AND st.weight_kg > 0
               AND st.reps > 0
             GROUP BY se.exercise_id, s.id
         )
-        SELECT exercise_id, rn, best_e1rm
+        SELECT exercise_id, day, best_e1rm
         FROM ranked
         WHERE rn <= 8
         ORDER BY exercise_id, rn"
     )?;
 
-    let trend_data: Vec<(i64, i64, f64)> = trend_stmt.query_map(
-        rusqlite::params![user_id],
+    let mut points: HashMap<i64, Vec<trend::TrendPoint>> = HashMap::new();
+    for row in trend_stmt.query_map(
+        rusqlite::params![user_id, calendar.tz_name()],
+        |row| Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?)),
+    )?.filter_map(|r| r.ok()) {
+        let (exercise_id, day, e1rm) = row;
+        points.entry(exercise_id).or_default().push(trend::TrendPoint { day, e1rm });
+    }
+
+    // Drop outlying sessions, fit the weighted trend, merge into summary rows
+    for row in &mut rows {
+        let Some(series) = points.get(&row.exercise_id) else { continue };
+        if let Some(fit) = trend::fit_trend(&trend::filter_outliers(series)) {
+            row.trend = Some(fit.direction.as_str().to_string());
+            row.trend_slope = Some(round2(fit.slope_per_week));
+            row.trend_slope_low = Some(round2(fit.slope_low));
+            row.trend_slope_high = Some(round2(fit.slope_high));
+        }
+    }
+
+    Ok(rows)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepMax {
+    pub reps: i64,
+    /// The load to put on the bar: the estimate rounded to what the user's
+    /// equipment profile can make, or the estimate itself without one
+    pub weight_kg: f64,
+    /// The load the formula gives, before rounding to the equipment
+    pub estimate_kg: f64,
+    /// Plates for each side of the bar, heaviest first, for barbell
+    /// exercises when the profile lists plates
+    pub plates_per_side: Option<Vec<f64>>,
+    /// `weight_kg` as a percentage of the e1RM
+    pub pct_e1rm: f64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RepMaxes {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    /// Best e1RM on the latest day the exercise was trained
+    pub e1rm: f64,
+    /// That day, local date
+    pub date: String,
+    /// Reps in reserve the loads are prescribed at; 0 gives true rep maxes
+    pub rir: i64,
+    pub rep_maxes: Vec<RepMax>,
+}
+
+/// Estimated 1–15 rep maxes, or loads for 1–15 reps at `rir`, from the
+/// exercise's current e1RM (the same one the summary reports), rounded to
+/// loads the user's equipment profile can make. NotFound when the exercise
+/// has no sets that count toward e1RM.
+pub fn rep_maxes(db: &DbPool, user_id: i64, exercise_id: i64, rir: i64) -> Result<RepMaxes, AppError> {
+    if !(0..=e1rm::MAX_RIR).contains(&rir) {
+        return Err(AppError::BadRequest(format!("rir must be between 0 and {}, got {}", e1rm::MAX_RIR, rir)));
+    }
+    let profile = preferences::equipment_profile(db, user_id)?;
+    let conn = db.lock().unwrap();
+    let (exercise_name, equipment): (String, Option<String>) = conn.query_row(
+        "SELECT name, equipment FROM exercises WHERE id = ?1 AND user_id = ?2",
+        rusqlite::params![exercise_id, user_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound,
+        e => AppError::Database(e),
+    })?;
+
+    let calendar = Calendar::load(&conn, user_id)?;
+    // The latest day's qualifying sets; e1RM is worked out by the calc crate.
+    let mut stmt = conn.prepare(
+        "WITH qualifying AS (
+             SELECT local_date(s.started_at, ?3) as day, st.weight_kg, st.reps
+             FROM sets st
+             JOIN session_exercises se ON se.id = st.session_exercise_id
+             JOIN sessions s ON s.id = se.session_id
+             WHERE s.user_id = ?1
+               AND s.status = 'completed'
+               AND se.exercise_id = ?2
+               AND counts_for_e1rm(st.set_type)
+               AND st.weight_kg IS NOT NULL
+               AND st.weight_kg > 0
+               AND st.reps > 0
+         )
+         SELECT day, weight_kg, reps FROM qualifying
+         WHERE day = (SELECT MAX(day) FROM qualifying)"
+    )?;
+    let sets: Vec<(String, f64, i64)> = stmt.query_map(
+        rusqlite::params![user_id, exercise_id, calendar.tz_name()],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
     )?
-        .filter_map(|r| r.ok())
+        .collect::<Result<_, _>>()?;
+    drop(stmt);
+    let date = sets.first().map(|(day, _, _)| day.clone()).ok_or(AppError::NotFound)?;
+    let set_data: Vec<e1rm::SetData> = sets
+        .into_iter()
+        .map(|(_, weight, reps)| e1rm::SetData { weight_kg: weight, reps })
         .collect();
+    let best = e1rm::best(&set_data).ok_or(AppError::NotFound)?;
+    // Rounding to plates can take a while; don't hold the database for it.
+    drop(conn);
 
-    // Group by exercise, filter deloads, compute trend direction
-    let mut trends: HashMap<i64, String> = HashMap::new();
-    let mut current_id: Option<i64> = None;
-    let mut session_e1rms: Vec<f64> = Vec::new();
-
-    for (exercise_id, _rn, e1rm) in &trend_data {
-        if current_id != Some(*exercise_id) {
-            if let Some(id) = current_id {
-                let filtered = trend::filter_deloads(&session_e1rms);
-                if let Some(t) = trend::compute_trend(&filtered) {
-                    trends.insert(id, t.as_str().to_string());
-                }
+    let rep_maxes = e1rm::rep_max_table(best, rir)
+        .into_iter()
+        .map(|(reps, load)| {
+            let achievable = profile.as_ref().and_then(|p| p.achievable(equipment.as_deref(), load));
+            let (weight_kg, plates_per_side) = match achievable {
+                Some(a) => (a.weight_kg, a.plates_per_side),
+                None => (load, None),
+            };
+            RepMax {
+                reps,
+                weight_kg: e1rm::round(weight_kg),
+                estimate_kg: e1rm::round(load),
+                plates_per_side,
+                pct_e1rm: round1(weight_kg / best * 100.0),
             }
-            current_id = Some(*exercise_id);
-            session_e1rms.clear();
+        })
+        .collect();
+
+    Ok(RepMaxes { exercise_id, exercise_name, e1rm: e1rm::round(best), date, rir, rep_maxes })
+}
+
+/// Stall threshold for `plateaus` when the caller doesn't give one.
+pub const DEFAULT_STALL_EXPOSURES: i64 = 4;
+
+/// Lifts not trained for this long are stale, not stalled; `stale_exercises`
+/// covers them.
+const PLATEAU_ACTIVE_DAYS: i64 = 42;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StalledExercise {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub muscle_group: Option<String>,
+    /// The standing best e1RM
+    pub best_e1rm: f64,
+    /// Local date of the session that set the standing best
+    pub best_date: String,
+    pub last_trained: String,
+    /// Sessions since the standing best
+    pub exposures_since_best: i64,
+    /// Days from the standing best to today
+    pub days_stalled: i64,
+    /// Fitted e1RM slope since the standing best, kg per week
+    pub slope_per_week: f64,
+    /// Change in average RPE (10 − RIR) across the stall; null when RIR
+    /// wasn't logged
+    pub effort_change: Option<f64>,
+    /// deload, rep_range or variation_swap
+    pub intervention: String,
+    pub suggestion: String,
+}
+
+fn intervention_suggestion(intervention: plateau::Intervention) -> &'static str {
+    match intervention {
+        plateau::Intervention::Deload => {
+            "Effort is rising or e1RM falling without a new best: take a lighter week at about 60% of the usual sets and load, then build back up."
+        }
+        plateau::Intervention::RepRange => {
+            "Move the rep target for the next block, e.g. from sets of 5 to sets of 8, or the reverse."
+        }
+        plateau::Intervention::VariationSwap => {
+            "Swap to a close variation (grip, stance, tempo or implement) for a block, then come back to this lift."
         }
-        session_e1rms.push(*e1rm);
     }
-    // Final exercise
-    if let Some(id) = current_id {
-        let filtered = trend::filter_deloads(&session_e1rms);
-        if let Some(t) = trend::compute_trend(&filtered) {
-            trends.insert(id, t.as_str().to_string());
+}
+
+/// Exercises trained in the last six weeks whose best e1RM has stood for at
+/// least `stall_after` sessions without the trend climbing, with a suggested
+/// intervention. Longest stalls first.
+pub fn plateaus(db: &DbPool, user_id: i64, stall_after: i64) -> Result<Vec<StalledExercise>, AppError> {
+    if !(2..=20).contains(&stall_after) {
+        return Err(AppError::BadRequest(format!("exposures must be between 2 and 20, got {}", stall_after)));
+    }
+    let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
+    let mut stmt = conn.prepare(
+        "SELECT se.exercise_id, e.name, e.muscle_group,
+                local_date(s.started_at, ?2) as day,
+                julianday(local_date(s.started_at, ?2)),
+                MAX(st.weight_kg * (1.0 + st.reps / 30.0)) as best_e1rm,
+                AVG(10 - st.rir) as effort
+         FROM sets st
+         JOIN session_exercises se ON se.id = st.session_exercise_id
+         JOIN sessions s ON s.id = se.session_id
+         JOIN exercises e ON e.id = se.exercise_id
+         WHERE s.user_id = ?1
+           AND s.status = 'completed'
+           AND e.archived = 0
+           AND counts_for_e1rm(st.set_type)
+           AND st.weight_kg IS NOT NULL
+           AND st.weight_kg > 0
+           AND st.reps > 0
+         GROUP BY se.exercise_id, s.id
+         ORDER BY se.exercise_id, s.started_at, s.id"
+    )?;
+
+    struct Series {
+        name: String,
+        muscle_group: Option<String>,
+        dates: Vec<String>,
+        exposures: Vec<plateau::Exposure>,
+    }
+    let mut by_exercise: Vec<(i64, Series)> = Vec::new();
+    let rows = stmt.query_map(rusqlite::params![user_id, calendar.tz_name()], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, String>(3)?,
+            plateau::Exposure { day: row.get(4)?, best_e1rm: row.get(5)?, effort: row.get(6)? },
+        ))
+    })?;
+    for (exercise_id, name, muscle_group, date, exposure) in rows.filter_map(|r| r.ok()) {
+        if by_exercise.last().map(|(id, _)| *id) != Some(exercise_id) {
+            by_exercise.push((exercise_id, Series { name, muscle_group, dates: Vec::new(), exposures: Vec::new() }));
         }
+        let series = &mut by_exercise.last_mut().unwrap().1;
+        series.dates.push(date);
+        series.exposures.push(exposure);
     }
 
-    // Merge trends into summary rows
-    for row in &mut rows {
-        row.trend = trends.remove(&row.exercise_id);
+    let today = calendar.today();
+    let days_before_today = |date: &str| {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").map_or(0, |d| (today - d).num_days())
+    };
+    let mut stalled: Vec<StalledExercise> = by_exercise
+        .into_iter()
+        .filter_map(|(exercise_id, series)| {
+            let last_trained = series.dates.last()?.clone();
+            if days_before_today(&last_trained) > PLATEAU_ACTIVE_DAYS {
+                return None;
+            }
+            let p = plateau::detect_plateau(&series.exposures, stall_after as usize)?;
+            let best_date = series.dates[p.best_index].clone();
+            Some(StalledExercise {
+                exercise_id,
+                exercise_name: series.name,
+                muscle_group: series.muscle_group,
+                best_e1rm: e1rm::round(series.exposures[p.best_index].best_e1rm),
+                days_stalled: days_before_today(&best_date),
+                best_date,
+                last_trained,
+                exposures_since_best: p.exposures_since_best as i64,
+                slope_per_week: round2(p.slope_per_week),
+                effort_change: p.effort_change.map(round2),
+                intervention: p.intervention.as_str().to_string(),
+                suggestion: intervention_suggestion(p.intervention).to_string(),
+            })
+        })
+        .collect();
+    stalled.sort_by(|a, b| b.days_stalled.cmp(&a.days_stalled).then(a.exercise_name.cmp(&b.exercise_name)));
+
+    Ok(stalled)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StrengthScorePoint {
+    pub date: String,
+    /// The latest bodyweight on or before this date; before the first entry,
+    /// the first entry
+    pub bodyweight_kg: f64,
+    /// Each lift's best e1RM on its latest training day up to this date
+    pub squat_e1rm: Option<f64>,
+    pub bench_e1rm: Option<f64>,
+    pub deadlift_e1rm: Option<f64>,
+    /// Squat + bench + deadlift, once all three have an e1RM
+    pub total: Option<f64>,
+    pub wilks: Option<f64>,
+    pub dots: Option<f64>,
+    pub ipf_gl: Option<f64>,
+    /// IPF GL for the bench press alone
+    pub bench_ipf_gl: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StrengthScores {
+    /// male or female
+    pub sex: String,
+    /// raw or equipped
+    pub equipment: String,
+    pub lifts: CompetitionLifts,
+    pub points: Vec<StrengthScorePoint>,
+}
+
+/// Wilks 2020, DOTS and IPF GL from the competition lifts' e1RMs, one point
+/// per local day any of them was trained. Needs the `sex` and
+/// `competition_lifts` preferences; `equipment` defaults to raw. Empty until
+/// a bodyweight is logged.
+pub fn strength_scores(
+    db: &DbPool,
+    user_id: i64,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<StrengthScores, AppError> {
+    let sex: Sex = preferences::get_preference(db, user_id, preferences::SEX_KEY)?
+        .and_then(|v| v.parse().ok())
+        .ok_or_else(|| AppError::BadRequest("Set the sex preference to male or female for strength scores".into()))?;
+    let equipment: Equipment = preferences::get_preference(db, user_id, preferences::EQUIPMENT_KEY)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or_default();
+    let lifts: CompetitionLifts = preferences::get_preference(db, user_id, preferences::COMPETITION_LIFTS_KEY)?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+    let mapped = [lifts.squat, lifts.bench, lifts.deadlift];
+    if mapped.iter().all(Option::is_none) {
+        return Err(AppError::BadRequest(
+            "Set the competition_lifts preference to the squat, bench and deadlift exercise IDs".into(),
+        ));
     }
+    let bodyweights = bodyweight::list(db, user_id, None, None)?;
 
-    Ok(rows)
+    let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
+    let mut stmt = conn.prepare(
+        "SELECT local_date(s.started_at, ?2) as day, se.exercise_id,
+                MAX(st.weight_kg * (1.0 + st.reps / 30.0))
+         FROM sets st
+         JOIN session_exercises se ON se.id = st.session_exercise_id
+         JOIN sessions s ON s.id = se.session_id
+         WHERE s.user_id = ?1
+           AND s.status = 'completed'
+           AND se.exercise_id IN (?3, ?4, ?5)
+           AND counts_for_e1rm(st.set_type)
+           AND st.weight_kg IS NOT NULL
+           AND st.weight_kg > 0
+           AND st.reps > 0
+         GROUP BY day, se.exercise_id
+         ORDER BY day"
+    )?;
+    let rows: Vec<(String, i64, f64)> = stmt
+        .query_map(
+            rusqlite::params![user_id, calendar.tz_name(), mapped[0], mapped[1], mapped[2]],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut points: Vec<StrengthScorePoint> = Vec::new();
+    // Latest e1RM per lift (squat, bench, deadlift), carried forward by day.
+    let mut current: [Option<f64>; 3] = [None; 3];
+    let mut weigh_in = 0;
+    let mut i = 0;
+    while i < rows.len() && !bodyweights.is_empty() {
+        let day = rows[i].0.clone();
+        while i < rows.len() && rows[i].0 == day {
+            let (_, exercise_id, best) = rows[i];
+            for (slot, id) in current.iter_mut().zip(mapped) {
+                if id == Some(exercise_id) {
+                    *slot = Some(best);
+                }
+            }
+            i += 1;
+        }
+        if since.is_some_and(|s| day.as_str() < s) || until.is_some_and(|u| day.as_str() > u) {
+            continue;
+        }
+        while weigh_in + 1 < bodyweights.len() && bodyweights[weigh_in + 1].measured_on <= day {
+            weigh_in += 1;
+        }
+        let bw = bodyweights[weigh_in].weight_kg;
+        let [squat, bench, deadlift] = current;
+        let total = match current {
+            [Some(s), Some(b), Some(d)] => Some(s + b + d),
+            _ => None,
+        };
+        let score = |f: &dyn Fn(f64) -> Option<f64>| total.and_then(f).map(round2);
+        points.push(StrengthScorePoint {
+            date: day,
+            bodyweight_kg: bw,
+            squat_e1rm: squat.map(e1rm::round),
+            bench_e1rm: bench.map(e1rm::round),
+            deadlift_e1rm: deadlift.map(e1rm::round),
+            total: total.map(e1rm::round),
+            wilks: score(&|t| relative_strength::wilks2020(sex, bw, t)),
+            dots: score(&|t| relative_strength::dots(sex, bw, t)),
+            ipf_gl: score(&|t| relative_strength::ipf_gl(sex, equipment, Event::Total, bw, t)),
+            bench_ipf_gl: bench
+                .and_then(|b| relative_strength::ipf_gl(sex, equipment, Event::Bench, bw, b))
+                .map(round2),
+        });
+    }
+
+    Ok(StrengthScores { sex: sex.as_str().into(), equipment: equipment.as_str().into(), lifts, points })
 }
 
 /// For each exercise in a session, returns the historical best e1RM (absolute)
@@ -963,7 +1364,7 @@ pub fn session_prs(db: &DbPool, user_id: i64, session_id: i64) -> Result<Vec<Exe
                AND se.exercise_id = ?2
                AND s.id != ?3
                AND s.status = 'completed'
-               AND st.set_type = 'working'
+               AND counts_for_e1rm(st.set_type)
                AND st.weight_kg IS NOT NULL
                AND st.weight_kg > 0
                AND st.reps > 0"
@@ -995,9 +1396,116 @@ pub fn session_prs(db: &DbPool, user_id: i64, session_id: i64) -> Result<Vec<Exe
     Ok(results)
 }
 
+/// An e1RM PR set in one session.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionPR {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    /// The session's best working set for the exercise.
+    pub set_id: i64,
+    pub weight_kg: f64,
+    pub reps: i64,
+    pub e1rm: f64,
+    /// Best e1RM from completed sessions started before this one.
+    pub previous_best: f64,
+}
+
+/// Exercises whose best working-set e1RM in the session beats every earlier
+/// completed session. An exercise's first appearance doesn't count.
+pub fn session_prs_hit(db: &DbPool, user_id: i64, session_id: i64) -> Result<Vec<SessionPR>, AppError> {
+    let conn = db.lock().unwrap();
+    Ok(prs_hit(&conn, user_id, session_id)?)
+}
+
+pub(crate) fn prs_hit(conn: &rusqlite::Connection, user_id: i64, session_id: i64) -> rusqlite::Result<Vec<SessionPR>> {
+    let mut stmt = conn.prepare(
+        "SELECT se.exercise_id, e.name, st.id, st.weight_kg, st.reps
+         FROM sets st
+         JOIN session_exercises se ON se.id = st.session_exercise_id
+         JOIN sessions s ON s.id = se.session_id
+         JOIN exercises e ON e.id = se.exercise_id
+         WHERE s.id = ?1 AND s.user_id = ?2
+           AND counts_for_e1rm(st.set_type) AND st.weight_kg > 0 AND st.reps > 0
+         ORDER BY se.position, st.set_number",
+    )?;
+    let sets = stmt
+        .query_map(rusqlite::params![session_id, user_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, f64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Best set per exercise, in session order.
+    let mut best: Vec<SessionPR> = Vec::new();
+    for (exercise_id, exercise_name, set_id, weight_kg, reps) in sets {
+        let value = e1rm::e1rm(weight_kg, reps);
+        match best.iter_mut().find(|b| b.exercise_id == exercise_id) {
+            Some(b) if b.e1rm >= value => {}
+            Some(b) => {
+                b.set_id = set_id;
+                b.weight_kg = weight_kg;
+                b.reps = reps;
+                b.e1rm = value;
+            }
+            None => best.push(SessionPR {
+                exercise_id,
+                exercise_name,
+                set_id,
+                weight_kg,
+                reps,
+                e1rm: value,
+                previous_best: 0.0,
+            }),
+        }
+    }
+
+    let mut prs = Vec::new();
+    for mut pr in best {
+        if let Some(previous_best) = prior_best_e1rm(conn, user_id, pr.exercise_id, session_id)? {
+            if pr.e1rm > previous_best {
+                pr.e1rm = e1rm::round(pr.e1rm);
+                pr.previous_best = e1rm::round(previous_best);
+                prs.push(pr);
+            }
+        }
+    }
+    Ok(prs)
+}
+
+/// Best working-set e1RM for the exercise over completed sessions started
+/// before `session_id`. Unrounded.
+pub(crate) fn prior_best_e1rm(
+    conn: &rusqlite::Connection,
+    user_id: i64,
+    exercise_id: i64,
+    session_id: i64,
+) -> rusqlite::Result<Option<f64>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT st.weight_kg, st.reps
+         FROM sets st
+         JOIN session_exercises se ON se.id = st.session_exercise_id
+         JOIN sessions s ON s.id = se.session_id
+         WHERE s.user_id = ?1 AND se.exercise_id = ?2 AND s.id != ?3
+           AND s.status = 'completed'
+           AND julianday(s.started_at) < (SELECT julianday(started_at) FROM sessions WHERE id = ?3)
+           AND counts_for_e1rm(st.set_type) AND st.weight_kg > 0 AND st.reps > 0",
+    )?;
+    let previous: Vec<e1rm::SetData> = stmt
+        .query_map(rusqlite::params![user_id, exercise_id, session_id], |row| {
+            Ok(e1rm::SetData { weight_kg: row.get(0)?, reps: row.get(1)? })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(e1rm::best(&previous))
+}
+
 // ── Report ──
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Report {
     pub watched: Vec<WatchedExercise>,
     pub all_exercises: Vec<AnalyticsSummary>,
@@ -1005,7 +1513,7 @@ pub struct Report {
     pub frequency: Vec<WeeklyFrequency>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WatchedExercise {
     pub exercise_id: i64,
     pub exercise_name: String,
@@ -1059,3 +1567,622 @@ pub fn report(db: &DbPool, user_id: i64, watched_ids: &[i64]) -> Result<Report,
         frequency,
     })
 }
+
+// ── Rest intervals ──
+
+/// Gaps longer than this are treated as interruptions (phone call, machine
+/// queue, forgotten log) rather than rest and are left out.
+pub const MAX_REST_SECONDS: f64 = 1200.0;
+
+/// Upper bounds (exclusive) of the rest-vs-performance buckets; the last
+/// bucket is open-ended.
+const REST_BUCKETS: [i64; 4] = [60, 120, 180, 300];
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExerciseRest {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    /// Number of measured inter-set gaps.
+    pub intervals: i64,
+    pub avg_rest_seconds: f64,
+    /// Average template `rest_seconds` over the gaps that had one.
+    pub prescribed_rest_seconds: Option<f64>,
+    /// Average of actual minus prescribed rest; positive means resting longer
+    /// than the template asks.
+    pub avg_deviation_seconds: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionRest {
+    pub session_id: i64,
+    pub started_at: String,
+    pub template_id: Option<i64>,
+    pub exercises: Vec<ExerciseRest>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestAnalytics {
+    /// Newest session first.
+    pub sessions: Vec<SessionRest>,
+    /// Long-run averages per exercise across all listed sessions.
+    pub exercises: Vec<ExerciseRest>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RestPerformanceBucket {
+    pub min_rest_seconds: i64,
+    /// Exclusive; None for the open-ended last bucket.
+    pub max_rest_seconds: Option<i64>,
+    pub intervals: i64,
+    pub avg_rest_seconds: Option<f64>,
+    /// Reps lost from one working set to the next at the same weight.
+    pub avg_rep_drop: Option<f64>,
+    pub avg_rep_drop_pct: Option<f64>,
+}
+
+/// One gap between consecutive sets of the same session exercise.
+struct RestGap {
+    session_id: i64,
+    started_at: String,
+    template_id: Option<i64>,
+    exercise_id: i64,
+    exercise_name: String,
+    prescribed: Option<i64>,
+    seconds: f64,
+    prev_weight: Option<f64>,
+    prev_reps: i64,
+    prev_type: SetType,
+    weight: Option<f64>,
+    reps: i64,
+    set_type: SetType,
+}
+
+/// Gaps in session order, then exercise order. Sets are paired by
+/// `completed_at`; rest leading into a warmup is skipped, as are gaps over
+/// [`MAX_REST_SECONDS`].
+fn rest_gaps(
+    db: &DbPool,
+    user_id: i64,
+    exercise_id: Option<i64>,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<Vec<RestGap>, AppError> {
+    let conn = db.lock().unwrap();
+    let calendar = Calendar::load(&conn, user_id)?;
+
+    let mut filter = String::new();
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(user_id)];
+    if let Some(eid) = exercise_id {
+        params.push(Box::new(eid));
+        filter.push_str(&format!(" AND se.exercise_id = ?{}", params.len()));
+    }
+    if since.is_some() || until.is_some() {
+        params.push(Box::new(calendar.tz_name()));
+    }
+    let tz = params.len();
+    if let Some(s) = since {
+        params.push(Box::new(s.to_string()));
+        filter.push_str(&format!(" AND local_date(s.started_at, ?{}) >= ?{}", tz, params.len()));
+    }
+    if let Some(u) = until {
+        params.push(Box::new(u.to_string()));
+        filter.push_str(&format!(" AND local_date(s.started_at, ?{}) <= ?{}", tz, params.len()));
+    }
+    let sql = format!(
+        "SELECT * FROM (
+            SELECT s.id, s.started_at, s.template_id, se.exercise_id, e.name,
+                   (SELECT te.rest_seconds FROM template_exercises te
+                    WHERE te.template_id = s.template_id AND te.exercise_id = se.exercise_id
+                      AND te.rest_seconds IS NOT NULL
+                    ORDER BY te.position LIMIT 1),
+                   (julianday(st.completed_at) - julianday(LAG(st.completed_at) OVER w)) * 86400.0 AS gap,
+                   LAG(st.weight_kg) OVER w, LAG(st.reps) OVER w, LAG(st.set_type) OVER w,
+                   st.weight_kg, st.reps, st.set_type, se.position
+            FROM sets st
+            JOIN session_exercises se ON se.id = st.session_exercise_id
+            JOIN sessions s ON s.id = se.session_id
+            JOIN exercises e ON e.id = se.exercise_id
+            WHERE s.user_id = ?1{}
+            WINDOW w AS (PARTITION BY st.session_exercise_id
+                         ORDER BY julianday(st.completed_at), st.set_number)
+         )
+         WHERE gap IS NOT NULL AND gap >= 0 AND gap <= {} AND counts_for_volume(set_type)
+         ORDER BY julianday(started_at), id, position",
+        filter, MAX_REST_SECONDS
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let params_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let gaps = stmt
+        .query_map(params_refs.as_slice(), |row| {
+            Ok(RestGap {
+                session_id: row.get(0)?,
+                started_at: row.get(1)?,
+                template_id: row.get(2)?,
+                exercise_id: row.get(3)?,
+                exercise_name: row.get(4)?,
+                prescribed: row.get(5)?,
+                seconds: row.get(6)?,
+                prev_weight: row.get(7)?,
+                prev_reps: row.get(8)?,
+                prev_type: row.get(9)?,
+                weight: row.get(10)?,
+                reps: row.get(11)?,
+                set_type: row.get(12)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(gaps)
+}
+
+fn round1(v: f64) -> f64 {
+    (v * 10.0).round() / 10.0
+}
+
+fn round2(v: f64) -> f64 {
+    (v * 100.0).round() / 100.0
+}
+
+/// Average the gaps per exercise, keeping first-seen exercise order.
+fn exercise_rest<'a>(gaps: impl Iterator<Item = &'a RestGap>) -> Vec<ExerciseRest> {
+    // (exercise, name, rest sum, count, prescribed sum, deviation sum, prescribed count)
+    let mut acc: Vec<(i64, String, f64, i64, f64, f64, i64)> = Vec::new();
+    for g in gaps {
+        let i = match acc.iter().position(|a| a.0 == g.exercise_id) {
+            Some(i) => i,
+            None => {
+                acc.push((g.exercise_id, g.exercise_name.clone(), 0.0, 0, 0.0, 0.0, 0));
+                acc.len() - 1
+            }
+        };
+        let a = &mut acc[i];
+        a.2 += g.seconds;
+        a.3 += 1;
+        if let Some(p) = g.prescribed {
+            a.4 += p as f64;
+            a.5 += g.seconds - p as f64;
+            a.6 += 1;
+        }
+    }
+    acc.into_iter()
+        .map(|(exercise_id, exercise_name, sum, n, p_sum, d_sum, p_n)| ExerciseRest {
+            exercise_id,
+            exercise_name,
+            intervals: n,
+            avg_rest_seconds: round1(sum / n as f64),
+            prescribed_rest_seconds: (p_n > 0).then(|| round1(p_sum / p_n as f64)),
+            avg_deviation_seconds: (p_n > 0).then(|| round1(d_sum / p_n as f64)),
+        })
+        .collect()
+}
+
+/// Actual rest between consecutive sets, per session and averaged per exercise,
+/// next to the template's prescribed `rest_seconds`.
+/// Optional exercise and since/until (YYYY-MM-DD, on session start) filters.
+pub fn rest_intervals(
+    db: &DbPool,
+    user_id: i64,
+    exercise_id: Option<i64>,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<RestAnalytics, AppError> {
+    let gaps = rest_gaps(db, user_id, exercise_id, since, until)?;
+
+    let mut sessions = Vec::new();
+    for chunk in gaps.chunk_by(|a, b| a.session_id == b.session_id) {
+        let first = &chunk[0];
+        sessions.push(SessionRest {
+            session_id: first.session_id,
+            started_at: first.started_at.clone(),
+            template_id: first.template_id,
+            exercises: exercise_rest(chunk.iter()),
+        });
+    }
+    sessions.reverse();
+
+    let mut exercises = exercise_rest(gaps.iter());
+    exercises.sort_by(|a, b| a.exercise_name.cmp(&b.exercise_name));
+
+    Ok(RestAnalytics { sessions, exercises })
+}
+
+/// Rep drop-off between consecutive working sets at the same weight, bucketed
+/// by the rest taken between them. Same filters as [`rest_intervals`].
+pub fn rest_performance(
+    db: &DbPool,
+    user_id: i64,
+    exercise_id: Option<i64>,
+    since: Option<&str>,
+    until: Option<&str>,
+) -> Result<Vec<RestPerformanceBucket>, AppError> {
+    let gaps = rest_gaps(db, user_id, exercise_id, since, until)?;
+
+    // (rest sum, drop sum, drop pct sum, count)
+    let mut acc = [(0.0, 0.0, 0.0, 0i64); REST_BUCKETS.len() + 1];
+    let comparable = gaps.iter().filter(|g| {
+        g.set_type.counts_for_e1rm()
+            && g.prev_type.counts_for_e1rm()
+            && g.prev_reps > 0
+            && match (g.prev_weight, g.weight) {
+                (Some(a), Some(b)) => (a - b).abs() < 0.01,
+                (None, None) => true,
+                _ => false,
+            }
+    });
+    for g in comparable {
+        let bucket = REST_BUCKETS
+            .iter()
+            .position(|&max| g.seconds < max as f64)
+            .unwrap_or(REST_BUCKETS.len());
+        let drop = (g.prev_reps - g.reps) as f64;
+        let a = &mut acc[bucket];
+        a.0 += g.seconds;
+        a.1 += drop;
+        a.2 += drop / g.prev_reps as f64 * 100.0;
+        a.3 += 1;
+    }
+
+    Ok(acc
+        .iter()
+        .enumerate()
+        .map(|(i, &(rest, drop, pct, n))| {
+            let avg = |sum: f64| (n > 0).then(|| round1(sum / n as f64));
+            RestPerformanceBucket {
+                min_rest_seconds: if i == 0 { 0 } else { REST_BUCKETS[i - 1] },
+                max_rest_seconds: REST_BUCKETS.get(i).copied(),
+                intervals: n,
+                avg_rest_seconds: avg(rest),
+                avg_rep_drop: avg(drop),
+                avg_rep_drop_pct: avg(pct),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One templated bench session: 90s prescribed rest, sets logged 90s,
+    /// 200s and (after an interruption) 40 minutes apart.
+    fn seeded_db() -> DbPool {
+        let db = crate::db::init_memory_db().unwrap();
+        {
+            let conn = db.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO users (id, username, password_hash) VALUES (42, 'testuser', 'x');
+                 INSERT INTO exercises (id, user_id, name) VALUES (901, 42, 'BENCH PRESS');
+                 INSERT INTO templates (id, user_id, name) VALUES (901, 42, 'Push');
+                 INSERT INTO template_exercises (template_id, exercise_id, position, rest_seconds)
+                     VALUES (901, 901, 0, 90);
+                 INSERT INTO sessions (id, user_id, template_id, started_at, ended_at, status)
+                     VALUES (901, 42, 901, '2026-10-01 18:00:00', '2026-10-01 19:00:00', 'completed');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES (901, 901, 901, 0);
+                 INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, set_type, completed_at) VALUES
+                     (901, 1, 60, 10, 'warmup',  '2026-10-01 18:05:00'),
+                     (901, 2, 100, 8, 'working', '2026-10-01 18:06:30'),
+                     (901, 3, 100, 6, 'working', '2026-10-01 18:08:00'),
+                     (901, 4, 100, 6, 'working', '2026-10-01 18:11:20'),
+                     (901, 5, 100, 5, 'working', '2026-10-01 18:51:20');",
+            )
+            .unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn rest_intervals_compare_with_template() {
+        let db = seeded_db();
+        let rest = rest_intervals(&db, 42, None, None, None).unwrap();
+        assert_eq!(rest.sessions.len(), 1);
+        let bench = &rest.exercises[0];
+        // 18:05→18:06:30 and 18:06:30→18:08 are 90s, 18:08→18:11:20 is 200s;
+        // the 40-minute gap is an interruption.
+        assert_eq!(bench.intervals, 3);
+        assert_eq!(bench.avg_rest_seconds, round1(380.0 / 3.0));
+        assert_eq!(bench.prescribed_rest_seconds, Some(90.0));
+        assert_eq!(bench.avg_deviation_seconds, Some(round1(110.0 / 3.0)));
+        assert!(rest_intervals(&db, 7, None, None, None).unwrap().sessions.is_empty());
+    }
+
+    #[test]
+    fn rest_performance_buckets_rep_drop() {
+        let db = seeded_db();
+        let buckets = rest_performance(&db, 42, Some(901), None, None).unwrap();
+        assert_eq!(buckets.len(), REST_BUCKETS.len() + 1);
+        // 90s between the first two working sets: 8 → 6.
+        let short = &buckets[1];
+        assert_eq!((short.min_rest_seconds, short.max_rest_seconds), (60, Some(120)));
+        assert_eq!(short.intervals, 1);
+        assert_eq!(short.avg_rep_drop, Some(2.0));
+        assert_eq!(short.avg_rep_drop_pct, Some(25.0));
+        // 200s: 6 → 6.
+        assert_eq!(buckets[3].avg_rep_drop, Some(0.0));
+        assert_eq!(buckets[4].intervals, 0);
+        assert_eq!(buckets[4].max_rest_seconds, None);
+    }
+
+    #[test]
+    fn dates_follow_the_users_calendar() {
+        use crate::preferences::{set_preference, TIMEZONE_KEY, WEEK_START_KEY};
+        let db = seeded_db();
+        // Thursday 18:00 UTC.
+        assert_eq!(e1rm_progression(&db, 42, 901, None, None).unwrap().data[0].date, "2026-10-01");
+        assert_eq!(weekly_volume(&db, 42, None, None).unwrap()[0].week, "2026-09-28");
+
+        // Friday morning in Auckland.
+        set_preference(&db, 42, TIMEZONE_KEY, "Pacific/Auckland").unwrap();
+        assert_eq!(e1rm_progression(&db, 42, 901, None, None).unwrap().data[0].date, "2026-10-02");
+        assert_eq!(weekly_volume(&db, 42, Some("2026-10-02"), None).unwrap()[0].set_count, 4);
+        assert!(weekly_volume(&db, 42, None, Some("2026-10-01")).unwrap().is_empty());
+
+        set_preference(&db, 42, WEEK_START_KEY, "friday").unwrap();
+        assert_eq!(session_frequency(&db, 42).unwrap()[0].week, "2026-10-02");
+        assert_eq!(exercise_volume(&db, 42, Some(901), None, None).unwrap()[0].week, "2026-10-02");
+        assert!(set_preference(&db, 42, WEEK_START_KEY, "someday").is_err());
+    }
+
+    #[test]
+    fn summary_fits_trend_without_the_deload() {
+        let db = seeded_db();
+        {
+            // Weekly sessions after the seeded one, e1RM climbing ~2.5 kg a
+            // week with a deload in the middle.
+            let conn = db.lock().unwrap();
+            for (i, weight) in [102.5, 105.0, 80.0, 107.5, 110.0].iter().enumerate() {
+                let id = 910 + i as i64;
+                conn.execute(
+                    "INSERT INTO sessions (id, user_id, started_at, status)
+                     VALUES (?1, 42, datetime('2026-10-01 18:00:00', '+' || ?2 || ' days'), 'completed')",
+                    rusqlite::params![id, 7 * (i + 1)],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES (?1, ?1, 901, 0)",
+                    [id],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps) VALUES (?1, 1, ?2, 8)",
+                    rusqlite::params![id, weight],
+                )
+                .unwrap();
+            }
+        }
+        let bench = summary(&db, 42).unwrap().into_iter().find(|s| s.exercise_id == 901).unwrap();
+        assert_eq!(bench.trend.as_deref(), Some("up"));
+        let slope = bench.trend_slope.unwrap();
+        assert!((2.0..4.0).contains(&slope), "slope {}", slope);
+        assert!(bench.trend_slope_low.unwrap() > 0.0);
+        assert!(bench.trend_slope_high.unwrap() >= slope);
+    }
+
+    #[test]
+    fn plateaus_flag_stalled_lifts_with_an_intervention() {
+        let db = seeded_db();
+        {
+            // Squat: best five weeks ago, flat since with RPE creeping up.
+            // Row: best e1RM four sessions ago is below threshold five.
+            let conn = db.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO exercises (id, user_id, name) VALUES (902, 42, 'SQUAT'), (903, 42, 'ROW');",
+            )
+            .unwrap();
+            let squat = [(35, 140.0, 8), (28, 137.5, 8), (21, 140.0, 9), (14, 137.5, 9), (7, 137.5, 10)];
+            let row = [(28, 80.0, 8), (21, 80.0, 8), (14, 77.5, 8), (7, 77.5, 8)];
+            for (i, (exercise_id, days_ago, weight, rpe)) in squat
+                .iter()
+                .map(|&(d, w, r)| (902, d, w, r))
+                .chain(row.iter().map(|&(d, w, r)| (903, d, w, r)))
+                .enumerate()
+            {
+                let id = 920 + i as i64;
+                conn.execute(
+                    "INSERT INTO sessions (id, user_id, started_at, status)
+                     VALUES (?1, 42, datetime('now', '-' || ?2 || ' days'), 'completed')",
+                    rusqlite::params![id, days_ago],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES (?1, ?1, ?2, 0)",
+                    [id, exercise_id],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, rir) VALUES (?1, 1, ?2, 5, ?3)",
+                    rusqlite::params![id, weight, 10 - rpe],
+                )
+                .unwrap();
+            }
+        }
+
+        let stalled = plateaus(&db, 42, DEFAULT_STALL_EXPOSURES).unwrap();
+        // The seeded bench session is a single exposure; row's best is only
+        // three sessions old.
+        assert_eq!(stalled.len(), 1);
+        let squat = &stalled[0];
+        assert_eq!(squat.exercise_name, "SQUAT");
+        assert_eq!(squat.exposures_since_best, 4);
+        assert_eq!(squat.days_stalled, 35);
+        assert_eq!(squat.effort_change, Some(1.5));
+        assert_eq!(squat.intervention, "deload");
+
+        assert_eq!(plateaus(&db, 42, 3).unwrap().len(), 2);
+        assert!(matches!(plateaus(&db, 42, 1), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn unfinished_and_abandoned_sessions_are_left_out() {
+        let db = seeded_db();
+        {
+            // Bench: a heavier abandoned session and a lighter one still in
+            // progress. Row: best three sessions ago, then two more below it
+            // that weren't completed.
+            let conn = db.lock().unwrap();
+            conn.execute("INSERT INTO exercises (id, user_id, name) VALUES (903, 42, 'ROW')", []).unwrap();
+            let sessions = [
+                (940, 901, "'2026-10-05 18:00:00'", "abandoned", 130.0),
+                (941, 901, "'2026-10-06 18:00:00'", "active", 60.0),
+                (942, 903, "datetime('now', '-28 days')", "completed", 80.0),
+                (943, 903, "datetime('now', '-21 days')", "completed", 77.5),
+                (944, 903, "datetime('now', '-14 days')", "completed", 77.5),
+                (945, 903, "datetime('now', '-7 days')", "abandoned", 75.0),
+                (946, 903, "datetime('now')", "active", 75.0),
+            ];
+            for (id, exercise_id, started_at, status, weight) in sessions {
+                conn.execute(
+                    &format!(
+                        "INSERT INTO sessions (id, user_id, started_at, status) VALUES (?1, 42, {}, ?2)",
+                        started_at
+                    ),
+                    rusqlite::params![id, status],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES (?1, ?1, ?2, 0)",
+                    [id, exercise_id],
+                )
+                .unwrap();
+                conn.execute(
+                    "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps) VALUES (?1, 1, ?2, 5)",
+                    rusqlite::params![id, weight],
+                )
+                .unwrap();
+            }
+        }
+
+        let progression = e1rm_progression(&db, 42, 901, None, None).unwrap();
+        assert_eq!(progression.data.len(), 1);
+        assert_eq!(progression.data[0].date, "2026-10-01");
+        let table = rep_maxes(&db, 42, 901, 0).unwrap();
+        assert_eq!((table.date.as_str(), table.e1rm), ("2026-10-01", 126.7));
+        // Two completed sessions since row's best: not yet three.
+        assert!(plateaus(&db, 42, 3).unwrap().iter().all(|p| p.exercise_name != "ROW"));
+    }
+
+    #[test]
+    fn strength_scores_use_bodyweight_at_each_date() {
+        use crate::preferences::{set_preference, COMPETITION_LIFTS_KEY, SEX_KEY};
+        let db = seeded_db();
+        {
+            let conn = db.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO exercises (id, user_id, name) VALUES (904, 42, 'SQUAT'), (905, 42, 'DEADLIFT');
+                 INSERT INTO sessions (id, user_id, started_at, status) VALUES
+                     (930, 42, '2026-10-08 18:00:00', 'completed'),
+                     (931, 42, '2026-10-15 18:00:00', 'completed');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES
+                     (930, 930, 904, 0), (931, 930, 905, 1), (932, 931, 901, 0);
+                 INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps) VALUES
+                     (930, 1, 150, 3), (931, 1, 180, 3), (932, 1, 105, 5);",
+            )
+            .unwrap();
+        }
+        assert!(matches!(strength_scores(&db, 42, None, None), Err(AppError::BadRequest(_))));
+        set_preference(&db, 42, SEX_KEY, "male").unwrap();
+        assert!(matches!(strength_scores(&db, 42, None, None), Err(AppError::BadRequest(_))));
+        set_preference(&db, 42, COMPETITION_LIFTS_KEY, r#"{"squat": 904, "bench": 901, "deadlift": 905}"#).unwrap();
+        assert!(strength_scores(&db, 42, None, None).unwrap().points.is_empty());
+
+        bodyweight::record(&db, 42, "2026-10-05", 90.0).unwrap();
+        bodyweight::record(&db, 42, "2026-10-12", 88.0).unwrap();
+        let scores = strength_scores(&db, 42, None, None).unwrap();
+        assert_eq!(scores.equipment, "raw");
+        let dates: Vec<&str> = scores.points.iter().map(|p| p.date.as_str()).collect();
+        assert_eq!(dates, ["2026-10-01", "2026-10-08", "2026-10-15"]);
+
+        // Bench only on day one, before the first weigh-in.
+        let first = &scores.points[0];
+        assert_eq!(first.bodyweight_kg, 90.0);
+        assert_eq!(first.total, None);
+        assert_eq!(first.wilks, None);
+        assert!(first.bench_ipf_gl.is_some());
+
+        // All three lifts by day two; day three beats the bench at a lighter
+        // bodyweight.
+        let (second, third) = (&scores.points[1], &scores.points[2]);
+        let total = 100.0 * (1.0 + 8.0 / 30.0) + 150.0 * 1.1 + 180.0 * 1.1;
+        assert_eq!(second.total, Some(e1rm::round(total)));
+        assert_eq!(second.dots, relative_strength::dots(Sex::Male, 90.0, total).map(round2));
+        assert_eq!(third.bodyweight_kg, 88.0);
+        assert_eq!(third.bench_e1rm, Some(e1rm::round(105.0 * (1.0 + 5.0 / 30.0))));
+        assert!(third.ipf_gl > second.ipf_gl);
+
+        let recent = strength_scores(&db, 42, Some("2026-10-10"), None).unwrap();
+        assert_eq!(recent.points.len(), 1);
+        assert_eq!(recent.points[0].squat_e1rm, second.squat_e1rm);
+    }
+
+    #[test]
+    fn rep_maxes_come_from_the_latest_e1rm() {
+        let db = seeded_db();
+        // Seeded best: 100 × 8 → e1RM 126.67; its 8RM is 100 again.
+        let table = rep_maxes(&db, 42, 901, 0).unwrap();
+        assert_eq!(table.date, "2026-10-01");
+        assert_eq!(table.e1rm, 126.7);
+        assert_eq!(table.rep_maxes.len(), 15);
+        assert_eq!(table.rep_maxes[7].reps, 8);
+        assert_eq!(table.rep_maxes[7].weight_kg, 100.0);
+        assert_eq!(table.rep_maxes[7].pct_e1rm, 78.9);
+
+        // 6 reps at RIR 2 is the 8RM.
+        let at_rir = rep_maxes(&db, 42, 901, 2).unwrap();
+        assert_eq!(at_rir.rep_maxes[5].weight_kg, 100.0);
+
+        assert!(matches!(rep_maxes(&db, 42, 901, 6), Err(AppError::BadRequest(_))));
+        assert!(matches!(rep_maxes(&db, 42, 999, 0), Err(AppError::NotFound)));
+    }
+
+    #[test]
+    fn rep_maxes_round_to_the_equipment_profile() {
+        use crate::preferences::{set_preference, EQUIPMENT_PROFILE_KEY};
+        let db = seeded_db();
+        db.lock().unwrap().execute("UPDATE exercises SET equipment = 'Barbell' WHERE id = 901", []).unwrap();
+        assert!(set_preference(&db, 42, EQUIPMENT_PROFILE_KEY, r#"{"plates": [{"weight_kg": 0, "pairs": 1}]}"#).is_err());
+        assert!(set_preference(&db, 42, EQUIPMENT_PROFILE_KEY, r#"{"bar": 20}"#).is_err());
+        // A 1 g plate would make the loading table count in grams.
+        assert!(set_preference(
+            &db, 42, EQUIPMENT_PROFILE_KEY,
+            r#"{"plates": [{"weight_kg": 50, "pairs": 20}, {"weight_kg": 0.001, "pairs": 1}]}"#,
+        ).is_err());
+        set_preference(
+            &db, 42, EQUIPMENT_PROFILE_KEY,
+            r#"{"plates": [{"weight_kg": 20, "pairs": 2}, {"weight_kg": 10, "pairs": 1}, {"weight_kg": 2.5, "pairs": 2}]}"#,
+        ).unwrap();
+
+        let table = rep_maxes(&db, 42, 901, 0).unwrap();
+        // 5RM estimate 108.6: 110 is 45 a side, 105 is 42.5 a side.
+        let five = &table.rep_maxes[4];
+        assert_eq!(five.estimate_kg, 108.6);
+        assert_eq!(five.weight_kg, 110.0);
+        assert_eq!(five.plates_per_side, Some(vec![20.0, 20.0, 2.5, 2.5]));
+        assert_eq!(five.pct_e1rm, 86.8);
+        // The 1RM of 126.7 needs 53.3 a side: 52.5 is nearer than 55.
+        assert_eq!(table.rep_maxes[0].weight_kg, 125.0);
+        assert_eq!(table.rep_maxes[0].plates_per_side, Some(vec![20.0, 20.0, 10.0, 2.5]));
+
+        // A profile that passes the checks can't make the rounding run away,
+        // even for an absurd logged set; past the table cap loads stand.
+        let full_kit: Vec<String> =
+            (1..=16).map(|i| format!(r#"{{"weight_kg": {}, "pairs": 20}}"#, 50.0 - 0.25 * i as f64)).collect();
+        set_preference(&db, 42, EQUIPMENT_PROFILE_KEY, &format!(r#"{{"plates": [{}]}}"#, full_kit.join(","))).unwrap();
+        db.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO sessions (id, user_id, started_at, status) VALUES (950, 42, '2026-10-02 18:00:00', 'completed');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES (950, 950, 901, 0);
+                 INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps) VALUES (950, 1, 30000, 1);",
+            )
+            .unwrap();
+        let table = rep_maxes(&db, 42, 901, 0).unwrap();
+        assert_eq!(table.rep_maxes[0].weight_kg, table.rep_maxes[0].estimate_kg);
+        assert_eq!(table.rep_maxes[0].plates_per_side, None);
+        db.lock().unwrap().execute("DELETE FROM sessions WHERE id = 950", []).unwrap();
+
+        // No rounding for equipment the profile doesn't cover.
+        db.lock().unwrap().execute("UPDATE exercises SET equipment = 'Cable' WHERE id = 901", []).unwrap();
+        let table = rep_maxes(&db, 42, 901, 0).unwrap();
+        assert_eq!(table.rep_maxes[4].weight_kg, 108.6);
+        assert_eq!(table.rep_maxes[4].plates_per_side, None);
+    }
+}
@@ -71,13 +71,18 @@ pub(crate) fn seed_exercises(conn: &rusqlite::Connection, user_id: i64) -> Resul
     Ok(())
 }
 
-const SESSION_DURATION_DAYS: i32 = 30;
-
-pub(crate) fn create_auth_session(conn: &rusqlite::Connection, user_id: i64) -> Result<String, AppError> {
+/// Default auth session lifetime; the server overrides it from its config.
+pub const DEFAULT_SESSION_LIFETIME_DAYS: u32 = 30;
+
+pub(crate) fn create_auth_session(
+    conn: &rusqlite::Connection,
+    user_id: i64,
+    lifetime_days: u32,
+) -> Result<String, AppError> {
     let token = generate_token();
     conn.execute(
         "INSERT INTO auth_sessions (user_id, token, expires_at) VALUES (?1, ?2, datetime('now', ?3))",
-        rusqlite::params![user_id, token, format!("+{} days", SESSION_DURATION_DAYS)],
+        rusqlite::params![user_id, token, format!("+{} days", lifetime_days)],
     )?;
     Ok(token)
 }
@@ -89,6 +94,7 @@ pub fn register(
     invite_code: Option<&str>,
     required_invite_code: Option<&str>,
     email: Option<&str>,
+    session_lifetime_days: u32,
 ) -> Result<AuthResponse, AppError> {
     // Validate invite code if required
     if let Some(required) = required_invite_code {
@@ -122,11 +128,16 @@ pub fn register(
 
     seed_exercises(&conn, user_id)?;
 
-    let token = create_auth_session(&conn, user_id)?;
+    let token = create_auth_session(&conn, user_id, session_lifetime_days)?;
     Ok(AuthResponse { token, user_id })
 }
 
-pub fn login(db: &DbPool, username: &str, password: &str) -> Result<AuthResponse, AppError> {
+pub fn login(
+    db: &DbPool,
+    username: &str,
+    password: &str,
+    session_lifetime_days: u32,
+) -> Result<AuthResponse, AppError> {
     let conn = db.lock().unwrap();
 
     let result: Result<(i64, Option<String>), _> = conn.query_row(
@@ -144,7 +155,7 @@ pub fn login(db: &DbPool, username: &str, password: &str) -> Result<AuthResponse
         return Err(AppError::Unauthorized);
     }
 
-    let token = create_auth_session(&conn, user_id)?;
+    let token = create_auth_session(&conn, user_id, session_lifetime_days)?;
     Ok(AuthResponse { token, user_id })
 }
 
@@ -153,6 +164,7 @@ pub fn google_auth(
     db: &DbPool,
     google_id: &str,
     email: Option<&str>,
+    session_lifetime_days: u32,
 ) -> Result<AuthResponse, AppError> {
     let conn = db.lock().unwrap();
 
@@ -186,7 +198,7 @@ pub fn google_auth(
         Err(e) => return Err(AppError::Database(e)),
     };
 
-    let token = create_auth_session(&conn, user_id)?;
+    let token = create_auth_session(&conn, user_id, session_lifetime_days)?;
     Ok(AuthResponse { token, user_id })
 }
 
@@ -199,6 +211,16 @@ pub fn logout(db: &DbPool, token: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Delete expired login sessions. Returns how many were removed.
+pub fn purge_expired_sessions(db: &DbPool) -> Result<usize, AppError> {
+    let conn = db.lock().unwrap();
+    let removed = conn.execute(
+        "DELETE FROM auth_sessions WHERE expires_at <= datetime('now')",
+        [],
+    )?;
+    Ok(removed)
+}
+
 pub fn verify_token(db: &DbPool, token: &str) -> Result<Option<i64>, AppError> {
     let conn = db.lock().unwrap();
     let result: Result<i64, _> = conn.query_row(
@@ -0,0 +1,68 @@
+//! Bodyweight log: one weight per user per local date, used to scale lifts
+//! into relative-strength scores.
+
+use chrono::NaiveDate;
+
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::Bodyweight;
+
+/// Accepted range, kg: any lifter fits, a stray extra digit doesn't.
+const MIN_KG: f64 = 20.0;
+const MAX_KG: f64 = 400.0;
+
+fn parse_day(date: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest(format!("Invalid date: {} (expected YYYY-MM-DD)", date)))
+}
+
+/// Entries in date order, optionally between two local dates inclusive.
+pub fn list(db: &DbPool, user_id: i64, since: Option<&str>, until: Option<&str>) -> Result<Vec<Bodyweight>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(
+        "SELECT measured_on, weight_kg FROM bodyweights
+         WHERE user_id = ?1
+           AND (?2 IS NULL OR measured_on >= ?2)
+           AND (?3 IS NULL OR measured_on <= ?3)
+         ORDER BY measured_on",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![user_id, since, until], |row| {
+            Ok(Bodyweight { measured_on: row.get(0)?, weight_kg: row.get(1)? })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+/// Record the weight for a date, replacing any earlier entry for that date.
+pub fn record(db: &DbPool, user_id: i64, date: &str, weight_kg: f64) -> Result<Bodyweight, AppError> {
+    let day = parse_day(date)?;
+    if !(MIN_KG..=MAX_KG).contains(&weight_kg) {
+        return Err(AppError::BadRequest(format!(
+            "weight_kg must be between {} and {}, got {}",
+            MIN_KG, MAX_KG, weight_kg
+        )));
+    }
+    let measured_on = day.to_string();
+    let conn = db.lock().unwrap();
+    conn.execute(
+        "INSERT INTO bodyweights (user_id, measured_on, weight_kg) VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id, measured_on) DO UPDATE SET weight_kg = excluded.weight_kg",
+        rusqlite::params![user_id, measured_on, weight_kg],
+    )?;
+    Ok(Bodyweight { measured_on, weight_kg })
+}
+
+pub fn delete(db: &DbPool, user_id: i64, date: &str) -> Result<(), AppError> {
+    let day = parse_day(date)?;
+    let conn = db.lock().unwrap();
+    let rows = conn.execute(
+        "DELETE FROM bodyweights WHERE user_id = ?1 AND measured_on = ?2",
+        rusqlite::params![user_id, day.to_string()],
+    )?;
+    if rows == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
@@ -0,0 +1,184 @@
+//! A user's calendar: the timezone their days are in and the weekday their
+//! weeks start on, both user preferences.
+//!
+//! Timestamps are stored in UTC. Queries that bucket by day or week convert
+//! them with two SQL functions, registered on every connection:
+//!
+//! ```sql
+//! local_date(ts, tz)             -- 'YYYY-MM-DD' in the IANA zone `tz`
+//! local_week(ts, tz, week_start) -- first day of that date's week; 0 = Monday … 6 = Sunday
+//! ```
+//!
+//! Both return NULL for a NULL or unparseable timestamp and treat an unknown
+//! zone as UTC.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::Connection;
+
+use crate::preferences::{TIMEZONE_KEY, WEEK_START_KEY};
+use crate::timestamp;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calendar {
+    pub tz: Tz,
+    pub week_start: Weekday,
+}
+
+impl Default for Calendar {
+    fn default() -> Self {
+        Calendar { tz: chrono_tz::UTC, week_start: Weekday::Mon }
+    }
+}
+
+/// Parse a weekday preference value (`monday` … `sunday`).
+pub fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "monday" => Some(Weekday::Mon),
+        "tuesday" => Some(Weekday::Tue),
+        "wednesday" => Some(Weekday::Wed),
+        "thursday" => Some(Weekday::Thu),
+        "friday" => Some(Weekday::Fri),
+        "saturday" => Some(Weekday::Sat),
+        "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+impl Calendar {
+    /// The user's preferences; UTC and Monday when unset or invalid.
+    pub(crate) fn load(conn: &Connection, user_id: i64) -> rusqlite::Result<Calendar> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT key, value FROM user_preferences WHERE user_id = ?1 AND key IN (?2, ?3)",
+        )?;
+        let mut calendar = Calendar::default();
+        let rows = stmt.query_map(rusqlite::params![user_id, TIMEZONE_KEY, WEEK_START_KEY], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (key, value) = row?;
+            if key == TIMEZONE_KEY {
+                calendar.tz = value.parse().unwrap_or(chrono_tz::UTC);
+            } else if let Some(day) = parse_weekday(&value) {
+                calendar.week_start = day;
+            }
+        }
+        Ok(calendar)
+    }
+
+    pub fn tz_name(&self) -> &'static str {
+        self.tz.name()
+    }
+
+    /// `week_start` as the SQL functions take it: 0 = Monday … 6 = Sunday.
+    pub fn week_start_day(&self) -> i64 {
+        self.week_start.num_days_from_monday() as i64
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date_at(Utc::now())
+    }
+
+    pub fn date_at(&self, now: DateTime<Utc>) -> NaiveDate {
+        now.with_timezone(&self.tz).date_naive()
+    }
+
+    /// Local date of a stored UTC timestamp.
+    pub fn date_of(&self, ts: &str) -> Option<NaiveDate> {
+        timestamp::parse(ts).map(|t| t.with_timezone(&self.tz).date_naive())
+    }
+
+    /// First day of the week containing `date`.
+    pub fn week_of(&self, date: NaiveDate) -> NaiveDate {
+        date.week(self.week_start).first_day()
+    }
+
+    /// UTC instant of local midnight starting `date`. Where a DST change skips
+    /// midnight the day starts at the first valid local time after it.
+    pub fn midnight(&self, date: NaiveDate) -> NaiveDateTime {
+        let mut local = date.and_hms_opt(0, 0, 0).unwrap();
+        loop {
+            if let Some(t) = self.tz.from_local_datetime(&local).earliest() {
+                return t.naive_utc();
+            }
+            local += Duration::minutes(30);
+        }
+    }
+}
+
+fn calendar_arg(ctx: &rusqlite::functions::Context, tz: usize, week_start: Option<usize>) -> rusqlite::Result<Calendar> {
+    let tz = ctx.get::<Option<String>>(tz)?.and_then(|name| name.parse().ok()).unwrap_or(chrono_tz::UTC);
+    let week_start = match week_start {
+        Some(i) => match ctx.get::<i64>(i)?.rem_euclid(7) {
+            0 => Weekday::Mon,
+            1 => Weekday::Tue,
+            2 => Weekday::Wed,
+            3 => Weekday::Thu,
+            4 => Weekday::Fri,
+            5 => Weekday::Sat,
+            _ => Weekday::Sun,
+        },
+        None => Weekday::Mon,
+    };
+    Ok(Calendar { tz, week_start })
+}
+
+/// Register `local_date` and `local_week` on a connection.
+pub(crate) fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    conn.create_scalar_function("local_date", 2, flags, |ctx| {
+        let calendar = calendar_arg(ctx, 1, None)?;
+        let ts = ctx.get::<Option<String>>(0)?;
+        Ok(ts.and_then(|ts| calendar.date_of(&ts)).map(|d| d.to_string()))
+    })?;
+    conn.create_scalar_function("local_week", 3, flags, |ctx| {
+        let calendar = calendar_arg(ctx, 1, Some(2))?;
+        let ts = ctx.get::<Option<String>>(0)?;
+        Ok(ts.and_then(|ts| calendar.date_of(&ts)).map(|d| calendar.week_of(d).to_string()))
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sql_functions_bucket_in_local_time() {
+        let conn = Connection::open_in_memory().unwrap();
+        register_functions(&conn).unwrap();
+        let query = |sql: &str| conn.query_row(sql, [], |row| row.get::<_, Option<String>>(0)).unwrap();
+
+        // 20:30 UTC on a Saturday is Sunday morning in Sydney.
+        assert_eq!(query("SELECT local_date('2026-10-17 20:30:00', 'UTC')").as_deref(), Some("2026-10-17"));
+        assert_eq!(
+            query("SELECT local_date('2026-10-17 20:30:00', 'Australia/Sydney')").as_deref(),
+            Some("2026-10-18")
+        );
+        assert_eq!(query("SELECT local_date('2026-10-17T20:30:00Z', 'Nowhere/Else')").as_deref(), Some("2026-10-17"));
+        assert_eq!(query("SELECT local_date(NULL, 'UTC')"), None);
+        assert_eq!(query("SELECT local_date('not a date', 'UTC')"), None);
+
+        // Monday weeks put that Sunday at the end of the week of the 12th;
+        // Sunday weeks start a new one.
+        assert_eq!(
+            query("SELECT local_week('2026-10-17 20:30:00', 'Australia/Sydney', 0)").as_deref(),
+            Some("2026-10-12")
+        );
+        assert_eq!(
+            query("SELECT local_week('2026-10-17 20:30:00', 'Australia/Sydney', 6)").as_deref(),
+            Some("2026-10-18")
+        );
+    }
+
+    #[test]
+    fn midnight_follows_dst() {
+        let calendar = Calendar { tz: "Europe/London".parse().unwrap(), week_start: Weekday::Mon };
+        let summer = NaiveDate::from_ymd_opt(2026, 7, 1).unwrap();
+        let winter = NaiveDate::from_ymd_opt(2026, 12, 1).unwrap();
+        assert_eq!(calendar.midnight(summer).to_string(), "2026-06-30 23:00:00");
+        assert_eq!(calendar.midnight(winter).to_string(), "2026-12-01 00:00:00");
+        assert_eq!(calendar.week_of(winter), NaiveDate::from_ymd_opt(2026, 11, 30).unwrap());
+    }
+}
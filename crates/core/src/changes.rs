@@ -0,0 +1,58 @@
+//! Change notifications for live clients.
+//!
+//! Core mutations describe what changed as a [`Change`] and hand it to the
+//! observer installed with [`crate::db::Db::observe_changes`]; the server
+//! fans these out to connected devices. Without an observer (CLI, tests)
+//! they are dropped. Every change belongs to one session of one user.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::models::{RestTimer, SessionExerciseWithSets, Set};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    /// Account the change belongs to; only that user's devices receive it.
+    #[serde(skip)]
+    pub user_id: i64,
+    pub session_id: i64,
+    #[serde(flatten)]
+    pub event: ChangeEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChangeEvent {
+    /// Status or end time changed: pause, resume, finish, auto-close, reopen.
+    /// `deleted` when the session is gone.
+    SessionStatus { status: String, ended_at: Option<DateTime<Utc>> },
+    ExerciseAdded { exercise: SessionExerciseWithSets },
+    /// Position (reorder) or notes changed.
+    ExerciseUpdated { session_exercise_id: i64, position: i32, notes: Option<String> },
+    ExerciseRemoved { session_exercise_id: i64 },
+    SetAdded { set: Set },
+    SetUpdated { set: Set },
+    SetDeleted { session_exercise_id: i64, set_id: i64 },
+    /// The rest timer was started, adjusted or skipped; None when cleared.
+    RestTimer { timer: Option<RestTimer> },
+}
+
+impl Change {
+    pub fn new(user_id: i64, session_id: i64, event: ChangeEvent) -> Self {
+        Change { user_id, session_id, event }
+    }
+
+    /// Event name for the wire; matches the serialized `type`.
+    pub fn kind(&self) -> &'static str {
+        match self.event {
+            ChangeEvent::SessionStatus { .. } => "session_status",
+            ChangeEvent::ExerciseAdded { .. } => "exercise_added",
+            ChangeEvent::ExerciseUpdated { .. } => "exercise_updated",
+            ChangeEvent::ExerciseRemoved { .. } => "exercise_removed",
+            ChangeEvent::SetAdded { .. } => "set_added",
+            ChangeEvent::SetUpdated { .. } => "set_updated",
+            ChangeEvent::SetDeleted { .. } => "set_deleted",
+            ChangeEvent::RestTimer { .. } => "rest_timer",
+        }
+    }
+}
@@ -1,23 +1,107 @@
+use rusqlite::functions::FunctionFlags;
 use rusqlite::Connection;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LockResult, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
 
+use crate::changes::Change;
 use crate::error::AppError;
+use crate::models::SetType;
 
-pub type DbPool = Arc<Mutex<Connection>>;
+pub type DbPool = Arc<Db>;
+
+type WaitObserver = Box<dyn Fn(Duration) + Send + Sync>;
+type ChangeObserver = Box<dyn Fn(&Change) + Send + Sync>;
+
+/// The single shared SQLite connection.
+///
+/// `lock` behaves like `Mutex::lock`; if an observer is installed it is also
+/// told how long each caller waited for the connection (lock contention).
+pub struct Db {
+    conn: Mutex<Connection>,
+    wait_observer: OnceLock<WaitObserver>,
+    change_observer: OnceLock<ChangeObserver>,
+}
+
+impl Db {
+    fn new(conn: Connection) -> Self {
+        Db {
+            conn: Mutex::new(conn),
+            wait_observer: OnceLock::new(),
+            change_observer: OnceLock::new(),
+        }
+    }
+
+    pub fn lock(&self) -> LockResult<MutexGuard<'_, Connection>> {
+        match self.wait_observer.get() {
+            Some(observe) => {
+                let start = Instant::now();
+                let guard = self.conn.lock();
+                observe(start.elapsed());
+                guard
+            }
+            None => self.conn.lock(),
+        }
+    }
+
+    /// Install the lock-wait observer. Only the first call takes effect.
+    pub fn observe_lock_wait(&self, observer: impl Fn(Duration) + Send + Sync + 'static) {
+        let _ = self.wait_observer.set(Box::new(observer));
+    }
+
+    /// Install the change observer (see [`crate::changes`]). Only the first
+    /// call takes effect. It runs on the mutating thread, so keep it cheap.
+    pub fn observe_changes(&self, observer: impl Fn(&Change) + Send + Sync + 'static) {
+        let _ = self.change_observer.set(Box::new(observer));
+    }
+
+    pub(crate) fn notify(&self, change: Change) {
+        if let Some(observe) = self.change_observer.get() {
+            observe(&change);
+        }
+    }
+}
 
 pub fn init_db(path: &str) -> Result<DbPool, AppError> {
     let conn = Connection::open(path)?;
     conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON;")?;
+    register_functions(&conn)?;
     run_migrations(&conn)?;
-    Ok(Arc::new(Mutex::new(conn)))
+    Ok(Arc::new(Db::new(conn)))
 }
 
 pub fn init_memory_db() -> Result<DbPool, AppError> {
     let conn = Connection::open_in_memory()?;
     conn.execute_batch("PRAGMA foreign_keys=ON;")?;
+    register_functions(&conn)?;
     run_migrations(&conn)?;
-    Ok(Arc::new(Mutex::new(conn)))
+    Ok(Arc::new(Db::new(conn)))
+}
+
+/// SQL functions available to every query: `local_date` and `local_week`
+/// (see [`crate::calendar`]), and `counts_for_volume(set_type)` and
+/// `counts_for_e1rm(set_type)`, which classify sets as [`SetType`] does.
+fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    crate::calendar::register_functions(conn)?;
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    let set_type = |ctx: &rusqlite::functions::Context| -> rusqlite::Result<Option<SetType>> {
+        Ok(ctx.get::<Option<String>>(0)?.and_then(|t| t.parse().ok()))
+    };
+    conn.create_scalar_function("counts_for_volume", 1, flags, move |ctx| {
+        Ok(set_type(ctx)?.is_some_and(SetType::counts_for_volume))
+    })?;
+    conn.create_scalar_function("counts_for_e1rm", 1, flags, move |ctx| {
+        Ok(set_type(ctx)?.is_some_and(SetType::counts_for_e1rm))
+    })?;
+    Ok(())
+}
+
+/// Write a consistent snapshot of the live database to `dest` (`VACUUM INTO`).
+/// `dest` must not exist yet.
+pub fn backup(db: &DbPool, dest: &Path) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])?;
+    Ok(())
 }
 
 fn run_migrations(conn: &Connection) -> Result<(), AppError> {
@@ -29,18 +113,7 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
         )"
     )?;
 
-    let migrations_dir = find_migrations_dir();
-    let mut entries: Vec<_> = std::fs::read_dir(&migrations_dir)
-        .map(|rd| rd.filter_map(|e| e.ok()).collect())
-        .unwrap_or_default();
-    entries.sort_by_key(|e| e.file_name());
-
-    for entry in entries {
-        let name = entry.file_name().to_string_lossy().to_string();
-        if !name.ends_with(".sql") {
-            continue;
-        }
-
+    for (name, path) in migration_files() {
         let already_applied: bool = conn.query_row(
             "SELECT COUNT(*) > 0 FROM _migrations WHERE name = ?1",
             [&name],
@@ -51,7 +124,7 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
             continue;
         }
 
-        let sql = std::fs::read_to_string(entry.path())
+        let sql = std::fs::read_to_string(&path)
             .map_err(|_| AppError::BadRequest(format!("Cannot read migration: {}", name)))?;
 
         conn.execute_batch(&sql)?;
@@ -64,7 +137,35 @@ fn run_migrations(conn: &Connection) -> Result<(), AppError> {
     Ok(())
 }
 
-fn find_migrations_dir() -> std::path::PathBuf {
+/// Migration files on disk that have not been applied to `conn` yet.
+/// Empty means the schema is current.
+pub fn pending_migrations(conn: &Connection) -> Result<Vec<String>, AppError> {
+    let mut stmt = conn.prepare("SELECT name FROM _migrations")?;
+    let applied = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<std::collections::HashSet<_>, _>>()?;
+    Ok(migration_files()
+        .into_iter()
+        .map(|(name, _)| name)
+        .filter(|name| !applied.contains(name))
+        .collect())
+}
+
+/// `*.sql` files in the migrations directory, sorted by name.
+fn migration_files() -> Vec<(String, PathBuf)> {
+    let mut files: Vec<(String, PathBuf)> = std::fs::read_dir(find_migrations_dir())
+        .map(|rd| {
+            rd.filter_map(|e| e.ok())
+                .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+                .filter(|(name, _)| name.ends_with(".sql"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn find_migrations_dir() -> PathBuf {
     // Check relative to current dir, then up a few levels (for workspace builds)
     let candidates = [
         "migrations",
@@ -0,0 +1,726 @@
+//! Weekly training digest: a summary of one week of the user's calendar
+//! (timezone and week start), stored for history and rendered as Markdown
+//! and HTML.
+//!
+//! - Completed sessions with their working sets and tonnage.
+//! - Working sets by muscle group ([`analytics::weekly_volume`]).
+//! - PRs hit during the week ([`analytics::session_prs_hit`]).
+//! - Biggest e1RM movers and exercises going stale, as of generation.
+//! - Training streak in consecutive weeks with at least one session.
+//!
+//! The server's `weekly_digests` job calls [`generate_due`], which builds last
+//! week's digest for everyone whose local week has rolled over. Each new
+//! digest is sent to `digest.ready` webhooks, and emailed by the server when
+//! the user asked for that.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use utoipa::ToSchema;
+
+use crate::analytics::{self, E1rmMover, SessionPR, StaleExercise};
+use crate::calendar::Calendar;
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::preferences;
+use crate::webhooks;
+
+/// Movers and stale exercises listed, most notable first.
+const MAX_LISTED: usize = 5;
+/// e1RM windows compared for movers: this week against the one before.
+const MOVER_DAYS: i64 = 7;
+/// Exercises in rotation not trained for this long are going stale.
+const STALE_DAYS: i64 = 14;
+/// Digests older than this are never emailed, so switching email on doesn't
+/// send a backlog.
+const EMAIL_WINDOW_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Digest {
+    /// First day of the week, in `timezone`.
+    pub week_start: String,
+    /// Last day of the week, in `timezone`.
+    pub week_end: String,
+    pub timezone: String,
+    pub sessions: Vec<DigestSession>,
+    pub working_sets: i64,
+    /// Working-set tonnage (weight × reps) across the week.
+    pub volume_kg: f64,
+    /// Working sets per muscle group, most trained first.
+    pub muscle_groups: Vec<MuscleGroupSets>,
+    /// Best PR per exercise, against bests from before the week.
+    pub prs: Vec<SessionPR>,
+    pub movers: Vec<E1rmMover>,
+    pub stale: Vec<StaleExercise>,
+    pub streak: Streak,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DigestSession {
+    pub session_id: i64,
+    pub name: Option<String>,
+    /// Local date the session started.
+    pub date: String,
+    pub working_sets: i64,
+    pub volume_kg: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MuscleGroupSets {
+    pub muscle_group: String,
+    pub set_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Streak {
+    /// Consecutive weeks with a completed session, ending with this one;
+    /// 0 when this week had none.
+    pub current_weeks: i64,
+    /// Longest such run up to and including this week.
+    pub longest_weeks: i64,
+}
+
+/// A stored digest.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DigestRecord {
+    pub id: i64,
+    pub week_start: String,
+    pub timezone: String,
+    pub created_at: String,
+    pub emailed_at: Option<String>,
+    pub digest: Digest,
+}
+
+/// A digest waiting to be emailed, with everything the mailer needs.
+#[derive(Debug)]
+pub struct DigestEmail {
+    pub id: i64,
+    pub to: String,
+    pub subject: String,
+    pub markdown: String,
+    pub html: String,
+}
+
+// ── Calendar ──
+
+fn sql_datetime(t: NaiveDateTime) -> String {
+    t.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// First day of the last full week before `now`.
+fn last_week_start(calendar: &Calendar, now: DateTime<Utc>) -> NaiveDate {
+    calendar.week_of(calendar.date_at(now)) - Duration::days(7)
+}
+
+fn parse_week(calendar: &Calendar, week_start: &str) -> Result<NaiveDate, AppError> {
+    NaiveDate::parse_from_str(week_start, "%Y-%m-%d")
+        .map(|d| calendar.week_of(d))
+        .map_err(|_| AppError::BadRequest(format!("Invalid week_start: {} (expected YYYY-MM-DD)", week_start)))
+}
+
+// ── Building ──
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn streak(weeks: &BTreeSet<NaiveDate>, week_start: NaiveDate) -> Streak {
+    let mut current = 0;
+    let mut week = week_start;
+    while weeks.contains(&week) {
+        current += 1;
+        week -= Duration::days(7);
+    }
+    let (mut longest, mut run, mut previous) = (0, 0, None::<NaiveDate>);
+    for &week in weeks.range(..=week_start) {
+        run = match previous {
+            Some(p) if week - p == Duration::days(7) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(week);
+    }
+    Streak { current_weeks: current, longest_weeks: longest }
+}
+
+/// Build the digest for the week starting on `week_start` (the first day of
+/// a week in `calendar`). Takes the database lock only for its own queries.
+pub fn build(db: &DbPool, user_id: i64, calendar: &Calendar, week_start: NaiveDate) -> Result<Digest, AppError> {
+    let week_end = week_start + Duration::days(6);
+    let from = sql_datetime(calendar.midnight(week_start));
+    let to = sql_datetime(calendar.midnight(week_start + Duration::days(7)));
+
+    let (sessions, session_starts) = {
+        let conn = db.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT s.id, COALESCE(s.name, t.name), datetime(s.started_at),
+                    (SELECT COUNT(*) FROM sets st
+                     JOIN session_exercises se ON se.id = st.session_exercise_id
+                     WHERE se.session_id = s.id AND counts_for_volume(st.set_type)),
+                    (SELECT COALESCE(SUM(st.weight_kg * st.reps), 0) FROM sets st
+                     JOIN session_exercises se ON se.id = st.session_exercise_id
+                     WHERE se.session_id = s.id AND counts_for_volume(st.set_type) AND st.weight_kg > 0)
+             FROM sessions s
+             LEFT JOIN templates t ON t.id = s.template_id
+             WHERE s.user_id = ?1 AND s.status = 'completed'
+               AND julianday(s.started_at) >= julianday(?2)
+               AND julianday(s.started_at) < julianday(?3)
+             ORDER BY julianday(s.started_at), s.id",
+        )?;
+        let sessions = stmt
+            .query_map(rusqlite::params![user_id, from, to], |row| {
+                let started_at: String = row.get(2)?;
+                Ok(DigestSession {
+                    session_id: row.get(0)?,
+                    name: row.get(1)?,
+                    date: calendar.date_of(&started_at).map(|d| d.to_string()).unwrap_or(started_at),
+                    working_sets: row.get(3)?,
+                    volume_kg: round1(row.get(4)?),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT datetime(started_at) FROM sessions
+             WHERE user_id = ?1 AND status = 'completed' AND julianday(started_at) < julianday(?2)",
+        )?;
+        let starts = stmt
+            .query_map(rusqlite::params![user_id, to], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        (sessions, starts)
+    };
+
+    let weeks: BTreeSet<NaiveDate> = session_starts
+        .iter()
+        .filter_map(|s| calendar.date_of(s))
+        .map(|d| calendar.week_of(d))
+        .collect();
+
+    let mut muscle_groups: Vec<MuscleGroupSets> = Vec::new();
+    let volume = analytics::weekly_volume(
+        db,
+        user_id,
+        Some(&week_start.to_string()),
+        Some(&week_end.to_string()),
+    )?;
+    for row in volume {
+        match muscle_groups.iter_mut().find(|m| m.muscle_group == row.muscle_group) {
+            Some(m) => m.set_count += row.set_count,
+            None => muscle_groups.push(MuscleGroupSets { muscle_group: row.muscle_group, set_count: row.set_count }),
+        }
+    }
+    muscle_groups.sort_by(|a, b| b.set_count.cmp(&a.set_count).then(a.muscle_group.cmp(&b.muscle_group)));
+
+    // Later PRs in the week beat earlier ones; keep the last but report it
+    // against the best from before the week.
+    let mut prs: Vec<SessionPR> = Vec::new();
+    for session in &sessions {
+        for pr in analytics::session_prs_hit(db, user_id, session.session_id)? {
+            match prs.iter_mut().find(|p| p.exercise_id == pr.exercise_id) {
+                Some(p) => *p = SessionPR { previous_best: p.previous_best, ..pr },
+                None => prs.push(pr),
+            }
+        }
+    }
+
+    let mut movers = analytics::e1rm_movers(db, user_id, MOVER_DAYS)?;
+    movers.retain(|m| m.pct_change != 0.0);
+    movers.sort_by(|a, b| b.pct_change.abs().total_cmp(&a.pct_change.abs()));
+    movers.truncate(MAX_LISTED);
+    movers.sort_by(|a, b| b.pct_change.total_cmp(&a.pct_change));
+
+    let mut stale = analytics::stale_exercises(db, user_id, STALE_DAYS)?;
+    stale.truncate(MAX_LISTED);
+
+    Ok(Digest {
+        week_start: week_start.to_string(),
+        week_end: week_end.to_string(),
+        timezone: calendar.tz_name().to_string(),
+        working_sets: sessions.iter().map(|s| s.working_sets).sum(),
+        volume_kg: round1(sessions.iter().map(|s| s.volume_kg).sum()),
+        sessions,
+        muscle_groups,
+        prs,
+        movers,
+        stale,
+        streak: streak(&weeks, week_start),
+    })
+}
+
+// ── Rendering ──
+
+fn fmt_kg(kg: f64) -> String {
+    let kg = round1(kg);
+    if kg.fract() == 0.0 {
+        format!("{:.0}", kg)
+    } else {
+        format!("{:.1}", kg)
+    }
+}
+
+/// Whole kilograms with thousands separators.
+fn fmt_tonnage(kg: f64) -> String {
+    let digits = format!("{:.0}", kg.round());
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn plural(n: i64, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+fn fmt_date(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format("%a %-d %b").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+impl Digest {
+    pub fn title(&self) -> String {
+        match NaiveDate::parse_from_str(&self.week_start, "%Y-%m-%d") {
+            Ok(d) => format!("Week of {}", d.format("%-d %b %Y")),
+            Err(_) => format!("Week of {}", self.week_start),
+        }
+    }
+
+    fn headline(&self) -> String {
+        format!(
+            "{} · {} · {} kg",
+            plural(self.sessions.len() as i64, "session", "sessions"),
+            plural(self.working_sets, "working set", "working sets"),
+            fmt_tonnage(self.volume_kg)
+        )
+    }
+
+    fn streak_line(&self) -> String {
+        let Streak { current_weeks, longest_weeks } = self.streak;
+        if current_weeks == 0 {
+            format!("Streak broken this week (longest {})", plural(longest_weeks, "week", "weeks"))
+        } else if current_weeks == longest_weeks && current_weeks > 1 {
+            format!("Streak: {}, your longest yet", plural(current_weeks, "week", "weeks"))
+        } else {
+            format!(
+                "Streak: {} (longest {})",
+                plural(current_weeks, "week", "weeks"),
+                plural(longest_weeks, "week", "weeks")
+            )
+        }
+    }
+
+    fn session_line(s: &DigestSession) -> String {
+        format!(
+            "{} — {}: {}, {} kg",
+            fmt_date(&s.date),
+            s.name.as_deref().unwrap_or("Workout"),
+            plural(s.working_sets, "set", "sets"),
+            fmt_tonnage(s.volume_kg)
+        )
+    }
+
+    fn pr_line(pr: &SessionPR) -> String {
+        format!(
+            "{}: {} kg × {} (e1RM {}, was {})",
+            pr.exercise_name,
+            fmt_kg(pr.weight_kg),
+            pr.reps,
+            fmt_kg(pr.e1rm),
+            fmt_kg(pr.previous_best)
+        )
+    }
+
+    fn mover_line(m: &E1rmMover) -> String {
+        format!("{}: {:+.1}% (e1RM {} kg)", m.exercise_name, m.pct_change, fmt_kg(m.current_e1rm))
+    }
+
+    fn stale_line(s: &StaleExercise) -> String {
+        format!("{}: last trained {} days ago", s.exercise_name, s.days_ago)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}\n", self.title());
+        let _ = writeln!(out, "{}\n", self.headline());
+        let _ = writeln!(out, "{}", self.streak_line());
+
+        let mut list = |heading: &str, lines: Vec<String>| {
+            if !lines.is_empty() {
+                let _ = writeln!(out, "\n## {}\n", heading);
+                for line in lines {
+                    let _ = writeln!(out, "- {}", line);
+                }
+            }
+        };
+        list("Sessions", self.sessions.iter().map(Self::session_line).collect());
+        list(
+            "Sets by muscle group",
+            self.muscle_groups.iter().map(|m| format!("{}: {}", m.muscle_group, m.set_count)).collect(),
+        );
+        list("PRs", self.prs.iter().map(Self::pr_line).collect());
+        list("Movers", self.movers.iter().map(Self::mover_line).collect());
+        list("Going stale", self.stale.iter().map(Self::stale_line).collect());
+        out
+    }
+
+    /// A standalone HTML document, suitable as an email body.
+    pub fn to_html(&self) -> String {
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+             <body style=\"font-family: sans-serif; max-width: 40em\">\n<h1>{title}</h1>\n<p><strong>{}</strong></p>\n<p>{}</p>\n",
+            escape(&self.headline()),
+            escape(&self.streak_line()),
+            title = escape(&self.title()),
+        );
+
+        let mut list = |heading: &str, lines: Vec<String>| {
+            if !lines.is_empty() {
+                let _ = writeln!(out, "<h2>{}</h2>\n<ul>", escape(heading));
+                for line in lines {
+                    let _ = writeln!(out, "<li>{}</li>", escape(&line));
+                }
+                out.push_str("</ul>\n");
+            }
+        };
+        list("Sessions", self.sessions.iter().map(Self::session_line).collect());
+        list(
+            "Sets by muscle group",
+            self.muscle_groups.iter().map(|m| format!("{}: {}", m.muscle_group, m.set_count)).collect(),
+        );
+        list("PRs", self.prs.iter().map(Self::pr_line).collect());
+        list("Movers", self.movers.iter().map(Self::mover_line).collect());
+        list("Going stale", self.stale.iter().map(Self::stale_line).collect());
+        out.push_str("</body></html>\n");
+        out
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// ── Storage ──
+
+fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<DigestRecord> {
+    let summary: String = row.get(5)?;
+    let digest = serde_json::from_str(&summary).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(5, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(DigestRecord {
+        id: row.get(0)?,
+        week_start: row.get(1)?,
+        timezone: row.get(2)?,
+        created_at: row.get(3)?,
+        emailed_at: row.get(4)?,
+        digest,
+    })
+}
+
+const SELECT_DIGEST: &str =
+    "SELECT id, week_start, timezone, created_at, emailed_at, summary FROM digests";
+
+/// Stored digests, newest week first.
+pub fn list(db: &DbPool, user_id: i64, limit: i64) -> Result<Vec<DigestRecord>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(&format!(
+        "{} WHERE user_id = ?1 ORDER BY week_start DESC LIMIT ?2",
+        SELECT_DIGEST
+    ))?;
+    let digests = stmt
+        .query_map(rusqlite::params![user_id, limit], row_to_record)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(digests)
+}
+
+pub fn get(db: &DbPool, user_id: i64, id: i64) -> Result<DigestRecord, AppError> {
+    let conn = db.lock().unwrap();
+    conn.query_row(
+        &format!("{} WHERE id = ?1 AND user_id = ?2", SELECT_DIGEST),
+        rusqlite::params![id, user_id],
+        row_to_record,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound,
+        e => AppError::Database(e),
+    })
+}
+
+/// A stored digest rendered as `markdown` or `html`.
+pub fn rendered(db: &DbPool, user_id: i64, id: i64, format: &str) -> Result<String, AppError> {
+    let column = match format {
+        "markdown" => "markdown",
+        "html" => "html",
+        _ => return Err(AppError::BadRequest(format!("Unknown digest format: {}", format))),
+    };
+    let conn = db.lock().unwrap();
+    conn.query_row(
+        &format!("SELECT {} FROM digests WHERE id = ?1 AND user_id = ?2", column),
+        rusqlite::params![id, user_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound,
+        e => AppError::Database(e),
+    })
+}
+
+/// Build and store the digest for the week containing `week_start` (default:
+/// last full week in the user's timezone), replacing any earlier version, and
+/// send it to `digest.ready` webhooks.
+pub fn generate(db: &DbPool, user_id: i64, week_start: Option<&str>) -> Result<DigestRecord, AppError> {
+    let calendar = Calendar::load(&db.lock().unwrap(), user_id)?;
+    let week_start = match week_start {
+        Some(week) => parse_week(&calendar, week)?,
+        None => last_week_start(&calendar, Utc::now()),
+    };
+    let id = store(db, user_id, &calendar, week_start)?;
+    get(db, user_id, id)
+}
+
+fn store(db: &DbPool, user_id: i64, calendar: &Calendar, week_start: NaiveDate) -> Result<i64, AppError> {
+    let digest = build(db, user_id, calendar, week_start)?;
+    let summary = serde_json::to_string(&digest).expect("digest serializes");
+
+    let conn = db.lock().unwrap();
+    let id: i64 = conn.query_row(
+        "INSERT INTO digests (user_id, week_start, timezone, summary, markdown, html)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(user_id, week_start) DO UPDATE SET
+            timezone = excluded.timezone, summary = excluded.summary,
+            markdown = excluded.markdown, html = excluded.html,
+            created_at = datetime('now')
+         RETURNING id",
+        rusqlite::params![
+            user_id,
+            digest.week_start,
+            digest.timezone,
+            summary,
+            digest.to_markdown(),
+            digest.to_html()
+        ],
+        |row| row.get(0),
+    )?;
+    webhooks::enqueue(
+        &conn,
+        user_id,
+        webhooks::DIGEST_READY,
+        &json!({ "digest_id": id, "digest": digest }),
+    )?;
+    Ok(id)
+}
+
+/// Generate last week's digest for every user whose local week has rolled
+/// over since, who hasn't turned digests off and who has trained at some
+/// point before. Returns how many were generated.
+pub fn generate_due(db: &DbPool, now: DateTime<Utc>) -> Result<usize, AppError> {
+    let users: Vec<i64> = {
+        let conn = db.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT u.id FROM users u
+             LEFT JOIN user_preferences d ON d.user_id = u.id AND d.key = ?1
+             WHERE COALESCE(d.value, 'on') != 'off'
+               AND EXISTS (SELECT 1 FROM sessions s WHERE s.user_id = u.id AND s.status = 'completed')
+             ORDER BY u.id",
+        )?;
+        let users = stmt
+            .query_map([preferences::WEEKLY_DIGEST_KEY], |row| row.get(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        users
+    };
+
+    let mut generated = 0;
+    for user_id in users {
+        let (calendar, due) = {
+            let conn = db.lock().unwrap();
+            let calendar = Calendar::load(&conn, user_id)?;
+            let week_start = last_week_start(&calendar, now);
+            let week_end = sql_datetime(calendar.midnight(week_start + Duration::days(7)));
+            let due = conn.query_row(
+                "SELECT NOT EXISTS (SELECT 1 FROM digests WHERE user_id = ?1 AND week_start = ?2)
+                    AND EXISTS (SELECT 1 FROM sessions WHERE user_id = ?1 AND status = 'completed'
+                                AND julianday(started_at) < julianday(?3))",
+                rusqlite::params![user_id, week_start.to_string(), week_end],
+                |row| row.get::<_, bool>(0),
+            )?;
+            (calendar, due.then_some(week_start))
+        };
+        if let Some(week_start) = due {
+            store(db, user_id, &calendar, week_start)?;
+            generated += 1;
+        }
+    }
+    Ok(generated)
+}
+
+/// Recent digests not yet emailed, for users who chose email delivery and
+/// have an address.
+pub fn pending_emails(db: &DbPool, limit: i64) -> Result<Vec<DigestEmail>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(
+        "SELECT d.id, u.email, d.summary, d.markdown, d.html
+         FROM digests d
+         JOIN users u ON u.id = d.user_id
+         JOIN user_preferences p ON p.user_id = d.user_id AND p.key = ?1 AND p.value = 'email'
+         WHERE d.emailed_at IS NULL AND u.email IS NOT NULL AND u.email != ''
+           AND d.created_at >= datetime('now', ?2)
+         ORDER BY d.id
+         LIMIT ?3",
+    )?;
+    let window = format!("-{} days", EMAIL_WINDOW_DAYS);
+    let emails = stmt
+        .query_map(rusqlite::params![preferences::WEEKLY_DIGEST_KEY, window, limit], |row| {
+            let summary: String = row.get(2)?;
+            let subject = serde_json::from_str::<Digest>(&summary)
+                .map(|d| format!("Your training week: {}", d.title()))
+                .unwrap_or_else(|_| "Your training week".to_string());
+            Ok(DigestEmail {
+                id: row.get(0)?,
+                to: row.get(1)?,
+                subject,
+                markdown: row.get(3)?,
+                html: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(emails)
+}
+
+pub fn mark_emailed(db: &DbPool, id: i64) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    conn.execute("UPDATE digests SET emailed_at = datetime('now') WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_memory_db;
+    use chrono::TimeZone;
+
+    fn seeded_db() -> DbPool {
+        let db = init_memory_db().unwrap();
+        {
+            let conn = db.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO users (id, username, password_hash, email) VALUES (900, 'digest', 'x', 'lifter@example.com');
+                 INSERT INTO exercises (id, user_id, name, muscle_group) VALUES (900, 900, 'Bench <Press>', 'Chest');
+                 INSERT INTO exercises (id, user_id, name, muscle_group) VALUES (901, 900, 'Squat', 'Legs');
+                 -- Two weeks before, the week before, then the digest week (Mon 5 Oct 2026).
+                 INSERT INTO sessions (id, user_id, name, started_at, status) VALUES
+                    (900, 900, 'Push', '2026-09-22 18:00:00', 'completed'),
+                    (901, 900, 'Push', '2026-09-29 18:00:00', 'completed'),
+                    (902, 900, 'Push', '2026-10-06 18:00:00', 'completed'),
+                    (903, 900, 'Legs', '2026-10-08 18:00:00', 'completed'),
+                    -- Sunday 23:30 in New York is Monday in UTC.
+                    (904, 900, 'Late', '2026-10-12 03:30:00', 'completed');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES
+                    (900, 900, 900, 0), (901, 901, 900, 0), (902, 902, 900, 0), (903, 903, 901, 0), (904, 904, 901, 0);
+                 INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, completed_at) VALUES
+                    (900, 1, 90, 5, '2026-09-22 18:05:00'),
+                    (901, 1, 95, 5, '2026-09-29 18:05:00'),
+                    (902, 1, 100, 5, '2026-10-06 18:05:00'),
+                    (902, 2, 100, 4, '2026-10-06 18:10:00'),
+                    (903, 1, 140, 5, '2026-10-08 18:05:00'),
+                    (904, 1, 60, 10, '2026-10-12 03:35:00');",
+            )
+            .unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn builds_and_renders_a_week() {
+        let db = seeded_db();
+        let monday = NaiveDate::from_ymd_opt(2026, 10, 5).unwrap();
+        let digest = build(&db, 900, &Calendar::default(), monday).unwrap();
+        assert_eq!(digest.week_end, "2026-10-11");
+        assert_eq!(digest.sessions.len(), 2);
+        assert_eq!(digest.working_sets, 3);
+        assert_eq!(digest.volume_kg, 1600.0);
+        assert_eq!(digest.muscle_groups[0].muscle_group, "Chest");
+        assert_eq!(digest.muscle_groups[0].set_count, 2);
+        assert_eq!(digest.prs.len(), 1);
+        assert_eq!(digest.prs[0].exercise_id, 900);
+        assert_eq!(digest.streak.current_weeks, 3);
+        assert_eq!(digest.streak.longest_weeks, 3);
+
+        let markdown = digest.to_markdown();
+        assert!(markdown.starts_with("# Week of 5 Oct 2026\n"));
+        assert!(markdown.contains("2 sessions · 3 working sets · 1,600 kg"));
+        assert!(markdown.contains("- Bench <Press>: 100 kg × 5"));
+        let html = digest.to_html();
+        assert!(html.contains("<li>Bench &lt;Press&gt;: 100 kg × 5"));
+        assert!(!html.contains("<Press>"));
+
+        // In New York the Sunday-night session belongs to this week...
+        let new_york = Calendar { tz: "America/New_York".parse().unwrap(), ..Calendar::default() };
+        let digest = build(&db, 900, &new_york, monday).unwrap();
+        assert_eq!(digest.sessions.len(), 3);
+        assert_eq!(digest.sessions[2].date, "2026-10-11");
+        // ...unless weeks start on Sunday.
+        let sunday_weeks = Calendar { week_start: chrono::Weekday::Sun, ..new_york };
+        let sunday = NaiveDate::from_ymd_opt(2026, 10, 4).unwrap();
+        let digest = build(&db, 900, &sunday_weeks, sunday).unwrap();
+        assert_eq!(digest.week_end, "2026-10-10");
+        assert_eq!(digest.sessions.len(), 2);
+    }
+
+    #[test]
+    fn generates_once_per_local_week_and_notifies() {
+        let db = seeded_db();
+        preferences::set_preference(&db, 900, preferences::TIMEZONE_KEY, "Pacific/Auckland").unwrap();
+        preferences::set_preference(&db, 900, preferences::WEEKLY_DIGEST_KEY, "email").unwrap();
+        crate::webhooks::create(
+            &db,
+            900,
+            &crate::models::CreateWebhook {
+                url: "https://example.com/hook".into(),
+                events: vec![webhooks::DIGEST_READY.into()],
+                description: None,
+            },
+        )
+        .unwrap();
+
+        // Sunday 11:00 UTC is already Monday in Auckland.
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 11, 0, 0).unwrap();
+        assert_eq!(generate_due(&db, now).unwrap(), 1);
+        assert_eq!(generate_due(&db, now).unwrap(), 0);
+
+        let history = list(&db, 900, 10).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].week_start, "2026-10-12");
+        assert_eq!(history[0].timezone, "Pacific/Auckland");
+        assert_eq!(crate::webhooks::list(&db, 900).unwrap()[0].pending, 1);
+
+        let emails = pending_emails(&db, 10).unwrap();
+        assert_eq!(emails.len(), 1);
+        assert_eq!(emails[0].to, "lifter@example.com");
+        assert_eq!(emails[0].subject, "Your training week: Week of 12 Oct 2026");
+        mark_emailed(&db, emails[0].id).unwrap();
+        assert!(pending_emails(&db, 10).unwrap().is_empty());
+
+        preferences::set_preference(&db, 900, preferences::WEEKLY_DIGEST_KEY, "off").unwrap();
+        let next_week = now + Duration::days(7);
+        assert_eq!(generate_due(&db, next_week).unwrap(), 0);
+        assert!(preferences::set_preference(&db, 900, preferences::TIMEZONE_KEY, "Mars/Olympus").is_err());
+    }
+}
@@ -1,8 +1,9 @@
 use crate::db::DbPool;
 use crate::error::AppError;
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ExportMeta {
     pub session_count: i64,
     pub set_count: i64,
@@ -122,6 +122,7 @@ pub fn register_with_invite(
     code: &str,
     username: &str,
     password: &str,
+    session_lifetime_days: u32,
 ) -> Result<AuthResponse, AppError> {
     let username = validate_username(username)?;
     validate_password(password)?;
@@ -171,7 +172,7 @@ pub fn register_with_invite(
         }
 
         seed_exercises(&conn, user_id)?;
-        let token = create_auth_session(&conn, user_id)?;
+        let token = create_auth_session(&conn, user_id, session_lifetime_days)?;
         Ok(AuthResponse { token, user_id })
     })();
 
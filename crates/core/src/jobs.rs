@@ -0,0 +1,225 @@
+//! Persisted state for the server's background jobs.
+//!
+//! The scheduler lives in the server; this module only records when each job
+//! last ran, how it went and when it is next due, so the state survives
+//! restarts and `lw-admin jobs` can show it (and request a run) from outside
+//! the process.
+
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::Job;
+
+/// Longest retry delay after consecutive failures, regardless of interval.
+const MAX_RETRY_SECS: i64 = 60 * 60;
+
+/// Add the job if it is new and update its interval if it changed. A new job
+/// is due immediately; an existing job keeps its schedule.
+pub fn register(db: &DbPool, name: &str, interval_secs: i64) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    conn.execute(
+        "INSERT INTO jobs (name, interval_secs) VALUES (?1, ?2)
+         ON CONFLICT(name) DO UPDATE SET interval_secs = excluded.interval_secs",
+        rusqlite::params![name, interval_secs],
+    )?;
+    Ok(())
+}
+
+pub fn list(db: &DbPool) -> Result<Vec<Job>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(
+        "SELECT name, interval_secs, next_run_at, last_started_at, last_finished_at,
+                last_status, last_message, last_duration_ms, run_count, failure_count,
+                consecutive_failures, run_requested
+         FROM jobs ORDER BY name",
+    )?;
+    let jobs = stmt
+        .query_map([], |row| {
+            Ok(Job {
+                name: row.get(0)?,
+                interval_secs: row.get(1)?,
+                next_run_at: row.get(2)?,
+                last_started_at: row.get(3)?,
+                last_finished_at: row.get(4)?,
+                last_status: row.get(5)?,
+                last_message: row.get(6)?,
+                last_duration_ms: row.get(7)?,
+                run_count: row.get(8)?,
+                failure_count: row.get(9)?,
+                consecutive_failures: row.get(10)?,
+                run_requested: row.get(11)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(jobs)
+}
+
+/// Names of jobs that should run now: explicitly requested, or scheduled
+/// (interval > 0) and past `next_run_at`. Each returned job is marked started
+/// and its request flag cleared, so it is handed out once.
+pub fn claim_due(db: &DbPool) -> Result<Vec<String>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(
+        "UPDATE jobs SET run_requested = 0, last_started_at = datetime('now')
+         WHERE run_requested = 1
+            OR (interval_secs > 0 AND next_run_at <= datetime('now'))
+         RETURNING name",
+    )?;
+    let mut names = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    names.sort();
+    Ok(names)
+}
+
+/// Record the outcome of a run and schedule the next one. After a failure the
+/// job is retried sooner than its interval, backing off exponentially from a
+/// minute up to an hour.
+pub fn record_run(
+    db: &DbPool,
+    name: &str,
+    outcome: Result<&str, &str>,
+    duration_ms: i64,
+) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    match outcome {
+        Ok(message) => conn.execute(
+            "UPDATE jobs SET
+                last_finished_at = datetime('now'),
+                last_status = 'ok',
+                last_message = ?2,
+                last_duration_ms = ?3,
+                run_count = run_count + 1,
+                consecutive_failures = 0,
+                next_run_at = datetime('now', '+' || MAX(interval_secs, 0) || ' seconds')
+             WHERE name = ?1",
+            rusqlite::params![name, message, duration_ms],
+        )?,
+        Err(error) => conn.execute(
+            "UPDATE jobs SET
+                last_finished_at = datetime('now'),
+                last_status = 'failed',
+                last_message = ?2,
+                last_duration_ms = ?3,
+                run_count = run_count + 1,
+                failure_count = failure_count + 1,
+                consecutive_failures = consecutive_failures + 1,
+                next_run_at = datetime('now', '+' || MIN(
+                    MAX(interval_secs, 60),
+                    ?4,
+                    60 << MIN(consecutive_failures, 6)
+                ) || ' seconds')
+             WHERE name = ?1",
+            rusqlite::params![name, error, duration_ms, MAX_RETRY_SECS],
+        )?,
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(db: &DbPool, name: &str) -> Job {
+        list(db)
+            .unwrap()
+            .into_iter()
+            .find(|j| j.name == name)
+            .unwrap()
+    }
+
+    /// Seconds between the last recorded run and the next one.
+    fn due_in(db: &DbPool, name: &str) -> i64 {
+        db.lock()
+            .unwrap()
+            .query_row(
+                "SELECT unixepoch(next_run_at) - unixepoch(last_finished_at)
+                 FROM jobs WHERE name = ?1",
+                [name],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn new_jobs_are_due_and_claimed_once() {
+        let db = crate::db::init_memory_db().unwrap();
+        register(&db, "hourly", 3600).unwrap();
+        register(&db, "manual", 0).unwrap();
+        assert_eq!(claim_due(&db).unwrap(), ["hourly"]);
+        assert!(job(&db, "hourly").last_started_at.is_some());
+        // Recording the run moves it on to its next interval.
+        record_run(&db, "hourly", Ok("done"), 5).unwrap();
+        assert!(claim_due(&db).unwrap().is_empty());
+
+        // A manual-only job runs when requested, once.
+        db.lock()
+            .unwrap()
+            .execute(
+                "UPDATE jobs SET run_requested = 1 WHERE name = 'manual'",
+                [],
+            )
+            .unwrap();
+        assert_eq!(claim_due(&db).unwrap(), ["manual"]);
+        assert!(!job(&db, "manual").run_requested);
+        assert!(claim_due(&db).unwrap().is_empty());
+    }
+
+    #[test]
+    fn re_registering_keeps_the_schedule() {
+        let db = crate::db::init_memory_db().unwrap();
+        register(&db, "hourly", 3600).unwrap();
+        claim_due(&db).unwrap();
+        record_run(&db, "hourly", Ok("done"), 5).unwrap();
+        let next = job(&db, "hourly").next_run_at;
+
+        register(&db, "hourly", 600).unwrap();
+        let again = job(&db, "hourly");
+        assert_eq!(again.interval_secs, 600);
+        assert_eq!(again.next_run_at, next);
+    }
+
+    #[test]
+    fn success_schedules_the_interval() {
+        let db = crate::db::init_memory_db().unwrap();
+        register(&db, "hourly", 3600).unwrap();
+        record_run(&db, "hourly", Ok("closed 2 session(s)"), 12).unwrap();
+        let hourly = job(&db, "hourly");
+        assert_eq!(hourly.last_status.as_deref(), Some("ok"));
+        assert_eq!(hourly.last_message.as_deref(), Some("closed 2 session(s)"));
+        assert_eq!((hourly.last_duration_ms, hourly.run_count), (Some(12), 1));
+        assert_eq!(due_in(&db, "hourly"), 3600);
+    }
+
+    #[test]
+    fn failures_back_off_up_to_the_interval_or_an_hour() {
+        let db = crate::db::init_memory_db().unwrap();
+        register(&db, "daily", 24 * 60 * 60).unwrap();
+        register(&db, "often", 90).unwrap();
+
+        // 60s, doubling per consecutive failure, capped at an hour.
+        for expected in [60, 120, 240, 480, 960, 1920, 3600, 3600] {
+            record_run(&db, "daily", Err("disk full"), 1).unwrap();
+            assert_eq!(due_in(&db, "daily"), expected);
+        }
+        let daily = job(&db, "daily");
+        assert_eq!((daily.failure_count, daily.consecutive_failures), (8, 8));
+        assert_eq!(daily.last_message.as_deref(), Some("disk full"));
+
+        // Never later than the job would have run anyway.
+        record_run(&db, "often", Err("busy"), 1).unwrap();
+        record_run(&db, "often", Err("busy"), 1).unwrap();
+        assert_eq!(due_in(&db, "often"), 90);
+
+        // A success clears the streak but not the total.
+        record_run(&db, "daily", Ok("done"), 1).unwrap();
+        let daily = job(&db, "daily");
+        assert_eq!(
+            (
+                daily.failure_count,
+                daily.consecutive_failures,
+                daily.run_count
+            ),
+            (8, 0, 9)
+        );
+    }
+}
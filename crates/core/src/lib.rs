@@ -1,12 +1,22 @@
 pub mod analytics;
 pub mod auth;
 pub mod beta;
+pub mod bodyweight;
+pub mod calendar;
+pub mod changes;
 pub mod db;
+pub mod digest;
 pub mod error;
 pub mod exercises;
 pub mod export;
 pub mod invites;
+pub mod jobs;
 pub mod models;
 pub mod preferences;
+pub mod rest_timer;
+pub mod session_events;
+pub mod session_report;
 pub mod sessions;
 pub mod templates;
+pub mod timestamp;
+pub mod webhooks;
@@ -1,8 +1,11 @@
+use chrono::{DateTime, Utc};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 // ── Exercises ──
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Exercise {
     pub id: i64,
     pub name: String,
@@ -13,7 +16,7 @@ pub struct Exercise {
     pub created_at: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateExercise {
     pub name: String,
     pub muscle_group: Option<String>,
@@ -21,7 +24,7 @@ pub struct CreateExercise {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateExercise {
     pub name: Option<String>,
     pub muscle_group: Option<String>,
@@ -31,7 +34,7 @@ pub struct UpdateExercise {
 
 // ── Templates ──
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Template {
     pub id: i64,
     pub name: String,
@@ -43,7 +46,7 @@ pub struct Template {
     pub exercises: Vec<TemplateExercise>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TemplateSnapshot {
     pub id: i64,
     pub template_id: i64,
@@ -52,7 +55,7 @@ pub struct TemplateSnapshot {
     pub created_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct TemplateExercise {
     pub id: i64,
     pub exercise_id: i64,
@@ -65,14 +68,14 @@ pub struct TemplateExercise {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateTemplate {
     pub name: String,
     pub notes: Option<String>,
     pub exercises: Vec<CreateTemplateExercise>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateTemplateExercise {
     pub exercise_id: i64,
     pub position: i32,
@@ -83,7 +86,7 @@ pub struct CreateTemplateExercise {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateTemplate {
     pub name: Option<String>,
     pub notes: Option<String>,
@@ -96,7 +99,7 @@ pub struct UpdateTemplate {
 /// name (resolved against the user's catalog, auto-created if absent) — the
 /// client has no server exercise ids for freshly-created movements, mirroring
 /// SyncExercise. `version` is advisory only; the server owns version numbers.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SyncTemplate {
     pub name: String,
     pub notes: Option<String>,
@@ -105,7 +108,7 @@ pub struct SyncTemplate {
     pub exercises: Vec<SyncTemplateExercise>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SyncTemplateExercise {
     pub name: String,
     pub position: i32,
@@ -120,7 +123,7 @@ pub struct SyncTemplateExercise {
 /// or unchanged — keyed by name. This is the id-mapping channel: the client
 /// matches responses to its local templates by name and adopts the server
 /// (id, version) before stamping and pushing sessions.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TemplateSyncResult {
     pub templates: Vec<Template>,
     pub exercises_created: Vec<String>,
@@ -128,37 +131,179 @@ pub struct TemplateSyncResult {
 
 // ── Sessions ──
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Where a session is in its lifecycle. Stored lowercase; the `sessions`
+/// table rejects anything else.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionStatus {
+    Active,
+    Paused,
+    /// Finished. The only status PRs, digests and the e1RM-based analytics
+    /// (progression, movers, summary, rep maxes, plateaus, strength scores)
+    /// look at; volume and the activity heatmaps count every session.
+    Completed,
+    /// Given up. Kept for history but left out of PRs, digests and e1RM.
+    Abandoned,
+}
+
+impl SessionStatus {
+    pub const ALL: [SessionStatus; 4] =
+        [SessionStatus::Active, SessionStatus::Paused, SessionStatus::Completed, SessionStatus::Abandoned];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionStatus::Active => "active",
+            SessionStatus::Paused => "paused",
+            SessionStatus::Completed => "completed",
+            SessionStatus::Abandoned => "abandoned",
+        }
+    }
+
+    /// Completed or abandoned: the session has an `ended_at`.
+    pub fn is_finished(self) -> bool {
+        matches!(self, SessionStatus::Completed | SessionStatus::Abandoned)
+    }
+}
+
+/// How a set was performed. Stored lowercase; the `sets` table rejects
+/// anything else.
+///
+/// Analytics split sets two ways. Everything but warmups counts toward
+/// volume, set counts, training frequency and rest. Only working, failure,
+/// AMRAP and back-off sets estimate an e1RM and can set a PR: drop, cluster
+/// and myo-rep sets are either pre-fatigued or split by short rests, so
+/// their reps say little about a one-rep max.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SetType {
+    /// Preparation. Left out of all analytics.
+    Warmup,
+    /// A straight set at working weight.
+    #[default]
+    Working,
+    /// Weight stripped straight after another set. Volume only.
+    Drop,
+    /// Taken to failure.
+    Failure,
+    /// As many reps as possible at a set weight.
+    Amrap,
+    /// Lighter straight sets after the top set.
+    Backoff,
+    /// Reps broken up by short intra-set rests. Volume only.
+    Cluster,
+    /// Activation set followed by short mini-sets. Volume only.
+    Myorep,
+}
+
+impl SetType {
+    pub const ALL: [SetType; 8] = [
+        SetType::Warmup,
+        SetType::Working,
+        SetType::Drop,
+        SetType::Failure,
+        SetType::Amrap,
+        SetType::Backoff,
+        SetType::Cluster,
+        SetType::Myorep,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SetType::Warmup => "warmup",
+            SetType::Working => "working",
+            SetType::Drop => "drop",
+            SetType::Failure => "failure",
+            SetType::Amrap => "amrap",
+            SetType::Backoff => "backoff",
+            SetType::Cluster => "cluster",
+            SetType::Myorep => "myorep",
+        }
+    }
+
+    /// Counts toward volume, set counts, frequency and rest analytics.
+    pub fn counts_for_volume(self) -> bool {
+        self != SetType::Warmup
+    }
+
+    /// Gives an e1RM estimate, so can set a PR.
+    pub fn counts_for_e1rm(self) -> bool {
+        matches!(self, SetType::Working | SetType::Failure | SetType::Amrap | SetType::Backoff)
+    }
+}
+
+/// `Display`, `FromStr` and SQLite text conversions through `as_str`.
+macro_rules! sql_text_enum {
+    ($name:ident, $all:expr) => {
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = String;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $all.into_iter()
+                    .find(|v| v.as_str() == s)
+                    .ok_or_else(|| format!("invalid {}: '{}'", stringify!($name), s))
+            }
+        }
+
+        impl ToSql for $name {
+            fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+                Ok(ToSqlOutput::from(self.as_str()))
+            }
+        }
+
+        impl FromSql for $name {
+            fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+                value.as_str()?.parse().map_err(|e: String| FromSqlError::Other(e.into()))
+            }
+        }
+    };
+}
+
+sql_text_enum!(SessionStatus, SessionStatus::ALL);
+sql_text_enum!(SetType, SetType::ALL);
+
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Session {
     pub id: i64,
     pub template_id: Option<i64>,
     pub template_name: Option<String>,
     pub name: Option<String>,
-    pub started_at: String,
-    pub ended_at: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
     pub paused_duration: i64,
     pub notes: Option<String>,
-    pub status: String,
+    pub status: SessionStatus,
     pub template_version: Option<i64>,
+    /// Closed by the server's stale-session policy rather than the user.
+    pub auto_closed: bool,
+    /// `idle` or `empty` when `auto_closed`.
+    pub auto_close_reason: Option<String>,
     pub exercises: Vec<SessionExerciseWithSets>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct SessionSummary {
     pub id: i64,
     pub template_id: Option<i64>,
     pub template_name: Option<String>,
     pub name: Option<String>,
-    pub started_at: String,
-    pub ended_at: Option<String>,
-    pub status: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub status: SessionStatus,
     pub set_count: i64,
     pub exercise_count: i64,
     pub target_set_count: Option<i64>,
     pub template_version: Option<i64>,
+    pub auto_closed: bool,
+    pub auto_close_reason: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct SessionExerciseWithSets {
     pub id: i64,
     pub exercise_id: i64,
@@ -168,84 +313,90 @@ pub struct SessionExerciseWithSets {
     pub sets: Vec<Set>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema)]
 pub struct Set {
     pub id: i64,
     pub session_exercise_id: i64,
     pub set_number: i32,
     pub weight_kg: Option<f64>,
     pub reps: i32,
-    pub set_type: String,
+    pub set_type: SetType,
     pub rir: Option<i32>,
-    pub completed_at: String,
+    pub completed_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateSession {
     pub template_id: Option<i64>,
     pub name: Option<String>,
-    pub started_at: Option<String>,
-    pub ended_at: Option<String>,
-    pub status: Option<String>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_option")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_option")]
+    pub ended_at: Option<DateTime<Utc>>,
+    pub status: Option<SessionStatus>,
     pub notes: Option<String>,
     pub paused_duration: Option<i64>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateSession {
-    pub status: Option<String>,
+    pub status: Option<SessionStatus>,
     pub notes: Option<String>,
     pub paused_duration: Option<i64>,
-    pub started_at: Option<String>,
-    pub ended_at: Option<String>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_option")]
+    pub started_at: Option<DateTime<Utc>>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_option")]
+    pub ended_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct AddSessionExercise {
     pub exercise_id: i64,
     pub position: Option<i32>,
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateSessionExercise {
     pub position: Option<i32>,
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateSet {
     pub weight_kg: Option<f64>,
     pub reps: i32,
-    pub set_type: Option<String>,
+    pub set_type: Option<SetType>,
     pub rir: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateSet {
     pub weight_kg: Option<f64>,
     pub reps: Option<i32>,
-    pub set_type: Option<String>,
+    pub set_type: Option<SetType>,
     pub rir: Option<i32>,
 }
 
 // ── Sync ──
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SyncSession {
     pub name: Option<String>,
     pub template_id: Option<i64>,
     pub template_version: Option<i64>,
-    pub started_at: String,
-    pub ended_at: Option<String>,
+    #[serde(deserialize_with = "crate::timestamp::deserialize")]
+    pub started_at: DateTime<Utc>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_option")]
+    pub ended_at: Option<DateTime<Utc>>,
     pub paused_duration: Option<i64>,
-    pub status: Option<String>,
+    pub status: Option<SessionStatus>,
     pub notes: Option<String>,
     #[serde(default)]
     pub exercises: Vec<SyncExercise>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SyncExercise {
     pub name: String,
     pub position: i32,
@@ -254,16 +405,17 @@ pub struct SyncExercise {
     pub sets: Vec<SyncSet>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SyncSet {
     pub weight_kg: Option<f64>,
     pub reps: i32,
-    pub set_type: Option<String>,
+    pub set_type: Option<SetType>,
     pub rir: Option<i32>,
-    pub completed_at: Option<String>,
+    #[serde(default, deserialize_with = "crate::timestamp::deserialize_option")]
+    pub completed_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SyncResult {
     pub pushed: Vec<Session>,
     pub skipped: i64,
@@ -272,7 +424,7 @@ pub struct SyncResult {
 
 // ── Import ──
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ImportSession {
     pub template: Option<String>,
     pub date: String,
@@ -280,21 +432,21 @@ pub struct ImportSession {
     pub exercises: Vec<ImportExercise>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ImportExercise {
     pub name: String,
     pub notes: Option<String>,
     pub sets: Vec<ImportSet>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct ImportSet {
     pub weight_kg: Option<f64>,
     pub reps: i32,
-    pub set_type: Option<String>,
+    pub set_type: Option<SetType>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ImportResult {
     pub sessions: Vec<Session>,
     pub exercises_created: Vec<String>,
@@ -303,13 +455,13 @@ pub struct ImportResult {
 
 // ── Auth ──
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub username: String,
     pub password: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RegisterRequest {
     pub username: String,
     pub password: String,
@@ -317,12 +469,12 @@ pub struct RegisterRequest {
     pub email: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct GoogleAuthRequest {
     pub id_token: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
     pub token: String,
     pub user_id: i64,
@@ -330,7 +482,7 @@ pub struct AuthResponse {
 
 // ── Invites ──
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Invite {
     pub id: i64,
     pub code: String,
@@ -339,20 +491,20 @@ pub struct Invite {
     pub used_at: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct InviteList {
     pub quota: i64,
     pub used_count: i64,
     pub invites: Vec<Invite>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct InviteValidation {
     pub valid: bool,
     pub invited_by: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct JoinRequest {
     pub username: String,
     pub password: String,
@@ -360,14 +512,14 @@ pub struct JoinRequest {
 
 // ── Beta ──
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BetaSignupRequest {
     pub id_token: String,
     pub platform: String,
     pub referrer: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BetaRegisterRequest {
     pub username: String,
     pub password: String,
@@ -376,20 +528,20 @@ pub struct BetaRegisterRequest {
     pub referrer: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct BetaJoinRequest {
     pub email: String,
     pub platform: String,
     pub referrer: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BetaJoinResponse {
     pub email: String,
     pub platform: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BetaSignupResponse {
     pub token: String,
     pub user_id: i64,
@@ -397,7 +549,7 @@ pub struct BetaSignupResponse {
     pub platform: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct BetaSignup {
     pub id: i64,
     pub email: String,
@@ -411,7 +563,8 @@ pub struct BetaSignup {
 
 // ── Query params ──
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SessionListParams {
     pub limit: Option<i64>,
     pub offset: Option<i64>,
@@ -421,14 +574,14 @@ pub struct SessionListParams {
 
 // ── History ──
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct ExerciseHistory {
     pub exercise_id: i64,
     pub exercise_name: String,
     pub sessions: Vec<ExerciseHistoryEntry>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct ExerciseHistoryEntry {
     pub session_id: i64,
     pub session_name: Option<String>,
@@ -436,8 +589,163 @@ pub struct ExerciseHistoryEntry {
     pub sets: Vec<Set>,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, ToSchema)]
 pub struct ExercisePreviousSets {
     pub exercise_id: i64,
     pub sets: Vec<Set>,
 }
+
+// ── Jobs ──
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Job {
+    pub name: String,
+    pub interval_secs: i64,
+    pub next_run_at: String,
+    pub last_started_at: Option<String>,
+    pub last_finished_at: Option<String>,
+    pub last_status: Option<String>,
+    pub last_message: Option<String>,
+    pub last_duration_ms: Option<i64>,
+    pub run_count: i64,
+    pub failure_count: i64,
+    pub consecutive_failures: i64,
+    pub run_requested: bool,
+}
+
+// ── Session timeline ──
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct SessionEvent {
+    pub id: i64,
+    /// `start`, `pause`, `resume`, `exercise_added`, `set_logged`, `end` or `reopen`.
+    pub kind: String,
+    pub at: String,
+    pub session_exercise_id: Option<i64>,
+    pub set_id: Option<i64>,
+    /// Final status for `end` (prefixed `auto:` when the server closed it).
+    pub detail: Option<String>,
+}
+
+/// A span during which the session was not running: paused, or ended and
+/// later reopened. `ended_at` is `None` while the pause is ongoing.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct PauseInterval {
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub seconds: i64,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct SessionTimeline {
+    pub session_id: i64,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+    pub events: Vec<SessionEvent>,
+    pub pauses: Vec<PauseInterval>,
+    /// Total paused seconds, derived from `pauses`.
+    pub paused_duration: i64,
+    /// Wall-clock seconds from start to end (or now) minus `paused_duration`.
+    pub active_duration: i64,
+}
+
+// ── Rest timer ──
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct RestTimer {
+    pub session_id: i64,
+    pub session_exercise_id: Option<i64>,
+    pub set_id: Option<i64>,
+    pub duration_seconds: i64,
+    pub started_at: String,
+    pub ends_at: String,
+    /// Seconds left as of `server_time`; 0 once done or skipped.
+    pub remaining_seconds: i64,
+    /// `running`, `done` or `skipped`.
+    pub state: String,
+    /// Server clock when this snapshot was taken, so clients can correct for
+    /// their own clock when counting down.
+    pub server_time: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AdjustRestTimer {
+    /// Seconds to add (negative to shorten), at most 3600 either way.
+    pub seconds: i64,
+}
+
+// ── Webhooks ──
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    pub events: Vec<String>,
+    pub description: Option<String>,
+    pub active: bool,
+    pub created_at: String,
+    /// Deliveries still waiting (including retries).
+    pub pending: i64,
+    /// Deliveries given up on after too many failed attempts.
+    pub failed: i64,
+}
+
+/// Returned once, on creation: the only time the signing secret is shown.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookCreated {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWebhook {
+    pub url: String,
+    pub events: Vec<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateWebhook {
+    pub url: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub description: Option<String>,
+    pub active: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub outbox_id: i64,
+    pub event: String,
+    pub attempted_at: String,
+    /// HTTP status, if the receiver answered.
+    pub status_code: Option<i64>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    /// Outbox state after this attempt: pending, delivered or failed.
+    pub outbox_status: String,
+}
+
+// ── Digests ──
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct GenerateDigest {
+    /// Any date in the week (YYYY-MM-DD); defaults to last full week in the
+    /// user's timezone.
+    pub week_start: Option<String>,
+}
+
+// ── Bodyweight ──
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Bodyweight {
+    /// Local date (YYYY-MM-DD)
+    pub measured_on: String,
+    pub weight_kg: f64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RecordBodyweight {
+    pub weight_kg: f64,
+}
@@ -1,13 +1,196 @@
+use lightweight_calc::plates::{self, PlateStock};
+use lightweight_calc::relative_strength::{Equipment, Sex};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::db::DbPool;
 use crate::error::AppError;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct E1rmSpiderPrefs {
     pub exercise_ids: Vec<i64>,
 }
 
+/// Minutes without a new set before an active session is completed for the
+/// user. Stored as a preference; `0` turns idle auto-close off.
+pub const STALE_SESSION_TIMEOUT_KEY: &str = "stale_session_timeout_minutes";
+pub const DEFAULT_STALE_SESSION_TIMEOUT_MINUTES: i64 = 120;
+/// Minutes a session with no sets may stay open before it is abandoned.
+pub const EMPTY_SESSION_TIMEOUT_KEY: &str = "empty_session_timeout_minutes";
+pub const DEFAULT_EMPTY_SESSION_TIMEOUT_MINUTES: i64 = 360;
+/// Upper bound for either timeout: one week.
+const MAX_SESSION_TIMEOUT_MINUTES: i64 = 7 * 24 * 60;
+/// IANA timezone name (e.g. `Europe/London`) that analytics days and weeks
+/// and the weekly digest follow (see [`crate::calendar`]). Defaults to UTC.
+pub const TIMEZONE_KEY: &str = "timezone";
+/// First day of the user's week, `monday` … `sunday`. Defaults to Monday.
+pub const WEEK_START_KEY: &str = "week_start";
+/// Weekly digest delivery: `off`, `on` (stored and sent to webhooks) or
+/// `email` (also emailed when the server has a mailer). Defaults to `on`.
+pub const WEEKLY_DIGEST_KEY: &str = "weekly_digest";
+pub const WEEKLY_DIGEST_MODES: [&str; 3] = ["off", "on", "email"];
+/// `male` or `female`; picks the relative-strength curves. No default.
+pub const SEX_KEY: &str = "sex";
+/// `raw` or `equipped`, for IPF GL. Defaults to `raw`.
+pub const EQUIPMENT_KEY: &str = "equipment";
+/// Which exercises count as the competition squat, bench press and deadlift,
+/// as JSON: `{"squat": 1, "bench": 4, "deadlift": 7}`. Any may be left out.
+pub const COMPETITION_LIFTS_KEY: &str = "competition_lifts";
+
+/// The bar, plates and dumbbells the user trains with, as JSON:
+/// `{"bar_kg": 20, "plates": [{"weight_kg": 25, "pairs": 4}], "dumbbells_kg": [10, 12.5]}`.
+/// Prescribed loads round to what this kit can make. Not to be confused
+/// with `equipment`, the raw/equipped division.
+pub const EQUIPMENT_PROFILE_KEY: &str = "equipment_profile";
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CompetitionLifts {
+    pub squat: Option<i64>,
+    pub bench: Option<i64>,
+    pub deadlift: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PlateCount {
+    /// A multiple of 0.25, up to 50
+    pub weight_kg: f64,
+    pub pairs: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EquipmentProfile {
+    #[serde(default = "default_bar_kg")]
+    pub bar_kg: f64,
+    /// Plates on hand, in pairs; empty leaves barbell loads unrounded
+    #[serde(default)]
+    pub plates: Vec<PlateCount>,
+    /// Dumbbells on hand, kg each; empty leaves dumbbell loads unrounded
+    #[serde(default)]
+    pub dumbbells_kg: Vec<f64>,
+}
+
+fn default_bar_kg() -> f64 {
+    20.0
+}
+
+/// An achievable load and, for a barbell, the plates for each side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AchievableLoad {
+    pub weight_kg: f64,
+    pub plates_per_side: Option<Vec<f64>>,
+}
+
+impl EquipmentProfile {
+    fn check(&self) -> Result<(), String> {
+        if !(0.0..=50.0).contains(&self.bar_kg) {
+            return Err(format!("bar_kg must be between 0 and 50, got {}", self.bar_kg));
+        }
+        if self.plates.len() > 16 {
+            return Err("at most 16 plate sizes".into());
+        }
+        for plate in &self.plates {
+            if !(plate.weight_kg > 0.0 && plate.weight_kg <= 50.0) {
+                return Err(format!("plate weight_kg must be above 0 and at most 50, got {}", plate.weight_kg));
+            }
+            if !plates::is_plate_weight(plate.weight_kg) {
+                return Err(format!(
+                    "plate weight_kg must be a multiple of {}, got {}",
+                    plates::PLATE_UNIT_KG,
+                    plate.weight_kg
+                ));
+            }
+            if !(1..=20).contains(&plate.pairs) {
+                return Err(format!("plate pairs must be between 1 and 20, got {}", plate.pairs));
+            }
+        }
+        if self.dumbbells_kg.len() > 100 {
+            return Err("at most 100 dumbbells".into());
+        }
+        if let Some(w) = self.dumbbells_kg.iter().find(|w| !(**w > 0.0 && **w <= 200.0)) {
+            return Err(format!("dumbbells_kg must be above 0 and at most 200, got {}", w));
+        }
+        Ok(())
+    }
+
+    /// Round `target_kg` to what this kit can make for an exercise with the
+    /// given `equipment` (the exercise field, e.g. `Barbell`, `Dumbbells`).
+    /// None when the kit doesn't cover that equipment, so the load stands.
+    pub fn achievable(&self, equipment: Option<&str>, target_kg: f64) -> Option<AchievableLoad> {
+        let equipment = equipment?.trim().to_lowercase();
+        if equipment == "barbell" && !self.plates.is_empty() {
+            let stock: Vec<PlateStock> =
+                self.plates.iter().map(|p| PlateStock { weight_kg: p.weight_kg, pairs: p.pairs }).collect();
+            let load = plates::load_barbell(target_kg, self.bar_kg, &stock)?;
+            return Some(AchievableLoad { weight_kg: load.total_kg, plates_per_side: Some(load.per_side) });
+        }
+        if (equipment == "dumbbell" || equipment == "dumbbells") && !self.dumbbells_kg.is_empty() {
+            let weight_kg = plates::nearest_dumbbell(target_kg, &self.dumbbells_kg)?;
+            return Some(AchievableLoad { weight_kg, plates_per_side: None });
+        }
+        None
+    }
+}
+
+/// The user's equipment profile, if one is set.
+pub fn equipment_profile(db: &DbPool, user_id: i64) -> Result<Option<EquipmentProfile>, AppError> {
+    Ok(get_preference(db, user_id, EQUIPMENT_PROFILE_KEY)?.and_then(|v| serde_json::from_str(&v).ok()))
+}
+
+/// Reject values the server interprets itself; other keys are opaque client state.
+fn validate(key: &str, value: &str) -> Result<(), AppError> {
+    if key == STALE_SESSION_TIMEOUT_KEY || key == EMPTY_SESSION_TIMEOUT_KEY {
+        match value.trim().parse::<i64>() {
+            Ok(minutes) if (0..=MAX_SESSION_TIMEOUT_MINUTES).contains(&minutes) => {}
+            _ => {
+                return Err(AppError::BadRequest(format!(
+                    "{} must be a whole number of minutes between 0 and {}",
+                    key, MAX_SESSION_TIMEOUT_MINUTES
+                )))
+            }
+        }
+    }
+    if key == TIMEZONE_KEY && value.parse::<chrono_tz::Tz>().is_err() {
+        return Err(AppError::BadRequest(format!(
+            "{} must be an IANA timezone name such as Europe/London",
+            key
+        )));
+    }
+    if key == WEEK_START_KEY && crate::calendar::parse_weekday(value).is_none() {
+        return Err(AppError::BadRequest(format!("{} must be a day of the week such as monday", key)));
+    }
+    if key == SEX_KEY && value.parse::<Sex>().is_err() {
+        return Err(AppError::BadRequest(format!("{} must be male or female", key)));
+    }
+    if key == EQUIPMENT_KEY && value.parse::<Equipment>().is_err() {
+        return Err(AppError::BadRequest(format!("{} must be raw or equipped", key)));
+    }
+    if key == COMPETITION_LIFTS_KEY {
+        serde_json::from_str::<CompetitionLifts>(value).map_err(|e| {
+            AppError::BadRequest(format!(
+                "{} must be a JSON object of exercise IDs by squat, bench and deadlift: {}",
+                key, e
+            ))
+        })?;
+    }
+    if key == EQUIPMENT_PROFILE_KEY {
+        serde_json::from_str::<EquipmentProfile>(value)
+            .map_err(|e| e.to_string())
+            .and_then(|profile| profile.check())
+            .map_err(|e| AppError::BadRequest(format!("{} is not a valid equipment profile: {}", key, e)))?;
+    }
+    if key == WEEKLY_DIGEST_KEY && !WEEKLY_DIGEST_MODES.contains(&value) {
+        return Err(AppError::BadRequest(format!(
+            "{} must be one of {}",
+            key,
+            WEEKLY_DIGEST_MODES.join(", ")
+        )));
+    }
+    Ok(())
+}
+
 pub fn get_preference(db: &DbPool, user_id: i64, key: &str) -> Result<Option<String>, AppError> {
     let conn = db.lock().unwrap();
     let result = conn.query_row(
@@ -23,6 +206,7 @@ pub fn get_preference(db: &DbPool, user_id: i64, key: &str) -> Result<Option<Str
 }
 
 pub fn set_preference(db: &DbPool, user_id: i64, key: &str, value: &str) -> Result<(), AppError> {
+    validate(key, value)?;
     let conn = db.lock().unwrap();
     conn.execute(
         "INSERT INTO user_preferences (user_id, key, value) VALUES (?1, ?2, ?3)
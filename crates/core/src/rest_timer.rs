@@ -0,0 +1,264 @@
+//! Server-held rest timer.
+//!
+//! Each open session has at most one timer. Logging a set (re)starts it with
+//! the template's `rest_seconds` for that exercise; exercises without a
+//! prescribed rest clear it. Every change is published as a
+//! [`ChangeEvent::RestTimer`] so all of the account's devices show the same
+//! countdown.
+
+use crate::changes::{Change, ChangeEvent};
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::RestTimer;
+
+/// Largest change one `adjust` may make, either way.
+pub const MAX_ADJUST_SECONDS: i64 = 60 * 60;
+
+const SELECT_TIMER: &str =
+    "SELECT session_id, session_exercise_id, set_id, duration_seconds, started_at,
+            datetime(started_at, '+' || duration_seconds || ' seconds'), skipped_at,
+            (julianday(started_at) - julianday('now')) * 86400.0 + duration_seconds,
+            datetime('now')
+     FROM rest_timers WHERE session_id = ?1";
+
+fn load(conn: &rusqlite::Connection, session_id: i64) -> rusqlite::Result<Option<RestTimer>> {
+    let mut stmt = conn.prepare(SELECT_TIMER)?;
+    let mut rows = stmt.query_map([session_id], |row| {
+        let skipped_at: Option<String> = row.get(6)?;
+        let left: f64 = row.get(7)?;
+        let remaining_seconds = if skipped_at.is_some() { 0 } else { left.ceil().max(0.0) as i64 };
+        let state = match (&skipped_at, remaining_seconds) {
+            (Some(_), _) => "skipped",
+            (None, 0) => "done",
+            (None, _) => "running",
+        };
+        Ok(RestTimer {
+            session_id: row.get(0)?,
+            session_exercise_id: row.get(1)?,
+            set_id: row.get(2)?,
+            duration_seconds: row.get(3)?,
+            started_at: row.get(4)?,
+            ends_at: row.get(5)?,
+            remaining_seconds,
+            state: state.to_string(),
+            server_time: row.get(8)?,
+        })
+    })?;
+    rows.next().transpose()
+}
+
+/// Restart the session's timer for a just-logged set. Returns the change to
+/// publish, if anything changed. Closed sessions are left alone.
+pub(crate) fn start_after_set(
+    conn: &rusqlite::Connection,
+    user_id: i64,
+    session_exercise_id: i64,
+    set_id: i64,
+) -> rusqlite::Result<Option<Change>> {
+    let (session_id, open, rest): (i64, bool, Option<i64>) = conn.query_row(
+        "SELECT s.id, s.ended_at IS NULL,
+                (SELECT te.rest_seconds FROM template_exercises te
+                 WHERE te.template_id = s.template_id AND te.exercise_id = se.exercise_id
+                   AND te.rest_seconds > 0
+                 ORDER BY te.position LIMIT 1)
+         FROM session_exercises se JOIN sessions s ON s.id = se.session_id
+         WHERE se.id = ?1",
+        [session_exercise_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    if !open {
+        return Ok(None);
+    }
+
+    let timer = match rest {
+        Some(seconds) => {
+            conn.execute(
+                "INSERT OR REPLACE INTO rest_timers
+                     (session_id, session_exercise_id, set_id, duration_seconds, started_at)
+                 VALUES (?1, ?2, ?3, ?4, datetime('now'))",
+                rusqlite::params![session_id, session_exercise_id, set_id, seconds],
+            )?;
+            load(conn, session_id)?
+        }
+        None => {
+            if conn.execute("DELETE FROM rest_timers WHERE session_id = ?1", [session_id])? == 0 {
+                return Ok(None);
+            }
+            None
+        }
+    };
+    Ok(Some(Change::new(user_id, session_id, ChangeEvent::RestTimer { timer })))
+}
+
+fn verify_session_ownership(conn: &rusqlite::Connection, session_id: i64, user_id: i64) -> Result<(), AppError> {
+    let owns: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sessions WHERE id = ?1 AND user_id = ?2",
+        rusqlite::params![session_id, user_id],
+        |row| row.get(0),
+    )?;
+    if !owns {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+pub fn get(db: &DbPool, user_id: i64, session_id: i64) -> Result<Option<RestTimer>, AppError> {
+    let conn = db.lock().unwrap();
+    verify_session_ownership(&conn, session_id, user_id)?;
+    Ok(load(&conn, session_id)?)
+}
+
+/// Timer of the user's open session, if it has one.
+pub fn get_active(db: &DbPool, user_id: i64) -> Result<Option<RestTimer>, AppError> {
+    let conn = db.lock().unwrap();
+    let session_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM sessions WHERE user_id = ?1 AND ended_at IS NULL
+             ORDER BY started_at DESC LIMIT 1",
+            [user_id],
+            |row| row.get(0),
+        )
+        .map(Some)
+        .or_else(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => Ok(None),
+            e => Err(e),
+        })?;
+    match session_id {
+        Some(id) => Ok(load(&conn, id)?),
+        None => Ok(None),
+    }
+}
+
+/// Add (or with a negative value, take away) seconds from what is left. A
+/// finished or skipped timer counts from now again. At most
+/// [`MAX_ADJUST_SECONDS`] either way per call.
+pub fn adjust(db: &DbPool, user_id: i64, session_id: i64, seconds: i64) -> Result<RestTimer, AppError> {
+    if !(-MAX_ADJUST_SECONDS..=MAX_ADJUST_SECONDS).contains(&seconds) {
+        return Err(AppError::BadRequest(format!(
+            "seconds must be between -{} and {}, got {}",
+            MAX_ADJUST_SECONDS, MAX_ADJUST_SECONDS, seconds
+        )));
+    }
+    let timer = {
+        let conn = db.lock().unwrap();
+        verify_session_ownership(&conn, session_id, user_id)?;
+        let current = load(&conn, session_id)?.ok_or(AppError::NotFound)?;
+        let remaining = current.remaining_seconds.saturating_add(seconds).max(0);
+        // Whole seconds, matching the rounding up of `remaining_seconds`.
+        let elapsed: i64 = conn.query_row(
+            "SELECT CAST((julianday('now') - julianday(started_at)) * 86400.0 AS INTEGER)
+             FROM rest_timers WHERE session_id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )?;
+        conn.execute(
+            "UPDATE rest_timers SET duration_seconds = ?1, skipped_at = NULL WHERE session_id = ?2",
+            rusqlite::params![elapsed.saturating_add(remaining), session_id],
+        )?;
+        load(&conn, session_id)?.ok_or(AppError::NotFound)?
+    };
+    db.notify(Change::new(user_id, session_id, ChangeEvent::RestTimer { timer: Some(timer.clone()) }));
+    Ok(timer)
+}
+
+/// Stop the countdown early.
+pub fn skip(db: &DbPool, user_id: i64, session_id: i64) -> Result<RestTimer, AppError> {
+    let timer = {
+        let conn = db.lock().unwrap();
+        verify_session_ownership(&conn, session_id, user_id)?;
+        conn.execute(
+            "UPDATE rest_timers SET skipped_at = COALESCE(skipped_at, datetime('now')) WHERE session_id = ?1",
+            [session_id],
+        )?;
+        load(&conn, session_id)?.ok_or(AppError::NotFound)?
+    };
+    db.notify(Change::new(user_id, session_id, ChangeEvent::RestTimer { timer: Some(timer.clone()) }));
+    Ok(timer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An open session from a template that rests 90s after squats and
+    /// prescribes nothing for rows, with one set of each logged.
+    fn seeded_db() -> DbPool {
+        let db = crate::db::init_memory_db().unwrap();
+        db.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO users (id, username, password_hash) VALUES (42, 'testuser', 'x');
+                 INSERT INTO exercises (id, user_id, name) VALUES (901, 42, 'SQUAT'), (902, 42, 'ROW');
+                 INSERT INTO templates (id, user_id, name) VALUES (901, 42, 'Legs');
+                 INSERT INTO template_exercises (template_id, exercise_id, position, rest_seconds)
+                     VALUES (901, 901, 0, 90), (901, 902, 1, NULL);
+                 INSERT INTO sessions (id, user_id, template_id, started_at, status)
+                     VALUES (901, 42, 901, datetime('now'), 'active');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position)
+                     VALUES (901, 901, 901, 0), (902, 901, 902, 1);
+                 INSERT INTO sets (id, session_exercise_id, set_number, weight_kg, reps)
+                     VALUES (901, 901, 1, 100, 5), (902, 902, 1, 60, 10);",
+            )
+            .unwrap();
+        db
+    }
+
+    fn start(db: &DbPool, session_exercise_id: i64, set_id: i64) -> Option<Change> {
+        start_after_set(&db.lock().unwrap(), 42, session_exercise_id, set_id).unwrap()
+    }
+
+    #[test]
+    fn logging_a_set_starts_or_clears_the_timer() {
+        let db = seeded_db();
+        assert!(start(&db, 901, 901).is_some());
+        let timer = get(&db, 42, 901).unwrap().unwrap();
+        assert_eq!((timer.set_id, timer.duration_seconds, timer.state.as_str()), (Some(901), 90, "running"));
+        assert!((89..=90).contains(&timer.remaining_seconds));
+
+        // No prescribed rest clears it, once.
+        assert!(start(&db, 902, 902).is_some());
+        assert!(get(&db, 42, 901).unwrap().is_none());
+        assert!(start(&db, 902, 902).is_none());
+
+        // A closed session keeps no timer.
+        db.lock().unwrap().execute("UPDATE sessions SET ended_at = datetime('now') WHERE id = 901", []).unwrap();
+        assert!(start(&db, 901, 901).is_none());
+        assert!(get(&db, 42, 901).unwrap().is_none());
+    }
+
+    #[test]
+    fn adjust_adds_and_clamps() {
+        let db = seeded_db();
+        assert!(matches!(adjust(&db, 42, 901, 30), Err(AppError::NotFound)));
+        start(&db, 901, 901);
+
+        let timer = adjust(&db, 42, 901, 30).unwrap();
+        assert!((119..=120).contains(&timer.remaining_seconds));
+        let timer = adjust(&db, 42, 901, -MAX_ADJUST_SECONDS).unwrap();
+        assert_eq!((timer.remaining_seconds, timer.state.as_str()), (0, "done"));
+        let timer = adjust(&db, 42, 901, 60).unwrap();
+        assert_eq!(timer.state, "running");
+        assert!((59..=60).contains(&timer.remaining_seconds));
+
+        for seconds in [MAX_ADJUST_SECONDS + 1, -MAX_ADJUST_SECONDS - 1, i64::MAX, i64::MIN] {
+            assert!(matches!(adjust(&db, 42, 901, seconds), Err(AppError::BadRequest(_))), "{}", seconds);
+        }
+        assert!((59..=60).contains(&get(&db, 42, 901).unwrap().unwrap().remaining_seconds));
+        assert!(matches!(adjust(&db, 7, 901, 30), Err(AppError::NotFound)));
+    }
+
+    #[test]
+    fn skip_stops_the_countdown() {
+        let db = seeded_db();
+        start(&db, 901, 901);
+        let timer = skip(&db, 42, 901).unwrap();
+        assert_eq!((timer.remaining_seconds, timer.state.as_str()), (0, "skipped"));
+        // Skipping again keeps the first skip.
+        assert_eq!(skip(&db, 42, 901).unwrap().state, "skipped");
+        // Adjusting a skipped timer counts from now.
+        let timer = adjust(&db, 42, 901, 45).unwrap();
+        assert_eq!(timer.state, "running");
+        assert!((44..=45).contains(&timer.remaining_seconds));
+        assert!(matches!(skip(&db, 7, 901), Err(AppError::NotFound)));
+    }
+}
@@ -0,0 +1,330 @@
+//! Append-only session timeline.
+//!
+//! Session mutations in `sessions` record what happened and when. Pause
+//! totals are derived from the log rather than trusted from the client:
+//! once a session has a `pause` or `reopen` event, `sessions.paused_duration`
+//! is recomputed here and client-supplied values are ignored.
+
+use chrono::{DateTime, Utc};
+
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::{PauseInterval, SessionEvent, SessionTimeline};
+use crate::timestamp;
+
+pub(crate) const START: &str = "start";
+pub(crate) const PAUSE: &str = "pause";
+pub(crate) const RESUME: &str = "resume";
+pub(crate) const EXERCISE_ADDED: &str = "exercise_added";
+pub(crate) const SET_LOGGED: &str = "set_logged";
+pub(crate) const END: &str = "end";
+pub(crate) const REOPEN: &str = "reopen";
+
+/// Optional references carried by an event.
+#[derive(Default)]
+pub(crate) struct EventRefs<'a> {
+    pub at: Option<DateTime<Utc>>,
+    pub session_exercise_id: Option<i64>,
+    pub set_id: Option<i64>,
+    pub detail: Option<&'a str>,
+}
+
+/// Append an event; `at` defaults to now.
+pub(crate) fn record(
+    conn: &rusqlite::Connection,
+    session_id: i64,
+    kind: &str,
+    refs: EventRefs<'_>,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO session_events (session_id, kind, at, session_exercise_id, set_id, detail)
+         VALUES (?1, ?2, COALESCE(?3, datetime('now')), ?4, ?5, ?6)",
+        rusqlite::params![
+            session_id,
+            kind,
+            refs.at.as_ref().map(timestamp::to_sql),
+            refs.session_exercise_id,
+            refs.set_id,
+            refs.detail
+        ],
+    )?;
+    Ok(())
+}
+
+/// Reconstruct start, set and end events for a session written in one go
+/// (sync, import). Client pauses aren't known, so the stored total stands.
+pub(crate) fn record_history(conn: &rusqlite::Connection, session_id: i64) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO session_events (session_id, kind, at)
+         SELECT id, 'start', started_at FROM sessions WHERE id = ?1",
+        [session_id],
+    )?;
+    conn.execute(
+        "INSERT INTO session_events (session_id, kind, at, session_exercise_id, set_id)
+         SELECT se.session_id, 'set_logged', st.completed_at, se.id, st.id
+         FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id
+         WHERE se.session_id = ?1",
+        [session_id],
+    )?;
+    conn.execute(
+        "INSERT INTO session_events (session_id, kind, at, detail)
+         SELECT id, 'end', ended_at, status FROM sessions WHERE id = ?1 AND ended_at IS NOT NULL",
+        [session_id],
+    )?;
+    Ok(())
+}
+
+/// True once the server has seen the session pause or reopen, i.e. it owns
+/// the pause total.
+pub(crate) fn tracks_pauses(conn: &rusqlite::Connection, session_id: i64) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM session_events WHERE session_id = ?1 AND kind IN ('pause', 'reopen')",
+        [session_id],
+        |row| row.get(0),
+    )
+}
+
+/// Recompute `sessions.paused_duration` from closed pause intervals. No-op
+/// for sessions whose pauses were never recorded (legacy rows, synced data).
+pub(crate) fn sync_paused_duration(conn: &rusqlite::Connection, session_id: i64) -> rusqlite::Result<()> {
+    if !tracks_pauses(conn, session_id)? {
+        return Ok(());
+    }
+    let total: i64 = pauses(conn, session_id)?
+        .iter()
+        .filter(|p| p.ended_at.is_some())
+        .map(|p| p.seconds)
+        .sum();
+    conn.execute(
+        "UPDATE sessions SET paused_duration = ?1 WHERE id = ?2",
+        rusqlite::params![total, session_id],
+    )?;
+    Ok(())
+}
+
+fn seconds_between(from: f64, to: f64) -> i64 {
+    (((to - from) * 86400.0).round() as i64).max(0)
+}
+
+/// Walk the log: `pause`→`resume` is a pause, `end`→`reopen` is a gap that
+/// also counts as paused. Ending while paused closes the pause. A pause still
+/// open on a running session runs to now; a trailing `end` is just the end.
+fn pauses(conn: &rusqlite::Connection, session_id: i64) -> rusqlite::Result<Vec<PauseInterval>> {
+    let (ended, now): (bool, f64) = conn.query_row(
+        "SELECT ended_at IS NOT NULL, julianday('now') FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let mut stmt = conn.prepare(
+        "SELECT kind, at, julianday(at) FROM session_events
+         WHERE session_id = ?1 AND kind IN ('pause', 'resume', 'end', 'reopen')
+         ORDER BY julianday(at), id",
+    )?;
+    let events = stmt
+        .query_map([session_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, f64>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut intervals = Vec::new();
+    // (opened by, at, julianday)
+    let mut open: Option<(String, String, f64)> = None;
+    for (kind, at, jd) in events {
+        let closes = matches!(
+            (open.as_ref().map(|o| o.0.as_str()), kind.as_str()),
+            (Some(PAUSE), RESUME) | (Some(PAUSE), END) | (Some(END), REOPEN)
+        );
+        if closes {
+            let (_, started_at, start) = open.take().unwrap();
+            intervals.push(PauseInterval {
+                started_at,
+                ended_at: Some(at.clone()),
+                seconds: seconds_between(start, jd),
+            });
+        }
+        if open.is_none() && (kind == PAUSE || kind == END) {
+            open = Some((kind, at, jd));
+        }
+    }
+    if let Some((kind, started_at, start)) = open {
+        if kind == PAUSE && !ended {
+            intervals.push(PauseInterval {
+                started_at,
+                ended_at: None,
+                seconds: seconds_between(start, now),
+            });
+        }
+    }
+    Ok(intervals)
+}
+
+pub fn timeline(db: &DbPool, user_id: i64, session_id: i64) -> Result<SessionTimeline, AppError> {
+    let conn = db.lock().unwrap();
+    let (started_at, ended_at, start, end, stored_paused): (String, Option<String>, f64, f64, i64) = conn
+        .query_row(
+            "SELECT started_at, ended_at, julianday(started_at),
+                    julianday(COALESCE(ended_at, 'now')), paused_duration
+             FROM sessions WHERE id = ?1 AND user_id = ?2",
+            rusqlite::params![session_id, user_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound,
+            e => AppError::Database(e),
+        })?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, kind, at, session_exercise_id, set_id, detail FROM session_events
+         WHERE session_id = ?1 ORDER BY julianday(at), id",
+    )?;
+    let events = stmt
+        .query_map([session_id], |row| {
+            Ok(SessionEvent {
+                id: row.get(0)?,
+                kind: row.get(1)?,
+                at: row.get(2)?,
+                session_exercise_id: row.get(3)?,
+                set_id: row.get(4)?,
+                detail: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let pauses = pauses(&conn, session_id)?;
+    let paused_duration = if tracks_pauses(&conn, session_id)? {
+        pauses.iter().map(|p| p.seconds).sum()
+    } else {
+        stored_paused
+    };
+    let active_duration = (seconds_between(start, end) - paused_duration).max(0);
+
+    Ok(SessionTimeline {
+        session_id,
+        started_at,
+        ended_at,
+        events,
+        pauses,
+        paused_duration,
+        active_duration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A session started at 10:00, with `ended_at` as given, and the
+    /// listed events at those times on the same day.
+    fn session(ended_at: Option<&str>, events: &[(&str, &str)]) -> DbPool {
+        let db = crate::db::init_memory_db().unwrap();
+        {
+            let conn = db.lock().unwrap();
+            conn.execute("INSERT INTO users (id, username, password_hash) VALUES (42, 'testuser', 'x')", [])
+                .unwrap();
+            conn.execute(
+                "INSERT INTO sessions (id, user_id, started_at, ended_at, status)
+                 VALUES (901, 42, '2026-10-01 10:00:00', ?1, ?2)",
+                rusqlite::params![
+                    ended_at.map(|t| format!("2026-10-01 {}", t)),
+                    if ended_at.is_some() { "completed" } else { "active" }
+                ],
+            )
+            .unwrap();
+            record(&conn, 901, START, EventRefs { at: timestamp::parse("2026-10-01 10:00:00"), ..Default::default() })
+                .unwrap();
+            for (kind, at) in events {
+                let at = timestamp::parse(&format!("2026-10-01 {}", at));
+                record(&conn, 901, kind, EventRefs { at, ..Default::default() }).unwrap();
+            }
+            sync_paused_duration(&conn, 901).unwrap();
+        }
+        db
+    }
+
+    fn stored_paused(db: &DbPool) -> i64 {
+        db.lock()
+            .unwrap()
+            .query_row("SELECT paused_duration FROM sessions WHERE id = 901", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn pause_then_resume() {
+        let db = session(Some("11:00:00"), &[(PAUSE, "10:10:00"), (RESUME, "10:15:00"), (END, "11:00:00")]);
+        let timeline = timeline(&db, 42, 901).unwrap();
+        assert_eq!(timeline.pauses.len(), 1);
+        assert_eq!(timeline.pauses[0].ended_at.as_deref(), Some("2026-10-01 10:15:00"));
+        assert_eq!((timeline.paused_duration, timeline.active_duration), (300, 3300));
+        assert_eq!(stored_paused(&db), 300);
+    }
+
+    #[test]
+    fn ending_while_paused_closes_the_pause() {
+        let db = session(Some("11:00:00"), &[(PAUSE, "10:50:00"), (END, "11:00:00")]);
+        let timeline = timeline(&db, 42, 901).unwrap();
+        assert_eq!(timeline.pauses.len(), 1);
+        assert_eq!((timeline.paused_duration, timeline.active_duration), (600, 3000));
+        assert_eq!(stored_paused(&db), 600);
+    }
+
+    #[test]
+    fn ended_while_paused_then_reopened() {
+        // Paused 10:50, ended 11:00, reopened at noon and finished 12:30:
+        // ten minutes paused plus the hour it sat closed.
+        let db = session(
+            Some("12:30:00"),
+            &[(PAUSE, "10:50:00"), (END, "11:00:00"), (REOPEN, "12:00:00"), (END, "12:30:00")],
+        );
+        let timeline = timeline(&db, 42, 901).unwrap();
+        let seconds: Vec<i64> = timeline.pauses.iter().map(|p| p.seconds).collect();
+        assert_eq!(seconds, [600, 3600]);
+        assert_eq!((timeline.paused_duration, timeline.active_duration), (4200, 9000 - 4200));
+        assert_eq!(stored_paused(&db), 4200);
+    }
+
+    #[test]
+    fn reopened_and_still_running() {
+        // The trailing reopen closes the gap; nothing is open after it.
+        let db = session(None, &[(END, "11:00:00"), (REOPEN, "11:20:00"), (PAUSE, "11:30:00"), (RESUME, "11:40:00")]);
+        let timeline = timeline(&db, 42, 901).unwrap();
+        let seconds: Vec<i64> = timeline.pauses.iter().map(|p| p.seconds).collect();
+        assert_eq!(seconds, [1200, 600]);
+        assert_eq!(timeline.paused_duration, 1800);
+        assert_eq!(stored_paused(&db), 1800);
+    }
+
+    #[test]
+    fn open_pause_runs_to_now_but_is_not_stored() {
+        let db = crate::db::init_memory_db().unwrap();
+        {
+            let conn = db.lock().unwrap();
+            conn.execute_batch(
+                "INSERT INTO users (id, username, password_hash) VALUES (42, 'testuser', 'x');
+                 INSERT INTO sessions (id, user_id, started_at, status)
+                     VALUES (901, 42, datetime('now', '-1 hour'), 'active');
+                 INSERT INTO session_events (session_id, kind, at)
+                     VALUES (901, 'pause', datetime('now', '-10 minutes'));",
+            )
+            .unwrap();
+            sync_paused_duration(&conn, 901).unwrap();
+        }
+        let timeline = timeline(&db, 42, 901).unwrap();
+        assert_eq!(timeline.pauses[0].ended_at, None);
+        assert!((600..=601).contains(&timeline.paused_duration));
+        assert!((2999..=3000).contains(&timeline.active_duration));
+        // Only closed pauses are written back.
+        assert_eq!(stored_paused(&db), 0);
+    }
+
+    #[test]
+    fn untracked_sessions_keep_the_stored_total() {
+        let db = session(Some("11:00:00"), &[(END, "11:00:00")]);
+        db.lock().unwrap().execute("UPDATE sessions SET paused_duration = 120 WHERE id = 901", []).unwrap();
+        sync_paused_duration(&db.lock().unwrap(), 901).unwrap();
+        let timeline = timeline(&db, 42, 901).unwrap();
+        assert!(timeline.pauses.is_empty());
+        assert_eq!((timeline.paused_duration, timeline.active_duration), (120, 3480));
+        assert_eq!(stored_paused(&db), 120);
+        assert!(matches!(super::timeline(&db, 7, 901), Err(AppError::NotFound)));
+    }
+}
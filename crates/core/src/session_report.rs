@@ -0,0 +1,448 @@
+//! Post-workout report: what a finished session achieved next to the
+//! lifter's history and the template it followed.
+//!
+//! - PRs hit ([`analytics::session_prs_hit`]), and for every other exercise how far
+//!   its best set fell short of the all-time best and how many reps at the
+//!   session's top weight would have beaten it.
+//! - Volume against the previous session of the same template.
+//! - Adherence to the template's set and rep targets, as of the template
+//!   version the session was started from.
+//! - Anomalies worth a look: sudden rep drop-offs, e1RM well below last
+//!   time, planned exercises that were skipped.
+
+use chrono::{DateTime, Utc};
+use lightweight_calc::e1rm;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::analytics::{self, SessionPR};
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::{Session, SessionExerciseWithSets, SessionStatus, SetType, TemplateExercise};
+use crate::sessions;
+use crate::templates;
+
+/// Reps falling by at least this fraction from one working set to the next,
+/// at the same or a lighter weight, is reported as a drop-off.
+const DROP_OFF_RATIO: f64 = 0.4;
+/// ...and by at least this many reps, so 3 → 1 singles don't count.
+const DROP_OFF_MIN_REPS: i32 = 3;
+/// Best e1RM this far below the last time the exercise was done is a
+/// regression.
+const REGRESSION_PCT: f64 = -10.0;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionReport {
+    pub session_id: i64,
+    pub name: Option<String>,
+    pub template_id: Option<i64>,
+    pub template_name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub status: SessionStatus,
+    pub working_sets: i64,
+    /// Working-set tonnage (weight × reps).
+    pub volume_kg: f64,
+    pub prs: Vec<SessionPR>,
+    pub exercises: Vec<ExerciseReport>,
+    /// Against the previous completed session of the same template.
+    pub volume_change: Option<VolumeChange>,
+    /// Only for sessions started from a template.
+    pub adherence: Option<Adherence>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExerciseReport {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub working_sets: i64,
+    pub volume_kg: f64,
+    /// Best working-set e1RM in this session.
+    pub best_e1rm: Option<f64>,
+    /// Heaviest working weight in this session.
+    pub top_weight_kg: Option<f64>,
+    /// Best e1RM from earlier completed sessions.
+    pub previous_best_e1rm: Option<f64>,
+    pub pr: bool,
+    /// How far `best_e1rm` fell short of `previous_best_e1rm`; None on a PR.
+    pub e1rm_gap: Option<f64>,
+    /// Reps at `top_weight_kg` that would have set a PR; None on a PR or
+    /// when it would take more than 30.
+    pub reps_to_beat: Option<i64>,
+    /// Volume for this exercise in the previous session of the template.
+    pub previous_volume_kg: Option<f64>,
+    pub target_sets: Option<i32>,
+    pub target_reps_min: Option<i32>,
+    pub target_reps_max: Option<i32>,
+    /// Working sets within the target rep range, when the template sets one.
+    pub sets_in_range: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VolumeChange {
+    pub previous_session_id: i64,
+    pub previous_started_at: DateTime<Utc>,
+    pub previous_volume_kg: f64,
+    pub delta_kg: f64,
+    pub delta_pct: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Adherence {
+    pub template_version: Option<i64>,
+    pub planned_exercises: i64,
+    pub completed_exercises: i64,
+    /// Sum of `target_sets` over the planned exercises.
+    pub planned_sets: i64,
+    /// Working sets logged toward those targets, capped at each target.
+    pub completed_sets: i64,
+    pub sets_in_range: i64,
+    /// Working sets of exercises with a target rep range.
+    pub sets_with_range: i64,
+    /// `completed_sets / planned_sets` as a percentage; None without targets.
+    pub score: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Anomaly {
+    /// `rep_drop_off`, `e1rm_regression` or `skipped_exercise`.
+    pub kind: String,
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    /// The set it was spotted on, if it concerns one.
+    pub set_id: Option<i64>,
+    pub message: String,
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Sets with a load whose type passes `counts`: (set_id, weight, reps) in
+/// logged order.
+fn loaded_sets(exercise: &SessionExerciseWithSets, counts: fn(SetType) -> bool) -> Vec<(i64, f64, i32)> {
+    exercise
+        .sets
+        .iter()
+        .filter(|s| counts(s.set_type) && s.reps > 0)
+        .filter_map(|s| s.weight_kg.filter(|w| *w > 0.0).map(|w| (s.id, w, s.reps)))
+        .collect()
+}
+
+fn volume(sets: &[(i64, f64, i32)]) -> f64 {
+    sets.iter().map(|(_, w, r)| w * *r as f64).sum()
+}
+
+fn best_e1rm(sets: &[(i64, f64, i32)]) -> Option<f64> {
+    let data: Vec<e1rm::SetData> = sets
+        .iter()
+        .map(|(_, w, r)| e1rm::SetData { weight_kg: *w, reps: *r as i64 })
+        .collect();
+    e1rm::best(&data)
+}
+
+/// Best working-set e1RM the last time the exercise was done in a completed
+/// session before this one.
+fn last_best_e1rm(
+    conn: &rusqlite::Connection,
+    user_id: i64,
+    exercise_id: i64,
+    session_id: i64,
+) -> rusqlite::Result<Option<f64>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT st.weight_kg, st.reps
+         FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id
+         WHERE se.exercise_id = ?2 AND counts_for_e1rm(st.set_type) AND st.weight_kg > 0 AND st.reps > 0
+           AND se.session_id = (
+               SELECT s.id FROM sessions s JOIN session_exercises x ON x.session_id = s.id
+               WHERE s.user_id = ?1 AND x.exercise_id = ?2 AND s.id != ?3 AND s.status = 'completed'
+                 AND s.started_at < (SELECT started_at FROM sessions WHERE id = ?3)
+               ORDER BY s.started_at DESC LIMIT 1)",
+    )?;
+    let sets: Vec<e1rm::SetData> = stmt
+        .query_map(rusqlite::params![user_id, exercise_id, session_id], |row| {
+            Ok(e1rm::SetData { weight_kg: row.get(0)?, reps: row.get(1)? })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(e1rm::best(&sets))
+}
+
+/// Sudden drop-offs between consecutive working sets.
+fn drop_offs(exercise: &SessionExerciseWithSets, sets: &[(i64, f64, i32)]) -> Vec<Anomaly> {
+    sets.windows(2)
+        .filter_map(|pair| {
+            let ((_, w1, r1), (id, w2, r2)) = (pair[0], pair[1]);
+            let dropped = r1 - r2;
+            let heavier = w2 > w1;
+            if heavier || dropped < DROP_OFF_MIN_REPS || (dropped as f64) < r1 as f64 * DROP_OFF_RATIO {
+                return None;
+            }
+            Some(Anomaly {
+                kind: "rep_drop_off".to_string(),
+                exercise_id: exercise.exercise_id,
+                exercise_name: exercise.exercise_name.clone(),
+                set_id: Some(id),
+                message: format!("Reps fell from {} to {} at {} kg", r1, r2, w2),
+            })
+        })
+        .collect()
+}
+
+fn reps_in_range(sets: &[(i64, f64, i32)], min: Option<i32>, max: Option<i32>) -> Option<i64> {
+    if min.is_none() && max.is_none() {
+        return None;
+    }
+    let in_range = sets
+        .iter()
+        .filter(|(_, _, r)| min.is_none_or(|m| *r >= m) && max.is_none_or(|m| *r <= m))
+        .count();
+    Some(in_range as i64)
+}
+
+pub fn report(db: &DbPool, user_id: i64, session_id: i64) -> Result<SessionReport, AppError> {
+    let session = sessions::get(db, user_id, session_id)?;
+    let prs = analytics::session_prs_hit(db, user_id, session_id)?;
+    let previous: Option<Session> = match session.template_id {
+        Some(template_id) => sessions::template_previous_before(db, user_id, template_id, Some(session_id))?,
+        None => None,
+    };
+
+    let conn = db.lock().unwrap();
+    let targets: Vec<TemplateExercise> = match session.template_id {
+        Some(template_id) => templates::exercises_at_version(&conn, template_id, session.template_version)?,
+        None => Vec::new(),
+    };
+
+    let mut exercises: Vec<ExerciseReport> = Vec::new();
+    let mut anomalies = Vec::new();
+    for exercise in &session.exercises {
+        let sets = loaded_sets(exercise, SetType::counts_for_volume);
+        let strength_sets = loaded_sets(exercise, SetType::counts_for_e1rm);
+        // An exercise can appear twice in a session; fold it into one row.
+        let row = match exercises.iter().position(|e| e.exercise_id == exercise.exercise_id) {
+            Some(i) => &mut exercises[i],
+            None => {
+                let target = targets.iter().find(|t| t.exercise_id == exercise.exercise_id);
+                exercises.push(ExerciseReport {
+                    exercise_id: exercise.exercise_id,
+                    exercise_name: exercise.exercise_name.clone(),
+                    working_sets: 0,
+                    volume_kg: 0.0,
+                    best_e1rm: None,
+                    top_weight_kg: None,
+                    previous_best_e1rm: None,
+                    pr: false,
+                    e1rm_gap: None,
+                    reps_to_beat: None,
+                    previous_volume_kg: None,
+                    target_sets: target.and_then(|t| t.target_sets),
+                    target_reps_min: target.and_then(|t| t.target_reps_min),
+                    target_reps_max: target.and_then(|t| t.target_reps_max),
+                    sets_in_range: None,
+                });
+                exercises.last_mut().unwrap()
+            }
+        };
+        row.working_sets += sets.len() as i64;
+        row.volume_kg += volume(&sets);
+        row.best_e1rm = match (row.best_e1rm, best_e1rm(&strength_sets)) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let top = strength_sets.iter().map(|(_, w, _)| *w).fold(None, |top: Option<f64>, w| Some(top.map_or(w, |t| t.max(w))));
+        row.top_weight_kg = match (row.top_weight_kg, top) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        if let Some(in_range) = reps_in_range(&sets, row.target_reps_min, row.target_reps_max) {
+            row.sets_in_range = Some(row.sets_in_range.unwrap_or(0) + in_range);
+        }
+        anomalies.extend(drop_offs(exercise, &strength_sets));
+    }
+
+    for row in &mut exercises {
+        row.pr = prs.iter().any(|p| p.exercise_id == row.exercise_id);
+        let previous_best = analytics::prior_best_e1rm(&conn, user_id, row.exercise_id, session_id)?;
+        row.previous_best_e1rm = previous_best.map(e1rm::round);
+        if let (Some(best), Some(previous_best), false) = (row.best_e1rm, previous_best, row.pr) {
+            row.e1rm_gap = Some(e1rm::round(previous_best - best));
+            row.reps_to_beat = row.top_weight_kg.and_then(|w| e1rm::reps_to_beat(previous_best, w));
+        }
+        if let (Some(best), Some(last)) = (row.best_e1rm, last_best_e1rm(&conn, user_id, row.exercise_id, session_id)?) {
+            if e1rm::pct_change(best, last).is_some_and(|pct| pct <= REGRESSION_PCT) {
+                anomalies.push(Anomaly {
+                    kind: "e1rm_regression".to_string(),
+                    exercise_id: row.exercise_id,
+                    exercise_name: row.exercise_name.clone(),
+                    set_id: None,
+                    message: format!("Best e1RM {} kg, down from {} kg last time", e1rm::round(best), e1rm::round(last)),
+                });
+            }
+        }
+        if let Some(previous) = &previous {
+            let sets: Vec<_> = previous
+                .exercises
+                .iter()
+                .filter(|e| e.exercise_id == row.exercise_id)
+                .flat_map(|e| loaded_sets(e, SetType::counts_for_volume))
+                .collect();
+            if !sets.is_empty() {
+                row.previous_volume_kg = Some(round1(volume(&sets)));
+            }
+        }
+        row.best_e1rm = row.best_e1rm.map(e1rm::round);
+        row.volume_kg = round1(row.volume_kg);
+    }
+    drop(conn);
+
+    let total_sets: i64 = exercises.iter().map(|e| e.working_sets).sum();
+    let volume_kg = round1(exercises.iter().map(|e| e.volume_kg).sum());
+
+    let volume_change = previous.map(|previous| {
+        let previous_volume: f64 = previous.exercises.iter().map(|e| volume(&loaded_sets(e, SetType::counts_for_volume))).sum();
+        let previous_volume = round1(previous_volume);
+        VolumeChange {
+            previous_session_id: previous.id,
+            previous_started_at: previous.started_at,
+            previous_volume_kg: previous_volume,
+            delta_kg: round1(volume_kg - previous_volume),
+            delta_pct: e1rm::pct_change(volume_kg, previous_volume).map(round1),
+        }
+    });
+
+    let adherence = session.template_id.map(|_| {
+        let mut adherence = Adherence {
+            template_version: session.template_version,
+            planned_exercises: targets.len() as i64,
+            completed_exercises: 0,
+            planned_sets: 0,
+            completed_sets: 0,
+            sets_in_range: 0,
+            sets_with_range: 0,
+            score: None,
+        };
+        for target in &targets {
+            let done = exercises
+                .iter()
+                .find(|e| e.exercise_id == target.exercise_id)
+                .filter(|e| e.working_sets > 0);
+            let done_sets = done.map_or(0, |e| e.working_sets);
+            if done.is_some() {
+                adherence.completed_exercises += 1;
+            } else {
+                anomalies.push(Anomaly {
+                    kind: "skipped_exercise".to_string(),
+                    exercise_id: target.exercise_id,
+                    exercise_name: target.exercise_name.clone(),
+                    set_id: None,
+                    message: format!("{} was planned but no working sets were logged", target.exercise_name),
+                });
+            }
+            if let Some(planned) = target.target_sets {
+                adherence.planned_sets += planned as i64;
+                adherence.completed_sets += done_sets.min(planned as i64);
+            }
+            if let Some(in_range) = done.and_then(|e| e.sets_in_range) {
+                adherence.sets_in_range += in_range;
+                adherence.sets_with_range += done_sets;
+            }
+        }
+        if adherence.planned_sets > 0 {
+            adherence.score = Some(round1(adherence.completed_sets as f64 * 100.0 / adherence.planned_sets as f64));
+        }
+        adherence
+    });
+
+    Ok(SessionReport {
+        session_id,
+        name: session.name,
+        template_id: session.template_id,
+        template_name: session.template_name,
+        started_at: session.started_at,
+        ended_at: session.ended_at,
+        status: session.status,
+        working_sets: total_sets,
+        volume_kg,
+        prs,
+        exercises,
+        volume_change,
+        adherence,
+        anomalies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two runs of a Push template. Bench: 100x8 last week, 100x6, 100x5 and
+    /// 100x2 this week (no PR, drop-off on the last set). OHP: planned but
+    /// skipped. Dips: 3x10 at +20 kg, a first appearance.
+    fn seeded_db() -> DbPool {
+        let db = crate::db::init_memory_db().unwrap();
+        db.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO users (id, username, password_hash) VALUES (42, 'testuser', 'x');
+                 INSERT INTO exercises (id, user_id, name) VALUES
+                     (901, 42, 'BENCH PRESS'), (902, 42, 'OVERHEAD PRESS'), (903, 42, 'DIPS');
+                 INSERT INTO templates (id, user_id, name) VALUES (901, 42, 'Push');
+                 INSERT INTO template_exercises (template_id, exercise_id, position, target_sets, target_reps_min, target_reps_max)
+                     VALUES (901, 901, 0, 3, 5, 8), (901, 902, 1, 3, 8, 10);
+                 INSERT INTO sessions (id, user_id, template_id, started_at, ended_at, status) VALUES
+                     (901, 42, 901, '2026-10-01 18:00:00', '2026-10-01 19:00:00', 'completed'),
+                     (902, 42, 901, '2026-10-08 18:00:00', '2026-10-08 19:00:00', 'completed');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES
+                     (901, 901, 901, 0), (902, 902, 901, 0), (903, 902, 903, 1);
+                 INSERT INTO sets (id, session_exercise_id, set_number, weight_kg, reps, set_type) VALUES
+                     (901, 901, 1, 100, 8, 'working'),
+                     (902, 902, 1, 60, 10, 'warmup'),
+                     (903, 902, 2, 100, 6, 'working'),
+                     (904, 902, 3, 100, 5, 'working'),
+                     (905, 902, 4, 100, 2, 'working'),
+                     (906, 903, 1, 20, 10, 'working'),
+                     (907, 903, 2, 20, 10, 'working'),
+                     (908, 903, 3, 20, 10, 'working');",
+            )
+            .unwrap();
+        db
+    }
+
+    #[test]
+    fn report_compares_with_history_and_template() {
+        let db = seeded_db();
+        let report = report(&db, 42, 902).unwrap();
+        assert!(report.prs.is_empty());
+        assert_eq!(report.working_sets, 6);
+
+        let bench = &report.exercises[0];
+        assert!(!bench.pr);
+        // 100x8 = 126.7 before, 100x6 = 120 now; 9 reps at 100 beat it.
+        assert_eq!(bench.previous_best_e1rm, Some(126.7));
+        assert_eq!(bench.e1rm_gap, Some(6.7));
+        assert_eq!(bench.reps_to_beat, Some(9));
+        assert_eq!(bench.previous_volume_kg, Some(800.0));
+        // 6 and 5 are in 5-8; 2 is not.
+        assert_eq!(bench.sets_in_range, Some(2));
+
+        let change = report.volume_change.unwrap();
+        assert_eq!(change.previous_session_id, 901);
+        assert_eq!(change.delta_kg, 1300.0 + 600.0 - 800.0);
+
+        let adherence = report.adherence.unwrap();
+        assert_eq!((adherence.planned_exercises, adherence.completed_exercises), (2, 1));
+        assert_eq!((adherence.planned_sets, adherence.completed_sets), (6, 3));
+        assert_eq!(adherence.score, Some(50.0));
+
+        let kinds: Vec<&str> = report.anomalies.iter().map(|a| a.kind.as_str()).collect();
+        assert_eq!(kinds, ["rep_drop_off", "skipped_exercise"]);
+        assert_eq!(report.anomalies[0].set_id, Some(905));
+    }
+
+    #[test]
+    fn other_users_sessions_are_not_found() {
+        let db = seeded_db();
+        assert!(matches!(report(&db, 7, 902), Err(AppError::NotFound)));
+    }
+}
@@ -1,32 +1,116 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::db::DbPool;
+use chrono::{DateTime, Datelike, NaiveDate, SubsecRound, Utc};
+
+use crate::changes::{Change, ChangeEvent};
+use crate::db::{Db, DbPool};
 use crate::error::AppError;
 use crate::models::*;
+use crate::rest_timer;
+use crate::session_events::{self as events, EventRefs};
+use crate::timestamp;
+use crate::webhooks;
+use crate::preferences::{
+    DEFAULT_EMPTY_SESSION_TIMEOUT_MINUTES, DEFAULT_STALE_SESSION_TIMEOUT_MINUTES,
+    EMPTY_SESSION_TIMEOUT_KEY, STALE_SESSION_TIMEOUT_KEY,
+};
 
 // ── Stale session cleanup ──
 
-/// Close sessions that have been active/paused for over 2 hours since the last
-/// logged set. Sets `ended_at` to the last set's `completed_at` timestamp.
-/// Sessions with no sets are left alone (user may still be picking exercises).
-fn close_stale_sessions(conn: &rusqlite::Connection, user_id: i64) {
-    let _ = conn.execute(
-        "UPDATE sessions SET
-            ended_at = (
-                SELECT MAX(st.completed_at) FROM sets st
-                JOIN session_exercises se ON se.id = st.session_exercise_id
-                WHERE se.session_id = sessions.id
-            ),
-            status = 'completed'
-         WHERE user_id = ?1
-           AND status IN ('active', 'paused')
-           AND (
-               SELECT MAX(st.completed_at) FROM sets st
-               JOIN session_exercises se ON se.id = st.session_exercise_id
-               WHERE se.session_id = sessions.id
-           ) < datetime('now', '-120 minutes')",
-        rusqlite::params![user_id],
-    );
+/// Auto-close abandoned sessions, per each user's timeout preferences:
+/// - `idle`: sets were logged, but none within `stale_session_timeout_minutes`
+///   (default 120). Completed with `ended_at` at the last set, or at the start
+///   if every set is timestamped earlier.
+/// - `empty`: no sets at all after `empty_session_timeout_minutes` (default
+///   360). Marked abandoned with `ended_at = started_at`.
+///
+/// A timeout of 0 disables that rule. Both clocks restart when the session is
+/// reopened. `None` closes stale sessions for every user. Returns a status
+/// change per session closed.
+fn close_stale(conn: &rusqlite::Connection, user_id: Option<i64>) -> rusqlite::Result<Vec<Change>> {
+    let timeout = |key: &str, default: i64| {
+        format!(
+            "COALESCE((SELECT CAST(p.value AS INTEGER) FROM user_preferences p
+                       WHERE p.user_id = sessions.user_id AND p.key = '{}'), {})",
+            key, default
+        )
+    };
+    let last_set = "(SELECT MAX(st.completed_at) FROM sets st
+                     JOIN session_exercises se ON se.id = st.session_exercise_id
+                     WHERE se.session_id = sessions.id)";
+    let idle_timeout = timeout(STALE_SESSION_TIMEOUT_KEY, DEFAULT_STALE_SESSION_TIMEOUT_MINUTES);
+    let empty_timeout = timeout(EMPTY_SESSION_TIMEOUT_KEY, DEFAULT_EMPTY_SESSION_TIMEOUT_MINUTES);
+
+    let mut closed = Vec::new();
+    let mut run = |sql: String| -> rusqlite::Result<()> {
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params![user_id], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?, row.get::<_, DateTime<Utc>>(2)?, row.get::<_, SessionStatus>(3)?))
+        })?;
+        for row in rows {
+            closed.push(row?);
+        }
+        Ok(())
+    };
+    run(format!(
+            "UPDATE sessions SET
+                ended_at = MAX({last_set}, started_at),
+                status = 'completed',
+                auto_closed = 1,
+                auto_close_reason = 'idle'
+             WHERE (?1 IS NULL OR user_id = ?1)
+               AND status IN ('active', 'paused')
+               AND {idle_timeout} > 0
+               AND {last_set} IS NOT NULL
+               AND MAX({last_set}, COALESCE(reopened_at, ''))
+                   < datetime('now', '-' || {idle_timeout} || ' minutes')
+             RETURNING id, user_id, ended_at, status"
+    ))?;
+    run(format!(
+            "UPDATE sessions SET
+                ended_at = started_at,
+                status = 'abandoned',
+                auto_closed = 1,
+                auto_close_reason = 'empty'
+             WHERE (?1 IS NULL OR user_id = ?1)
+               AND status IN ('active', 'paused')
+               AND {empty_timeout} > 0
+               AND {last_set} IS NULL
+               AND MAX(started_at, COALESCE(reopened_at, ''))
+                   < datetime('now', '-' || {empty_timeout} || ' minutes')
+             RETURNING id, user_id, ended_at, status"
+    ))?;
+
+    let mut changes = Vec::new();
+    for (id, owner, ended_at, status) in closed {
+        let detail = format!("auto:{}", status);
+        events::record(conn, id, events::END, EventRefs { at: Some(ended_at), detail: Some(&detail), ..Default::default() })?;
+        events::sync_paused_duration(conn, id)?;
+        if status == SessionStatus::Completed {
+            webhooks::session_completed(conn, owner, id)?;
+        }
+        let status = status.to_string();
+        changes.push(Change::new(owner, id, ChangeEvent::SessionStatus { status, ended_at: Some(ended_at) }));
+    }
+    Ok(changes)
+}
+
+/// Lazy per-user cleanup before listing or starting sessions; the scheduler
+/// also sweeps everyone periodically via [`close_all_stale_sessions`].
+fn close_stale_sessions(db: &Db, conn: &rusqlite::Connection, user_id: i64) {
+    for change in close_stale(conn, Some(user_id)).unwrap_or_default() {
+        db.notify(change);
+    }
+}
+
+/// Close stale sessions for all users. Returns how many were closed.
+pub fn close_all_stale_sessions(db: &DbPool) -> Result<usize, AppError> {
+    let changes = close_stale(&db.lock().unwrap(), None)?;
+    let closed = changes.len();
+    for change in changes {
+        db.notify(change);
+    }
+    Ok(closed)
 }
 
 // ── Ownership verification helpers ──
@@ -74,7 +158,7 @@ fn verify_session_exercise_ownership(conn: &rusqlite::Connection, se_id: i64, us
 
 pub fn list(db: &DbPool, user_id: i64, params: &SessionListParams) -> Result<Vec<SessionSummary>, AppError> {
     let conn = db.lock().unwrap();
-    close_stale_sessions(&conn, user_id);
+    close_stale_sessions(db, &conn, user_id);
     let limit = params.limit.unwrap_or(20);
     let offset = params.offset.unwrap_or(0);
 
@@ -82,7 +166,7 @@ pub fn list(db: &DbPool, user_id: i64, params: &SessionListParams) -> Result<Vec
                     (SELECT COUNT(*) FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id WHERE se.session_id = s.id) as set_count,
                     (SELECT COUNT(DISTINCT se.id) FROM session_exercises se WHERE se.session_id = s.id) as exercise_count,
                     (SELECT SUM(te.target_sets) FROM template_exercises te WHERE te.template_id = s.template_id) as target_set_count,
-                    s.template_version
+                    s.template_version, s.auto_closed, s.auto_close_reason
              FROM sessions s LEFT JOIN templates t ON t.id = s.template_id
              WHERE s.user_id = ?1";
 
@@ -123,6 +207,8 @@ pub fn list(db: &DbPool, user_id: i64, params: &SessionListParams) -> Result<Vec
             exercise_count: row.get(8)?,
             target_set_count: row.get(9)?,
             template_version: row.get(10)?,
+            auto_closed: row.get(11)?,
+            auto_close_reason: row.get(12)?,
         })
     })?;
 
@@ -153,9 +239,10 @@ pub fn get_active(db: &DbPool, user_id: i64) -> Result<Option<Session>, AppError
 
 pub fn get(db: &DbPool, user_id: i64, id: i64) -> Result<Session, AppError> {
     let conn = db.lock().unwrap();
-    let (template_id, template_name, name, started_at, ended_at, paused_duration, notes, status, template_version) = conn
+    let (template_id, template_name, name, started_at, ended_at, paused_duration, notes, status, template_version, auto_closed, auto_close_reason) = conn
         .query_row(
-            "SELECT s.template_id, t.name, s.name, s.started_at, s.ended_at, s.paused_duration, s.notes, s.status, s.template_version
+            "SELECT s.template_id, t.name, s.name, s.started_at, s.ended_at, s.paused_duration, s.notes, s.status, s.template_version,
+                    s.auto_closed, s.auto_close_reason
              FROM sessions s LEFT JOIN templates t ON t.id = s.template_id
              WHERE s.id = ?1 AND s.user_id = ?2",
             rusqlite::params![id, user_id],
@@ -164,12 +251,14 @@ pub fn get(db: &DbPool, user_id: i64, id: i64) -> Result<Session, AppError> {
                     row.get::<_, Option<i64>>(0)?,
                     row.get::<_, Option<String>>(1)?,
                     row.get::<_, Option<String>>(2)?,
-                    row.get::<_, String>(3)?,
-                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, DateTime<Utc>>(3)?,
+                    row.get::<_, Option<DateTime<Utc>>>(4)?,
                     row.get::<_, i64>(5)?,
                     row.get::<_, Option<String>>(6)?,
-                    row.get::<_, String>(7)?,
+                    row.get::<_, SessionStatus>(7)?,
                     row.get::<_, Option<i64>>(8)?,
+                    row.get::<_, bool>(9)?,
+                    row.get::<_, Option<String>>(10)?,
                 ))
             },
         )
@@ -188,13 +277,15 @@ pub fn get(db: &DbPool, user_id: i64, id: i64) -> Result<Session, AppError> {
         notes,
         status,
         template_version,
+        auto_closed,
+        auto_close_reason,
         exercises,
     })
 }
 
 pub fn create(db: &DbPool, user_id: i64, input: &CreateSession) -> Result<Session, AppError> {
     let conn = db.lock().unwrap();
-    close_stale_sessions(&conn, user_id);
+    close_stale_sessions(db, &conn, user_id);
 
     // Verify template belongs to user if provided
     if let Some(template_id) = input.template_id {
@@ -208,7 +299,7 @@ pub fn create(db: &DbPool, user_id: i64, input: &CreateSession) -> Result<Sessio
         }
     }
 
-    let status = input.status.as_deref().unwrap_or("active");
+    let status = input.status.unwrap_or(SessionStatus::Active);
 
     // Read template version if creating from a template
     let template_version: Option<i64> = if let Some(template_id) = input.template_id {
@@ -223,12 +314,14 @@ pub fn create(db: &DbPool, user_id: i64, input: &CreateSession) -> Result<Sessio
 
     let paused_duration = input.paused_duration.unwrap_or(0);
     if input.started_at.is_some() || input.ended_at.is_some() {
+        let started_at = input.started_at.unwrap_or_else(|| Utc::now().trunc_subsecs(0));
+        check_span(&started_at, input.ended_at.as_ref())?;
         conn.execute(
             "INSERT INTO sessions (user_id, template_id, name, started_at, ended_at, status, notes, template_version, paused_duration)
-             VALUES (?1, ?2, ?3, COALESCE(?4, datetime('now')), ?5, ?6, ?7, ?8, ?9)",
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             rusqlite::params![
                 user_id, input.template_id, input.name,
-                input.started_at, input.ended_at,
+                timestamp::to_sql(&started_at), input.ended_at.as_ref().map(timestamp::to_sql),
                 status, input.notes, template_version, paused_duration
             ],
         )?;
@@ -240,6 +333,15 @@ pub fn create(db: &DbPool, user_id: i64, input: &CreateSession) -> Result<Sessio
     }
 
     let session_id = conn.last_insert_rowid();
+    let (started_at, ended_at): (DateTime<Utc>, Option<DateTime<Utc>>) = conn.query_row(
+        "SELECT started_at, ended_at FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    events::record(&conn, session_id, events::START, EventRefs { at: Some(started_at), ..Default::default() })?;
+    if let Some(ended_at) = ended_at {
+        events::record(&conn, session_id, events::END, EventRefs { at: Some(ended_at), detail: Some(status.as_str()), ..Default::default() })?;
+    }
 
     // If from template, pre-populate exercises
     if let Some(template_id) = input.template_id {
@@ -268,17 +370,37 @@ pub fn create(db: &DbPool, user_id: i64, input: &CreateSession) -> Result<Sessio
     get(db, user_id, session_id)
 }
 
+/// Reject a session that ends before it starts.
+fn check_span(started_at: &DateTime<Utc>, ended_at: Option<&DateTime<Utc>>) -> Result<(), AppError> {
+    match ended_at {
+        Some(ended_at) if ended_at < started_at => Err(AppError::BadRequest(format!(
+            "ended_at ({}) is before started_at ({})",
+            timestamp::to_sql(ended_at),
+            timestamp::to_sql(started_at)
+        ))),
+        _ => Ok(()),
+    }
+}
+
 pub fn update(db: &DbPool, user_id: i64, id: i64, input: &UpdateSession) -> Result<Session, AppError> {
     let conn = db.lock().unwrap();
 
     verify_session_ownership(&conn, id, user_id)?;
+    let (previous_status, started_at, ended_at): (SessionStatus, DateTime<Utc>, Option<DateTime<Utc>>) = conn.query_row(
+        "SELECT status, started_at, ended_at FROM sessions WHERE id = ?1",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    if input.started_at.is_some() || input.ended_at.is_some() {
+        // Finishing stamps the end now unless the client sends its own.
+        let finishing = input.status.is_some_and(SessionStatus::is_finished);
+        let now = Utc::now().trunc_subsecs(0);
+        let ended_at = input.ended_at.or(if finishing { Some(now) } else { ended_at });
+        check_span(&input.started_at.unwrap_or(started_at), ended_at.as_ref())?;
+    }
 
-    if let Some(ref status) = input.status {
-        let valid = ["active", "paused", "completed", "abandoned"];
-        if !valid.contains(&status.as_str()) {
-            return Err(AppError::BadRequest(format!("Invalid status: {}", status)));
-        }
-        if status == "completed" || status == "abandoned" {
+    if let Some(status) = input.status {
+        if status.is_finished() {
             // If no sets were recorded, delete the empty session instead of keeping it
             let set_count: i64 = conn.query_row(
                 "SELECT COUNT(*) FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id WHERE se.session_id = ?1",
@@ -288,42 +410,115 @@ pub fn update(db: &DbPool, user_id: i64, id: i64, input: &UpdateSession) -> Resu
             if set_count == 0 {
                 conn.execute("DELETE FROM session_exercises WHERE session_id = ?1", rusqlite::params![id])?;
                 conn.execute("DELETE FROM sessions WHERE id = ?1", rusqlite::params![id])?;
+                drop(conn);
+                notify_status(db, user_id, id, "deleted", None);
                 return Err(AppError::NotFound);
             }
             conn.execute(
                 "UPDATE sessions SET status = ?1, ended_at = datetime('now') WHERE id = ?2",
                 rusqlite::params![status, id],
             )?;
+            events::record(&conn, id, events::END, EventRefs { detail: Some(status.as_str()), ..Default::default() })?;
+            events::sync_paused_duration(&conn, id)?;
+            if status == SessionStatus::Completed && previous_status != SessionStatus::Completed {
+                webhooks::session_completed(&conn, user_id, id)?;
+            }
         } else {
             conn.execute(
                 "UPDATE sessions SET status = ?1 WHERE id = ?2",
                 rusqlite::params![status, id],
             )?;
+            match (previous_status, status) {
+                (SessionStatus::Active, SessionStatus::Paused) => events::record(&conn, id, events::PAUSE, EventRefs::default())?,
+                (SessionStatus::Paused, SessionStatus::Active) => {
+                    events::record(&conn, id, events::RESUME, EventRefs::default())?;
+                    events::sync_paused_duration(&conn, id)?;
+                }
+                _ => {}
+            }
         }
     }
     if let Some(ref notes) = input.notes {
         conn.execute("UPDATE sessions SET notes = ?1 WHERE id = ?2", rusqlite::params![notes, id])?;
     }
     if let Some(paused_duration) = input.paused_duration {
-        conn.execute(
-            "UPDATE sessions SET paused_duration = ?1 WHERE id = ?2",
-            rusqlite::params![paused_duration, id],
-        )?;
+        // Once pauses are logged the server derives the total; older clients
+        // that still send their own count are ignored.
+        if !events::tracks_pauses(&conn, id)? {
+            conn.execute(
+                "UPDATE sessions SET paused_duration = ?1 WHERE id = ?2",
+                rusqlite::params![paused_duration, id],
+            )?;
+        }
     }
     if let Some(ref started_at) = input.started_at {
         conn.execute(
             "UPDATE sessions SET started_at = ?1 WHERE id = ?2",
-            rusqlite::params![started_at, id],
+            rusqlite::params![timestamp::to_sql(started_at), id],
         )?;
     }
     if let Some(ref ended_at) = input.ended_at {
         conn.execute(
             "UPDATE sessions SET ended_at = ?1 WHERE id = ?2",
-            rusqlite::params![ended_at, id],
+            rusqlite::params![timestamp::to_sql(ended_at), id],
         )?;
     }
 
     drop(conn);
+    let session = get(db, user_id, id)?;
+    if input.status.is_some() || input.ended_at.is_some() {
+        notify_status(db, user_id, id, session.status.as_str(), session.ended_at);
+    }
+    Ok(session)
+}
+
+fn notify_status(db: &Db, user_id: i64, session_id: i64, status: &str, ended_at: Option<DateTime<Utc>>) {
+    db.notify(Change::new(
+        user_id,
+        session_id,
+        ChangeEvent::SessionStatus { status: status.to_string(), ended_at },
+    ));
+}
+
+/// Undo an auto-close: the session becomes active again and the idle clock
+/// restarts now. Only sessions the server closed can be reopened, and only
+/// while the user has no other session in progress.
+pub fn reopen(db: &DbPool, user_id: i64, id: i64) -> Result<Session, AppError> {
+    let conn = db.lock().unwrap();
+    verify_session_ownership(&conn, id, user_id)?;
+
+    let auto_closed: bool = conn.query_row(
+        "SELECT auto_closed FROM sessions WHERE id = ?1",
+        [id],
+        |row| row.get(0),
+    )?;
+    if !auto_closed {
+        return Err(AppError::BadRequest(
+            "Only auto-closed sessions can be reopened".to_string(),
+        ));
+    }
+    let in_progress: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sessions WHERE user_id = ?1 AND status IN ('active', 'paused')",
+        [user_id],
+        |row| row.get(0),
+    )?;
+    if in_progress {
+        return Err(AppError::BadRequest(
+            "Finish or discard the session in progress first".to_string(),
+        ));
+    }
+
+    conn.execute(
+        "UPDATE sessions SET status = 'active', ended_at = NULL, auto_closed = 0,
+             auto_close_reason = NULL, reopened_at = datetime('now')
+         WHERE id = ?1",
+        [id],
+    )?;
+    events::record(&conn, id, events::REOPEN, EventRefs::default())?;
+    events::sync_paused_duration(&conn, id)?;
+
+    drop(conn);
+    notify_status(db, user_id, id, "active", None);
     get(db, user_id, id)
 }
 
@@ -336,6 +531,8 @@ pub fn delete(db: &DbPool, user_id: i64, id: i64) -> Result<(), AppError> {
     if rows == 0 {
         return Err(AppError::NotFound);
     }
+    drop(conn);
+    notify_status(db, user_id, id, "deleted", None);
     Ok(())
 }
 
@@ -377,20 +574,25 @@ pub fn add_exercise(db: &DbPool, user_id: i64, session_id: i64, input: &AddSessi
     )?;
 
     let id = conn.last_insert_rowid();
+    events::record(&conn, session_id, events::EXERCISE_ADDED, EventRefs { session_exercise_id: Some(id), ..Default::default() })?;
     let exercise_name: String = conn.query_row(
         "SELECT name FROM exercises WHERE id = ?1",
         [input.exercise_id],
         |row| row.get(0),
     )?;
 
-    Ok(SessionExerciseWithSets {
+    drop(conn);
+
+    let exercise = SessionExerciseWithSets {
         id,
         exercise_id: input.exercise_id,
         exercise_name,
         position,
         notes: None,
         sets: vec![],
-    })
+    };
+    db.notify(Change::new(user_id, session_id, ChangeEvent::ExerciseAdded { exercise: exercise.clone() }));
+    Ok(exercise)
 }
 
 pub fn update_exercise(db: &DbPool, user_id: i64, _session_id: i64, se_id: i64, input: &UpdateSessionExercise) -> Result<(), AppError> {
@@ -412,6 +614,17 @@ pub fn update_exercise(db: &DbPool, user_id: i64, _session_id: i64, se_id: i64,
         )?;
     }
 
+    let (session_id, position, notes) = conn.query_row(
+        "SELECT session_id, position, notes FROM session_exercises WHERE id = ?1",
+        [se_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    drop(conn);
+    db.notify(Change::new(
+        user_id,
+        session_id,
+        ChangeEvent::ExerciseUpdated { session_exercise_id: se_id, position, notes },
+    ));
     Ok(())
 }
 
@@ -421,10 +634,14 @@ pub fn remove_exercise(db: &DbPool, user_id: i64, _session_id: i64, se_id: i64)
     // Verify ownership via session_exercises -> sessions
     verify_session_exercise_ownership(&conn, se_id, user_id)?;
 
+    let session_id: i64 =
+        conn.query_row("SELECT session_id FROM session_exercises WHERE id = ?1", [se_id], |row| row.get(0))?;
     let rows = conn.execute("DELETE FROM session_exercises WHERE id = ?1", [se_id])?;
     if rows == 0 {
         return Err(AppError::NotFound);
     }
+    drop(conn);
+    db.notify(Change::new(user_id, session_id, ChangeEvent::ExerciseRemoved { session_exercise_id: se_id }));
     Ok(())
 }
 
@@ -445,7 +662,7 @@ pub fn add_set(db: &DbPool, user_id: i64, se_id: i64, input: &CreateSet) -> Resu
         max.unwrap_or(0) + 1
     };
 
-    let set_type = input.set_type.as_deref().unwrap_or("working");
+    let set_type = input.set_type.unwrap_or_default();
 
     conn.execute(
         "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, set_type, rir)
@@ -454,22 +671,37 @@ pub fn add_set(db: &DbPool, user_id: i64, se_id: i64, input: &CreateSet) -> Resu
     )?;
 
     let id = conn.last_insert_rowid();
-    let completed_at: String = conn.query_row(
-        "SELECT completed_at FROM sets WHERE id = ?1",
+    let (completed_at, session_id): (DateTime<Utc>, i64) = conn.query_row(
+        "SELECT st.completed_at, se.session_id FROM sets st
+         JOIN session_exercises se ON se.id = st.session_exercise_id
+         WHERE st.id = ?1",
         [id],
-        |row| row.get(0),
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    events::record(
+        &conn,
+        session_id,
+        events::SET_LOGGED,
+        EventRefs { at: Some(completed_at), session_exercise_id: Some(se_id), set_id: Some(id), ..Default::default() },
     )?;
+    let timer_change = rest_timer::start_after_set(&conn, user_id, se_id, id)?;
+    drop(conn);
 
-    Ok(Set {
+    let set = Set {
         id,
         session_exercise_id: se_id,
         set_number,
         weight_kg: input.weight_kg,
         reps: input.reps,
-        set_type: set_type.to_string(),
+        set_type,
         rir: input.rir,
         completed_at,
-    })
+    };
+    db.notify(Change::new(user_id, session_id, ChangeEvent::SetAdded { set: set.clone() }));
+    if let Some(change) = timer_change {
+        db.notify(change);
+    }
+    Ok(set)
 }
 
 pub fn update_set(db: &DbPool, user_id: i64, set_id: i64, input: &UpdateSet) -> Result<Set, AppError> {
@@ -490,24 +722,33 @@ pub fn update_set(db: &DbPool, user_id: i64, set_id: i64, input: &UpdateSet) ->
         conn.execute("UPDATE sets SET rir = ?1 WHERE id = ?2", rusqlite::params![rir, set_id])?;
     }
 
-    conn.query_row(
-        "SELECT id, session_exercise_id, set_number, weight_kg, reps, set_type, rir, completed_at
-         FROM sets WHERE id = ?1",
-        [set_id],
-        |row| {
-            Ok(Set {
-                id: row.get(0)?,
-                session_exercise_id: row.get(1)?,
-                set_number: row.get(2)?,
-                weight_kg: row.get(3)?,
-                reps: row.get(4)?,
-                set_type: row.get(5)?,
-                rir: row.get(6)?,
-                completed_at: row.get(7)?,
-            })
-        },
-    )
-    .map_err(|_| AppError::NotFound)
+    let (set, session_id) = conn
+        .query_row(
+            "SELECT st.id, st.session_exercise_id, st.set_number, st.weight_kg, st.reps, st.set_type,
+                    st.rir, st.completed_at, se.session_id
+             FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id
+             WHERE st.id = ?1",
+            [set_id],
+            |row| {
+                Ok((
+                    Set {
+                        id: row.get(0)?,
+                        session_exercise_id: row.get(1)?,
+                        set_number: row.get(2)?,
+                        weight_kg: row.get(3)?,
+                        reps: row.get(4)?,
+                        set_type: row.get(5)?,
+                        rir: row.get(6)?,
+                        completed_at: row.get(7)?,
+                    },
+                    row.get(8)?,
+                ))
+            },
+        )
+        .map_err(|_| AppError::NotFound)?;
+    drop(conn);
+    db.notify(Change::new(user_id, session_id, ChangeEvent::SetUpdated { set: set.clone() }));
+    Ok(set)
 }
 
 pub fn delete_set(db: &DbPool, user_id: i64, set_id: i64) -> Result<(), AppError> {
@@ -515,10 +756,19 @@ pub fn delete_set(db: &DbPool, user_id: i64, set_id: i64) -> Result<(), AppError
 
     verify_set_ownership(&conn, set_id, user_id)?;
 
+    let (session_exercise_id, session_id): (i64, i64) = conn.query_row(
+        "SELECT st.session_exercise_id, se.session_id FROM sets st
+         JOIN session_exercises se ON se.id = st.session_exercise_id
+         WHERE st.id = ?1",
+        [set_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
     let rows = conn.execute("DELETE FROM sets WHERE id = ?1", [set_id])?;
     if rows == 0 {
         return Err(AppError::NotFound);
     }
+    drop(conn);
+    db.notify(Change::new(user_id, session_id, ChangeEvent::SetDeleted { session_exercise_id, set_id }));
     Ok(())
 }
 
@@ -593,12 +843,24 @@ pub fn exercise_history(db: &DbPool, user_id: i64, exercise_id: i64, limit: i64)
 }
 
 pub fn template_previous(db: &DbPool, user_id: i64, template_id: i64) -> Result<Option<Session>, AppError> {
+    template_previous_before(db, user_id, template_id, None)
+}
+
+/// Latest completed session of the template, or with `before` the latest one
+/// started before that session.
+pub fn template_previous_before(
+    db: &DbPool,
+    user_id: i64,
+    template_id: i64,
+    before: Option<i64>,
+) -> Result<Option<Session>, AppError> {
     let conn = db.lock().unwrap();
     let id: Result<i64, _> = conn.query_row(
         "SELECT id FROM sessions
          WHERE template_id = ?1 AND user_id = ?2 AND status = 'completed'
+           AND (?3 IS NULL OR (id != ?3 AND started_at < (SELECT started_at FROM sessions WHERE id = ?3)))
          ORDER BY started_at DESC LIMIT 1",
-        rusqlite::params![template_id, user_id],
+        rusqlite::params![template_id, user_id, before],
         |row| row.get(0),
     );
 
@@ -677,22 +939,14 @@ pub fn session_exercise_previous(db: &DbPool, user_id: i64, session_id: i64) ->
 
 // ── Import ──
 
-fn parse_import_date(date: &str) -> Result<String, AppError> {
+fn parse_import_date(date: &str) -> Result<DateTime<Utc>, AppError> {
     let date = date.trim();
-    if date.len() != 10 {
-        return Err(AppError::BadRequest(format!("Invalid date format: '{}', expected YYYY-MM-DD", date)));
-    }
-    let parts: Vec<&str> = date.split('-').collect();
-    if parts.len() != 3 {
-        return Err(AppError::BadRequest(format!("Invalid date format: '{}', expected YYYY-MM-DD", date)));
-    }
-    let year: u32 = parts[0].parse().map_err(|_| AppError::BadRequest(format!("Invalid year in date: '{}'", date)))?;
-    let month: u32 = parts[1].parse().map_err(|_| AppError::BadRequest(format!("Invalid month in date: '{}'", date)))?;
-    let day: u32 = parts[2].parse().map_err(|_| AppError::BadRequest(format!("Invalid day in date: '{}'", date)))?;
-    if year < 2000 || year > 2100 || month < 1 || month > 12 || day < 1 || day > 31 {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest(format!("Invalid date format: '{}', expected YYYY-MM-DD", date)))?;
+    if !(2000..=2100).contains(&day.year()) {
         return Err(AppError::BadRequest(format!("Date out of range: '{}'", date)));
     }
-    Ok(format!("{} 00:00:00", date))
+    Ok(day.and_hms_opt(0, 0, 0).unwrap().and_utc())
 }
 
 pub(crate) fn resolve_exercise(conn: &rusqlite::Connection, user_id: i64, name: &str, warnings: &mut Vec<String>) -> Result<Option<(i64, String)>, AppError> {
@@ -756,13 +1010,17 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
     let conn = db.lock().unwrap();
     let mut warnings: Vec<String> = Vec::new();
 
+    for session in &input {
+        check_span(&session.started_at, session.ended_at.as_ref())?;
+    }
+
     // Build set of existing started_at timestamps for dedup
-    let mut existing_timestamps: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut existing_timestamps: HashSet<DateTime<Utc>> = HashSet::new();
     {
         let mut stmt = conn.prepare(
             "SELECT started_at FROM sessions WHERE user_id = ?1"
         )?;
-        let rows = stmt.query_map([user_id], |row| row.get::<_, String>(0))?;
+        let rows = stmt.query_map([user_id], |row| row.get::<_, DateTime<Utc>>(0))?;
         for row in rows {
             if let Ok(ts) = row {
                 existing_timestamps.insert(ts);
@@ -774,10 +1032,10 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
     let mut to_insert: Vec<&SyncSession> = Vec::new();
     let mut skipped: i64 = 0;
     for session in &input {
-        if existing_timestamps.contains(&session.started_at) {
-            skipped += 1;
-        } else {
+        if existing_timestamps.insert(session.started_at) {
             to_insert.push(session);
+        } else {
+            skipped += 1;
         }
     }
 
@@ -823,7 +1081,7 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
     let mut session_ids: Vec<i64> = Vec::new();
 
     for session in &to_insert {
-        let status = session.status.as_deref().unwrap_or("completed");
+        let status = session.status.unwrap_or(SessionStatus::Completed);
         let paused_duration = session.paused_duration.unwrap_or(0);
 
         // Validate template_id belongs to this user — null out if not, so we don't
@@ -846,7 +1104,7 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             rusqlite::params![
                 user_id, session.name, template_id, template_version,
-                session.started_at, session.ended_at,
+                timestamp::to_sql(&session.started_at), session.ended_at.as_ref().map(timestamp::to_sql),
                 status, session.notes, paused_duration
             ],
         )?;
@@ -867,8 +1125,8 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
             let se_id = conn.last_insert_rowid();
 
             for (set_idx, set) in exercise.sets.iter().enumerate() {
-                let set_type = set.set_type.as_deref().unwrap_or("working");
-                let completed_at = set.completed_at.as_deref().unwrap_or(&session.started_at);
+                let set_type = set.set_type.unwrap_or_default();
+                let completed_at = timestamp::to_sql(set.completed_at.as_ref().unwrap_or(&session.started_at));
                 conn.execute(
                     "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, set_type, rir, completed_at)
                      VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
@@ -879,8 +1137,15 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
                 )?;
             }
         }
+        events::record_history(&conn, session_id)?;
     }
 
+    webhooks::enqueue(&conn, user_id, webhooks::SYNC_COMPLETED, &serde_json::json!({
+        "session_ids": session_ids,
+        "skipped": skipped,
+        "exercises_created": exercises_created,
+    }))?;
+
     conn.execute_batch("COMMIT")?;
 
     // Fetch full session objects for response
@@ -898,11 +1163,11 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
                         row.get::<_, Option<i64>>(0)?,
                         row.get::<_, Option<String>>(1)?,
                         row.get::<_, Option<String>>(2)?,
-                        row.get::<_, String>(3)?,
-                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, DateTime<Utc>>(3)?,
+                        row.get::<_, Option<DateTime<Utc>>>(4)?,
                         row.get::<_, i64>(5)?,
                         row.get::<_, Option<String>>(6)?,
-                        row.get::<_, String>(7)?,
+                        row.get::<_, SessionStatus>(7)?,
                     ))
                 },
             )?;
@@ -918,6 +1183,8 @@ pub fn sync_sessions(db: &DbPool, user_id: i64, input: Vec<SyncSession>) -> Resu
             notes,
             status,
             template_version: None,
+            auto_closed: false,
+            auto_close_reason: None,
             exercises,
         });
     }
@@ -991,7 +1258,7 @@ pub fn import_sessions(db: &DbPool, user_id: i64, input: Vec<ImportSession>) ->
     let mut session_ids: Vec<i64> = Vec::new();
 
     for (i, session) in input.iter().enumerate() {
-        let date = parse_import_date(&session.date)?;
+        let date = timestamp::to_sql(&parse_import_date(&session.date)?);
         let template_id = template_ids[i];
 
         conn.execute(
@@ -1016,7 +1283,7 @@ pub fn import_sessions(db: &DbPool, user_id: i64, input: Vec<ImportSession>) ->
             let se_id = conn.last_insert_rowid();
 
             for (set_idx, set) in exercise.sets.iter().enumerate() {
-                let set_type = set.set_type.as_deref().unwrap_or("working");
+                let set_type = set.set_type.unwrap_or_default();
                 conn.execute(
                     "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, set_type, completed_at)
                      VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
@@ -1024,6 +1291,7 @@ pub fn import_sessions(db: &DbPool, user_id: i64, input: Vec<ImportSession>) ->
                 )?;
             }
         }
+        events::record_history(&conn, session_id)?;
     }
 
     conn.execute_batch("COMMIT")?;
@@ -1043,11 +1311,11 @@ pub fn import_sessions(db: &DbPool, user_id: i64, input: Vec<ImportSession>) ->
                         row.get::<_, Option<i64>>(0)?,
                         row.get::<_, Option<String>>(1)?,
                         row.get::<_, Option<String>>(2)?,
-                        row.get::<_, String>(3)?,
-                        row.get::<_, Option<String>>(4)?,
+                        row.get::<_, DateTime<Utc>>(3)?,
+                        row.get::<_, Option<DateTime<Utc>>>(4)?,
                         row.get::<_, i64>(5)?,
                         row.get::<_, Option<String>>(6)?,
-                        row.get::<_, String>(7)?,
+                        row.get::<_, SessionStatus>(7)?,
                     ))
                 },
             )?;
@@ -1063,6 +1331,8 @@ pub fn import_sessions(db: &DbPool, user_id: i64, input: Vec<ImportSession>) ->
             notes,
             status,
             template_version: None,
+            auto_closed: false,
+            auto_close_reason: None,
             exercises,
         });
     }
@@ -1237,4 +1507,260 @@ mod tests {
         assert!(resolve(&db, "PULL-UPS").unwrap().is_none());
         assert!(resolve(&db, "Pull Up").unwrap().is_none());
     }
+
+    /// Session for user 42 started `started` ago, with one set logged `last_set`
+    /// ago (or none). Offsets are SQLite modifiers like "-3 hours".
+    fn stale_session(db: &crate::db::DbPool, started: &str, last_set: Option<&str>) -> i64 {
+        let conn = db.lock().unwrap();
+        conn.execute(
+            "INSERT INTO sessions (user_id, started_at, status) VALUES (42, datetime('now', ?1), 'active')",
+            [started],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+        if let Some(offset) = last_set {
+            conn.execute(
+                "INSERT INTO session_exercises (session_id, exercise_id, position)
+                 SELECT ?1, id, 0 FROM exercises WHERE user_id = 42 LIMIT 1",
+                [id],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, completed_at)
+                 VALUES (?1, 1, 100, 5, datetime('now', ?2))",
+                rusqlite::params![conn.last_insert_rowid(), offset],
+            )
+            .unwrap();
+        }
+        id
+    }
+
+    #[test]
+    fn auto_close_respects_timeout_preferences() {
+        let db = test_db();
+        let idle = stale_session(&db, "-4 hours", Some("-3 hours"));
+        assert_eq!(close_all_stale_sessions(&db).unwrap(), 1);
+        let session = get(&db, 42, idle).unwrap();
+        assert_eq!(session.status, SessionStatus::Completed);
+        assert!(session.auto_closed);
+        assert_eq!(session.auto_close_reason.as_deref(), Some("idle"));
+
+        // A longer personal timeout keeps the next one open; empty sessions use
+        // their own (default 6h) timeout.
+        crate::preferences::set_preference(&db, 42, STALE_SESSION_TIMEOUT_KEY, "240").unwrap();
+        let recent = stale_session(&db, "-4 hours", Some("-3 hours"));
+        let empty = stale_session(&db, "-7 hours", None);
+        assert_eq!(close_all_stale_sessions(&db).unwrap(), 1);
+        assert_eq!(get(&db, 42, recent).unwrap().status, SessionStatus::Active);
+        let empty = get(&db, 42, empty).unwrap();
+        assert_eq!(empty.status, SessionStatus::Abandoned);
+        assert_eq!(empty.auto_close_reason.as_deref(), Some("empty"));
+
+        assert!(crate::preferences::set_preference(&db, 42, STALE_SESSION_TIMEOUT_KEY, "soon").is_err());
+    }
+
+    #[test]
+    fn reopen_restarts_idle_clock() {
+        let db = test_db();
+        let id = stale_session(&db, "-4 hours", Some("-3 hours"));
+        close_all_stale_sessions(&db).unwrap();
+
+        let session = reopen(&db, 42, id).unwrap();
+        assert_eq!(session.status, SessionStatus::Active);
+        assert!(!session.auto_closed);
+        assert!(session.ended_at.is_none());
+        assert_eq!(close_all_stale_sessions(&db).unwrap(), 0);
+
+        // Only auto-closed sessions, and not while another is in progress.
+        assert!(matches!(reopen(&db, 42, id), Err(AppError::BadRequest(_))));
+    }
+
+    fn set_status(db: &crate::db::DbPool, id: i64, status: SessionStatus) {
+        let input = UpdateSession {
+            status: Some(status),
+            notes: None,
+            paused_duration: None,
+            started_at: None,
+            ended_at: None,
+        };
+        update(db, 42, id, &input).unwrap();
+    }
+
+    #[test]
+    fn timeline_derives_paused_duration() {
+        let db = test_db();
+        let input = CreateSession {
+            template_id: None,
+            name: Some("Push".to_string()),
+            started_at: None,
+            ended_at: None,
+            status: None,
+            notes: None,
+            paused_duration: None,
+        };
+        let id = create(&db, 42, &input).unwrap().id;
+        set_status(&db, id, SessionStatus::Paused);
+        // Pretend the session began twenty minutes ago and paused ten minutes ago.
+        {
+            let conn = db.lock().unwrap();
+            conn.execute(
+                "UPDATE sessions SET started_at = datetime('now', '-20 minutes') WHERE id = ?1",
+                [id],
+            )
+            .unwrap();
+            conn.execute(
+                "UPDATE session_events SET at = datetime('now', CASE kind WHEN 'start' THEN '-20 minutes' ELSE '-10 minutes' END)
+                 WHERE session_id = ?1",
+                [id],
+            )
+            .unwrap();
+        }
+        set_status(&db, id, SessionStatus::Active);
+        assert_eq!(get(&db, 42, id).unwrap().paused_duration, 600);
+
+        // The client's own count no longer overrides the log.
+        let input = UpdateSession {
+            status: None,
+            notes: None,
+            paused_duration: Some(5),
+            started_at: None,
+            ended_at: None,
+        };
+        update(&db, 42, id, &input).unwrap();
+        assert_eq!(get(&db, 42, id).unwrap().paused_duration, 600);
+
+        let timeline = crate::session_events::timeline(&db, 42, id).unwrap();
+        let kinds: Vec<_> = timeline.events.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, ["start", "pause", "resume"]);
+        assert_eq!(timeline.pauses.len(), 1);
+        assert_eq!(timeline.paused_duration, 600);
+        assert!((599..=601).contains(&timeline.active_duration));
+        assert!(crate::session_events::timeline(&db, 7, id).is_err());
+    }
+
+    #[test]
+    fn logging_a_set_starts_the_template_rest_timer() {
+        let db = test_db();
+        let changes = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        {
+            let changes = changes.clone();
+            db.observe_changes(move |c| changes.lock().unwrap().push(c.clone()));
+        }
+        let exercise_id: i64 = {
+            let conn = db.lock().unwrap();
+            conn.execute("INSERT INTO templates (id, user_id, name) VALUES (900, 42, 'Pull')", []).unwrap();
+            let exercise_id = conn
+                .query_row("SELECT id FROM exercises WHERE name = 'PULL-UPS'", [], |row| row.get(0))
+                .unwrap();
+            conn.execute(
+                "INSERT INTO template_exercises (template_id, exercise_id, position, rest_seconds)
+                 VALUES (900, ?1, 0, 90)",
+                [exercise_id],
+            )
+            .unwrap();
+            exercise_id
+        };
+        let input = CreateSession {
+            template_id: Some(900),
+            name: None,
+            started_at: None,
+            ended_at: None,
+            status: None,
+            notes: None,
+            paused_duration: None,
+        };
+        let id = create(&db, 42, &input).unwrap().id;
+        let se = add_exercise(&db, 42, id, &AddSessionExercise { exercise_id, position: None, notes: None }).unwrap();
+        let set = CreateSet { weight_kg: None, reps: 8, set_type: None, rir: None };
+        let logged = add_set(&db, 42, se.id, &set).unwrap();
+
+        let timer = crate::rest_timer::get(&db, 42, id).unwrap().unwrap();
+        assert_eq!(timer.set_id, Some(logged.id));
+        assert_eq!(timer.duration_seconds, 90);
+        assert_eq!(timer.state, "running");
+        assert!((89..=90).contains(&timer.remaining_seconds));
+
+        let timer = crate::rest_timer::adjust(&db, 42, id, 30).unwrap();
+        assert!((119..=120).contains(&timer.remaining_seconds));
+        let timer = crate::rest_timer::skip(&db, 42, id).unwrap();
+        assert_eq!((timer.state.as_str(), timer.remaining_seconds), ("skipped", 0));
+        assert_eq!(crate::rest_timer::get_active(&db, 42).unwrap().unwrap().state, "skipped");
+        assert!(crate::rest_timer::get(&db, 7, id).is_err());
+
+        // Every mutation reached the observer, tagged with the session.
+        let changes = changes.lock().unwrap();
+        let kinds: Vec<_> = changes.iter().map(|c| c.kind()).collect();
+        assert_eq!(kinds, ["exercise_added", "set_added", "rest_timer", "rest_timer", "rest_timer"]);
+        assert!(changes.iter().all(|c| c.user_id == 42 && c.session_id == id));
+    }
+
+    #[test]
+    fn timestamps_are_normalised_and_ordered() {
+        let db = test_db();
+        let sync = |body: serde_json::Value| sync_sessions(&db, 42, serde_json::from_value(body).unwrap());
+        let pushed = sync(serde_json::json!([{
+            "started_at": "2026-10-18T07:30:00.250+11:00",
+            "ended_at": "2026-10-17T21:45:00Z",
+            "exercises": [{ "name": "Pull Up", "position": 1, "sets": [{ "reps": 8, "completed_at": "2026-10-17T20:40:00Z" }] }]
+        }]))
+        .unwrap();
+        let id = pushed.pushed[0].id;
+        let stored: (String, String, String) = db
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT s.started_at, s.ended_at, st.completed_at FROM sessions s
+                 JOIN session_exercises se ON se.session_id = s.id
+                 JOIN sets st ON st.session_exercise_id = se.id WHERE s.id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            stored,
+            ("2026-10-17 20:30:00".into(), "2026-10-17 21:45:00".into(), "2026-10-17 20:40:00".into())
+        );
+
+        // The same start in another format is a duplicate.
+        let again = sync(serde_json::json!([{ "started_at": "2026-10-17 20:30:00" }])).unwrap();
+        assert_eq!((again.pushed.len(), again.skipped), (0, 1));
+
+        let backwards = serde_json::json!([{ "started_at": "2026-10-17T20:30:00Z", "ended_at": "2026-10-17T20:00:00Z" }]);
+        assert!(matches!(sync(backwards), Err(AppError::BadRequest(_))));
+        let input = UpdateSession {
+            status: None,
+            notes: None,
+            paused_duration: None,
+            started_at: timestamp::parse("2026-10-17 22:00:00"),
+            ended_at: None,
+        };
+        assert!(matches!(update(&db, 42, id, &input), Err(AppError::BadRequest(_))));
+        assert_eq!(get(&db, 42, id).unwrap().started_at, timestamp::parse("2026-10-17 20:30:00").unwrap());
+    }
+
+    #[test]
+    fn status_and_set_type_are_checked() {
+        let db = test_db();
+        let set = |body: serde_json::Value| serde_json::from_value::<CreateSet>(body);
+        assert_eq!(set(serde_json::json!({ "reps": 5, "set_type": "amrap" })).unwrap().set_type, Some(SetType::Amrap));
+        assert!(set(serde_json::json!({ "reps": 5, "set_type": "Working" })).is_err());
+        assert!(serde_json::from_value::<UpdateSession>(serde_json::json!({ "status": "done" })).is_err());
+
+        // The schema refuses what the API would, and the SQL helpers classify
+        // like the enum.
+        let conn = db.lock().unwrap();
+        let insert = |status: &str| {
+            conn.execute("INSERT INTO sessions (user_id, status) VALUES (42, ?1)", [status])
+        };
+        assert!(insert("Completed").is_err());
+        assert!(insert("completed").is_ok());
+        for set_type in SetType::ALL {
+            let (volume, e1rm): (bool, bool) = conn
+                .query_row("SELECT counts_for_volume(?1), counts_for_e1rm(?1)", [set_type], |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .unwrap();
+            assert_eq!((volume, e1rm), (set_type.counts_for_volume(), set_type.counts_for_e1rm()), "{}", set_type);
+        }
+    }
 }
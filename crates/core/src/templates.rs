@@ -6,6 +6,7 @@ use crate::models::{
     CreateTemplate, SyncTemplate, Template, TemplateExercise, TemplateSnapshot, TemplateSyncResult,
     UpdateTemplate,
 };
+use crate::webhooks;
 
 /// Comparable content of one template exercise, ordered by position:
 /// (exercise_id, position, target_sets, reps_min, reps_max, rest_seconds, notes).
@@ -200,6 +201,8 @@ pub fn update(db: &DbPool, user_id: i64, id: i64, input: &UpdateTemplate) -> Res
         conn.execute("UPDATE templates SET updated_at = datetime('now') WHERE id = ?1", [id])?;
     }
 
+    enqueue_updated(&conn, user_id, id, current_version)?;
+
     drop(conn);
     get(db, user_id, id)
 }
@@ -452,6 +455,7 @@ fn apply_template_sync(
                     )?;
                     conn.execute("DELETE FROM template_exercises WHERE template_id = ?1", [tid])?;
                     insert_template_exercises(conn, tid, &incoming_sig)?;
+                    enqueue_updated(conn, user_id, tid, current.version)?;
                     template_ids.push(tid);
                 }
             }
@@ -461,6 +465,22 @@ fn apply_template_sync(
     Ok((template_ids, exercises_created))
 }
 
+/// Queue `template.updated` for a template that just moved past `previous_version`.
+fn enqueue_updated(conn: &rusqlite::Connection, user_id: i64, id: i64, previous_version: i64) -> rusqlite::Result<()> {
+    let (name, version): (String, i64) = conn.query_row(
+        "SELECT name, version FROM templates WHERE id = ?1",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    webhooks::enqueue(conn, user_id, webhooks::TEMPLATE_UPDATED, &serde_json::json!({
+        "template_id": id,
+        "name": name,
+        "version": version,
+        "previous_version": previous_version,
+    }))?;
+    Ok(())
+}
+
 fn insert_template_exercises(conn: &rusqlite::Connection, template_id: i64, sig: &[ExerciseSig]) -> Result<(), AppError> {
     for (exercise_id, position, target_sets, reps_min, reps_max, rest_seconds, notes) in sig {
         conn.execute(
@@ -521,6 +541,33 @@ fn read_template(conn: &rusqlite::Connection, id: i64) -> Result<Template, AppEr
     })
 }
 
+/// The template's exercises as they were at `version`: from its snapshot
+/// when the template has moved on since, otherwise the current ones.
+pub(crate) fn exercises_at_version(
+    conn: &rusqlite::Connection,
+    template_id: i64,
+    version: Option<i64>,
+) -> Result<Vec<TemplateExercise>, AppError> {
+    if let Some(version) = version {
+        let snapshot: Option<String> = conn
+            .query_row(
+                "SELECT snapshot_json FROM template_snapshots WHERE template_id = ?1 AND version = ?2",
+                rusqlite::params![template_id, version],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(json) = snapshot {
+            let parsed: Option<Vec<TemplateExercise>> = serde_json::from_str::<serde_json::Value>(&json)
+                .ok()
+                .and_then(|v| serde_json::from_value(v["exercises"].clone()).ok());
+            if let Some(exercises) = parsed {
+                return Ok(exercises);
+            }
+        }
+    }
+    get_template_exercises(conn, template_id)
+}
+
 fn get_template_exercises(conn: &rusqlite::Connection, template_id: i64) -> Result<Vec<TemplateExercise>, AppError> {
     let mut stmt = conn.prepare(
         "SELECT te.id, te.exercise_id, e.name, te.position, te.target_sets,
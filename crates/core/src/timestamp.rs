@@ -0,0 +1,75 @@
+//! Stored timestamps.
+//!
+//! Every timestamp column holds UTC text in SQLite's own format,
+//! `YYYY-MM-DD HH:MM:SS`, the same as `datetime('now')` writes. One format
+//! means timestamps sort and compare correctly as strings, against each other
+//! and against `datetime('now', …)`. Models carry them as `DateTime<Utc>` and
+//! serialize them as RFC 3339; write paths bind them with [`to_sql`].
+//!
+//! Clients may send any format [`parse`] accepts. Fractional seconds are
+//! dropped on the way in.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SubsecRound, Utc};
+use serde::{Deserialize, Deserializer};
+
+/// The stored format, as a chrono format string.
+pub const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Parse a timestamp in whole seconds. Accepts SQLite's
+/// `YYYY-MM-DD HH:MM:SS`, ISO 8601 with `T`, fractional seconds and a `Z` or
+/// offset, and a bare date (midnight UTC). Times without an offset are UTC.
+pub fn parse(ts: &str) -> Option<DateTime<Utc>> {
+    let ts = ts.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(ts) {
+        return Some(t.with_timezone(&Utc).trunc_subsecs(0));
+    }
+    let naive = ts.strip_suffix('Z').unwrap_or(ts);
+    for format in ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(t) = NaiveDateTime::parse_from_str(naive, format) {
+            return Some(t.and_utc().trunc_subsecs(0));
+        }
+    }
+    NaiveDate::parse_from_str(naive, "%Y-%m-%d").ok().map(|d| d.and_hms_opt(0, 0, 0).unwrap().and_utc())
+}
+
+/// A timestamp in the stored format, for binding as a query parameter.
+pub fn to_sql(t: &DateTime<Utc>) -> String {
+    t.format(FORMAT).to_string()
+}
+
+/// Serde `deserialize_with` for a required timestamp in any format [`parse`]
+/// accepts.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    let ts = String::deserialize(deserializer)?;
+    parse(&ts).ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp: '{}'", ts)))
+}
+
+/// Serde `deserialize_with` for an optional timestamp; use with
+/// `#[serde(default)]`.
+pub fn deserialize_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(ts) => parse(&ts).map(Some).ok_or_else(|| serde::de::Error::custom(format!("invalid timestamp: '{}'", ts))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_normalise_to_one() {
+        let canonical = "2026-10-17 20:30:00";
+        for input in [
+            "2026-10-17 20:30:00",
+            "2026-10-17T20:30:00Z",
+            "2026-10-17T20:30:00.750Z",
+            "2026-10-18T07:30:00+11:00",
+            "2026-10-17T20:30",
+        ] {
+            assert_eq!(parse(input).map(|t| to_sql(&t)).as_deref(), Some(canonical), "{}", input);
+        }
+        assert_eq!(parse("2026-10-17").map(|t| to_sql(&t)).as_deref(), Some("2026-10-17 00:00:00"));
+        assert_eq!(parse("17/10/2026"), None);
+    }
+}
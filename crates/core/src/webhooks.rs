@@ -0,0 +1,437 @@
+//! Outgoing webhook subscriptions and their outbox.
+//!
+//! Mutations that users can subscribe to enqueue one outbox row per matching
+//! active subscription while they still hold the connection. The server's
+//! delivery worker ([`due`] / [`record_attempt`]) sends them, signs
+//! each body with the subscription's secret and retries failures with
+//! exponential backoff; every attempt lands in the delivery log.
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde_json::json;
+
+use crate::analytics;
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::models::{CreateWebhook, UpdateWebhook, Webhook, WebhookCreated, WebhookDelivery};
+
+pub const SESSION_COMPLETED: &str = "session.completed";
+pub const PR_ACHIEVED: &str = "pr.achieved";
+pub const TEMPLATE_UPDATED: &str = "template.updated";
+pub const SYNC_COMPLETED: &str = "sync.completed";
+pub const DIGEST_READY: &str = "digest.ready";
+/// Sent by [`ping`] only; not subscribable.
+pub const PING: &str = "ping";
+
+pub const EVENTS: [&str; 5] = [
+    SESSION_COMPLETED,
+    PR_ACHIEVED,
+    TEMPLATE_UPDATED,
+    SYNC_COMPLETED,
+    DIGEST_READY,
+];
+
+/// Attempts before a delivery is marked failed.
+pub const MAX_ATTEMPTS: i64 = 8;
+/// First retry delay; doubles per attempt up to [`MAX_RETRY_SECS`].
+const RETRY_BASE_SECS: i64 = 30;
+const MAX_RETRY_SECS: i64 = 6 * 60 * 60;
+
+// ── Subscriptions ──
+
+fn validate_url(url: &str) -> Result<(), AppError> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| AppError::BadRequest("Webhook URL must start with http:// or https://".into()))?;
+    if rest.is_empty() || rest.starts_with('/') || url.chars().any(char::is_whitespace) {
+        return Err(AppError::BadRequest(format!("Invalid webhook URL: {}", url)));
+    }
+    Ok(())
+}
+
+fn validate_events(events: &[String]) -> Result<String, AppError> {
+    if events.is_empty() {
+        return Err(AppError::BadRequest("Subscribe to at least one event".into()));
+    }
+    for event in events {
+        if !EVENTS.contains(&event.as_str()) {
+            return Err(AppError::BadRequest(format!(
+                "Unknown event: {} (expected one of {})",
+                event,
+                EVENTS.join(", ")
+            )));
+        }
+    }
+    let mut events = events.to_vec();
+    events.sort();
+    events.dedup();
+    Ok(events.join(","))
+}
+
+fn generate_secret() -> String {
+    let mut rng = rand::thread_rng();
+    let bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+    format!("whsec_{}", hex::encode(bytes))
+}
+
+const SELECT_WEBHOOK: &str =
+    "SELECT w.id, w.url, w.events, w.description, w.active, w.created_at,
+            (SELECT COUNT(*) FROM webhook_outbox o WHERE o.webhook_id = w.id AND o.status = 'pending'),
+            (SELECT COUNT(*) FROM webhook_outbox o WHERE o.webhook_id = w.id AND o.status = 'failed')
+     FROM webhooks w";
+
+fn row_to_webhook(row: &rusqlite::Row) -> rusqlite::Result<Webhook> {
+    let events: String = row.get(2)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        events: events.split(',').map(str::to_string).collect(),
+        description: row.get(3)?,
+        active: row.get(4)?,
+        created_at: row.get(5)?,
+        pending: row.get(6)?,
+        failed: row.get(7)?,
+    })
+}
+
+fn get_webhook(conn: &rusqlite::Connection, user_id: i64, id: i64) -> Result<Webhook, AppError> {
+    conn.query_row(
+        &format!("{} WHERE w.id = ?1 AND w.user_id = ?2", SELECT_WEBHOOK),
+        rusqlite::params![id, user_id],
+        row_to_webhook,
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound,
+        e => AppError::Database(e),
+    })
+}
+
+pub fn list(db: &DbPool, user_id: i64) -> Result<Vec<Webhook>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(&format!("{} WHERE w.user_id = ?1 ORDER BY w.id", SELECT_WEBHOOK))?;
+    let webhooks = stmt
+        .query_map([user_id], row_to_webhook)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(webhooks)
+}
+
+pub fn create(db: &DbPool, user_id: i64, input: &CreateWebhook) -> Result<WebhookCreated, AppError> {
+    validate_url(&input.url)?;
+    let events = validate_events(&input.events)?;
+    let secret = generate_secret();
+
+    let conn = db.lock().unwrap();
+    conn.execute(
+        "INSERT INTO webhooks (user_id, url, secret, events, description) VALUES (?1, ?2, ?3, ?4, ?5)",
+        rusqlite::params![user_id, input.url, secret, events, input.description],
+    )?;
+    let webhook = get_webhook(&conn, user_id, conn.last_insert_rowid())?;
+    Ok(WebhookCreated { webhook, secret })
+}
+
+pub fn update(db: &DbPool, user_id: i64, id: i64, input: &UpdateWebhook) -> Result<Webhook, AppError> {
+    let conn = db.lock().unwrap();
+    get_webhook(&conn, user_id, id)?;
+
+    if let Some(ref url) = input.url {
+        validate_url(url)?;
+        conn.execute("UPDATE webhooks SET url = ?1 WHERE id = ?2", rusqlite::params![url, id])?;
+    }
+    if let Some(ref events) = input.events {
+        let events = validate_events(events)?;
+        conn.execute("UPDATE webhooks SET events = ?1 WHERE id = ?2", rusqlite::params![events, id])?;
+    }
+    if let Some(ref description) = input.description {
+        conn.execute("UPDATE webhooks SET description = ?1 WHERE id = ?2", rusqlite::params![description, id])?;
+    }
+    if let Some(active) = input.active {
+        conn.execute("UPDATE webhooks SET active = ?1 WHERE id = ?2", rusqlite::params![active, id])?;
+    }
+    get_webhook(&conn, user_id, id)
+}
+
+pub fn delete(db: &DbPool, user_id: i64, id: i64) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    let rows = conn.execute(
+        "DELETE FROM webhooks WHERE id = ?1 AND user_id = ?2",
+        rusqlite::params![id, user_id],
+    )?;
+    if rows == 0 {
+        return Err(AppError::NotFound);
+    }
+    Ok(())
+}
+
+/// Queue a `ping` to this subscription only, whatever it subscribes to.
+pub fn ping(db: &DbPool, user_id: i64, id: i64) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    get_webhook(&conn, user_id, id)?;
+    conn.execute(
+        "INSERT INTO webhook_outbox (webhook_id, event, data) VALUES (?1, ?2, ?3)",
+        rusqlite::params![id, PING, json!({ "webhook_id": id }).to_string()],
+    )?;
+    Ok(())
+}
+
+/// Delivery attempts for one subscription, newest first.
+pub fn deliveries(db: &DbPool, user_id: i64, id: i64, limit: i64) -> Result<Vec<WebhookDelivery>, AppError> {
+    let conn = db.lock().unwrap();
+    get_webhook(&conn, user_id, id)?;
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.outbox_id, o.event, d.attempted_at, d.status_code, d.error, d.duration_ms, o.status
+         FROM webhook_deliveries d JOIN webhook_outbox o ON o.id = d.outbox_id
+         WHERE d.webhook_id = ?1
+         ORDER BY d.id DESC LIMIT ?2",
+    )?;
+    let deliveries = stmt
+        .query_map(rusqlite::params![id, limit], |row| {
+            Ok(WebhookDelivery {
+                id: row.get(0)?,
+                outbox_id: row.get(1)?,
+                event: row.get(2)?,
+                attempted_at: row.get(3)?,
+                status_code: row.get(4)?,
+                error: row.get(5)?,
+                duration_ms: row.get(6)?,
+                outbox_status: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(deliveries)
+}
+
+// ── Enqueueing ──
+
+/// Queue `event` for each of the user's active subscriptions to it. Returns
+/// how many deliveries were queued.
+pub(crate) fn enqueue(
+    conn: &rusqlite::Connection,
+    user_id: i64,
+    event: &str,
+    data: &serde_json::Value,
+) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO webhook_outbox (webhook_id, event, data)
+         SELECT id, ?2, ?3 FROM webhooks
+         WHERE user_id = ?1 AND active = 1 AND instr(',' || events || ',', ',' || ?2 || ',') > 0",
+        rusqlite::params![user_id, event, data.to_string()],
+    )
+}
+
+fn subscribed(conn: &rusqlite::Connection, user_id: i64, event: &str) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM webhooks
+         WHERE user_id = ?1 AND active = 1 AND instr(',' || events || ',', ',' || ?2 || ',') > 0",
+        rusqlite::params![user_id, event],
+        |row| row.get(0),
+    )
+}
+
+/// `session.completed`, plus `pr.achieved` when the session set any PRs.
+/// Covers sessions finished by the user and ones auto-closed as idle.
+pub(crate) fn session_completed(conn: &rusqlite::Connection, user_id: i64, session_id: i64) -> rusqlite::Result<()> {
+    if subscribed(conn, user_id, SESSION_COMPLETED)? {
+        let data = conn.query_row(
+            "SELECT s.id, s.name, s.template_id, t.name, s.started_at, s.ended_at, s.auto_closed,
+                    (SELECT COUNT(*) FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id
+                     WHERE se.session_id = s.id),
+                    (SELECT COALESCE(SUM(st.weight_kg * st.reps), 0) FROM sets st
+                     JOIN session_exercises se ON se.id = st.session_exercise_id
+                     WHERE se.session_id = s.id AND counts_for_volume(st.set_type))
+             FROM sessions s LEFT JOIN templates t ON t.id = s.template_id
+             WHERE s.id = ?1",
+            [session_id],
+            |row| {
+                Ok(json!({
+                    "session_id": row.get::<_, i64>(0)?,
+                    "name": row.get::<_, Option<String>>(1)?,
+                    "template_id": row.get::<_, Option<i64>>(2)?,
+                    "template_name": row.get::<_, Option<String>>(3)?,
+                    "started_at": row.get::<_, DateTime<Utc>>(4)?,
+                    "ended_at": row.get::<_, Option<DateTime<Utc>>>(5)?,
+                    "auto_closed": row.get::<_, bool>(6)?,
+                    "set_count": row.get::<_, i64>(7)?,
+                    "tonnage_kg": row.get::<_, f64>(8)?,
+                }))
+            },
+        )?;
+        enqueue(conn, user_id, SESSION_COMPLETED, &data)?;
+    }
+    if subscribed(conn, user_id, PR_ACHIEVED)? {
+        let prs = analytics::prs_hit(conn, user_id, session_id)?;
+        if !prs.is_empty() {
+            enqueue(conn, user_id, PR_ACHIEVED, &json!({ "session_id": session_id, "prs": prs }))?;
+        }
+    }
+    Ok(())
+}
+
+// ── Delivery ──
+
+/// A queued delivery handed to the worker.
+#[derive(Debug)]
+pub struct PendingDelivery {
+    pub outbox_id: i64,
+    pub webhook_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event: String,
+    /// JSON request body.
+    pub body: String,
+}
+
+/// Deliveries that are due, oldest first, for active subscriptions.
+pub fn due(db: &DbPool, limit: i64) -> Result<Vec<PendingDelivery>, AppError> {
+    let conn = db.lock().unwrap();
+    let mut stmt = conn.prepare(
+        "SELECT o.id, o.webhook_id, w.url, w.secret, o.event, o.data, o.created_at
+         FROM webhook_outbox o JOIN webhooks w ON w.id = o.webhook_id
+         WHERE o.status = 'pending' AND w.active = 1 AND o.next_attempt_at <= datetime('now')
+         ORDER BY o.id LIMIT ?1",
+    )?;
+    let due = stmt
+        .query_map([limit], |row| {
+            let outbox_id: i64 = row.get(0)?;
+            let event: String = row.get(4)?;
+            let data: String = row.get(5)?;
+            let created_at: DateTime<Utc> = row.get(6)?;
+            let data: serde_json::Value = serde_json::from_str(&data).unwrap_or(serde_json::Value::Null);
+            let body = json!({
+                "id": outbox_id,
+                "event": event,
+                "created_at": created_at,
+                "data": data,
+            });
+            Ok(PendingDelivery {
+                outbox_id,
+                webhook_id: row.get(1)?,
+                url: row.get(2)?,
+                secret: row.get(3)?,
+                event,
+                body: body.to_string(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(due)
+}
+
+/// Log an attempt. A 2xx response marks the delivery done; anything else
+/// schedules a retry after 30s, 1m, 2m, ... (capped at 6h) until
+/// [`MAX_ATTEMPTS`], then marks it failed.
+pub fn record_attempt(
+    db: &DbPool,
+    outbox_id: i64,
+    status_code: Option<u16>,
+    error: Option<&str>,
+    duration_ms: i64,
+) -> Result<(), AppError> {
+    let conn = db.lock().unwrap();
+    conn.execute(
+        "INSERT INTO webhook_deliveries (outbox_id, webhook_id, status_code, error, duration_ms)
+         SELECT id, webhook_id, ?2, ?3, ?4 FROM webhook_outbox WHERE id = ?1",
+        rusqlite::params![outbox_id, status_code, error, duration_ms],
+    )?;
+
+    let delivered = status_code.is_some_and(|code| (200..300).contains(&code));
+    if delivered {
+        conn.execute(
+            "UPDATE webhook_outbox SET status = 'delivered', attempts = attempts + 1,
+                 delivered_at = datetime('now')
+             WHERE id = ?1",
+            [outbox_id],
+        )?;
+        return Ok(());
+    }
+
+    let attempts: i64 = conn.query_row(
+        "UPDATE webhook_outbox SET attempts = attempts + 1 WHERE id = ?1 RETURNING attempts",
+        [outbox_id],
+        |row| row.get(0),
+    )?;
+    if attempts >= MAX_ATTEMPTS {
+        conn.execute("UPDATE webhook_outbox SET status = 'failed' WHERE id = ?1", [outbox_id])?;
+    } else {
+        let delay = (RETRY_BASE_SECS << (attempts - 1).min(20)).min(MAX_RETRY_SECS);
+        conn.execute(
+            "UPDATE webhook_outbox SET next_attempt_at = datetime('now', '+' || ?1 || ' seconds') WHERE id = ?2",
+            rusqlite::params![delay, outbox_id],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SessionStatus, UpdateSession};
+
+    /// An earlier bench session at 100x5 and an open one at 105x5.
+    fn seeded_db() -> DbPool {
+        let db = crate::db::init_memory_db().unwrap();
+        db.lock()
+            .unwrap()
+            .execute_batch(
+                "INSERT INTO users (id, username, password_hash) VALUES (42, 'testuser', 'x');
+                 INSERT INTO exercises (id, user_id, name) VALUES (901, 42, 'BENCH PRESS');
+                 INSERT INTO sessions (id, user_id, started_at, ended_at, status) VALUES
+                     (901, 42, '2026-10-01 18:00:00', '2026-10-01 19:00:00', 'completed'),
+                     (902, 42, '2026-10-08 18:00:00', NULL, 'active');
+                 INSERT INTO session_exercises (id, session_id, exercise_id, position) VALUES
+                     (901, 901, 901, 0), (902, 902, 901, 0);
+                 INSERT INTO sets (session_exercise_id, set_number, weight_kg, reps, set_type) VALUES
+                     (901, 1, 100, 5, 'working'), (902, 1, 105, 5, 'working');",
+            )
+            .unwrap();
+        db
+    }
+
+    fn queued(db: &DbPool) -> Vec<String> {
+        let conn = db.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT event FROM webhook_outbox ORDER BY id").unwrap();
+        let events = stmt.query_map([], |row| row.get(0)).unwrap();
+        events.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn finishing_a_session_queues_completion_and_prs() {
+        let db = seeded_db();
+        let hook = CreateWebhook {
+            url: "https://example.com/hook".into(),
+            events: vec![SESSION_COMPLETED.into(), PR_ACHIEVED.into()],
+            description: None,
+        };
+        let created = create(&db, 42, &hook).unwrap();
+        assert!(created.secret.starts_with("whsec_"));
+
+        let finish = UpdateSession {
+            status: Some(SessionStatus::Completed),
+            notes: None,
+            paused_duration: None,
+            started_at: None,
+            ended_at: None,
+        };
+        crate::sessions::update(&db, 42, 902, &finish).unwrap();
+        assert_eq!(queued(&db), [SESSION_COMPLETED, PR_ACHIEVED]);
+
+        let due = due(&db, 10).unwrap();
+        // Timestamps in the API's RFC 3339, not the stored format.
+        let body: serde_json::Value = serde_json::from_str(&due[0].body).unwrap();
+        assert_eq!(body["data"]["started_at"], "2026-10-08T18:00:00Z");
+        for ts in [&body["created_at"], &body["data"]["ended_at"]] {
+            assert!(DateTime::parse_from_rfc3339(ts.as_str().unwrap()).is_ok(), "{}", ts);
+        }
+        let body: serde_json::Value = serde_json::from_str(&due[1].body).unwrap();
+        assert_eq!(body["data"]["prs"][0]["weight_kg"], 105.0);
+        assert!(body["data"]["prs"][0]["previous_best"].as_f64().is_some());
+    }
+
+    #[test]
+    fn rejects_unknown_events_and_bad_urls() {
+        let db = seeded_db();
+        let bad_event = CreateWebhook { url: "https://example.com".into(), events: vec!["set.added".into()], description: None };
+        assert!(matches!(create(&db, 42, &bad_event), Err(AppError::BadRequest(_))));
+        let bad_url = CreateWebhook { url: "ftp://example.com".into(), events: vec![SYNC_COMPLETED.into()], description: None };
+        assert!(matches!(create(&db, 42, &bad_url), Err(AppError::BadRequest(_))));
+    }
+}
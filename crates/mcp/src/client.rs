@@ -63,9 +63,7 @@ impl Client {
             .map_err(|e| format!("Request failed: {}", e))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(format!("API error {}: {}", status, text));
+            return Err(error_message(resp).await);
         }
 
         resp.json().await.map_err(|e| format!("Parse error: {}", e))
@@ -87,9 +85,7 @@ impl Client {
             .map_err(|e| format!("Request failed: {}", e))?;
 
         if !resp.status().is_success() {
-            let status = resp.status();
-            let text = resp.text().await.unwrap_or_default();
-            return Err(format!("API error {}: {}", status, text));
+            return Err(error_message(resp).await);
         }
 
         resp.json().await.map_err(|e| format!("Parse error: {}", e))
@@ -133,6 +129,22 @@ impl Client {
     }
 }
 
+/// Error text for a non-2xx response. Prefers the server's structured
+/// `{"error": {"code", "message"}}` body so the model sees the real reason
+/// (e.g. which exercises an ambiguous name matched), not just a status code.
+async fn error_message(resp: reqwest::Response) -> String {
+    let status = resp.status();
+    let text = resp.text().await.unwrap_or_default();
+    let body: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+    match (body["error"]["message"].as_str(), body["error"]["code"].as_str()) {
+        (Some(message), Some(code)) => match body["error"]["details"]["reason"].as_str() {
+            Some(reason) => format!("API error {} ({}): {}: {}", status, code, message, reason),
+            None => format!("API error {} ({}): {}", status, code, message),
+        },
+        _ => format!("API error {}: {}", status, text),
+    }
+}
+
 fn config_path() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -45,12 +45,26 @@ pub struct ExerciseParam {
     pub until: Option<String>,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct RepMaxParam {
+    /// Exercise name (fuzzy match, e.g. "bench press")
+    pub exercise: String,
+    /// Reps in reserve to prescribe at, 0-5 (default 0: true rep maxes)
+    pub rir: Option<i64>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct DaysParam {
     /// Number of days to look back (default 30)
     pub days: Option<i64>,
 }
 
+#[derive(Deserialize, JsonSchema)]
+pub struct PlateauParam {
+    /// Sessions without a new best e1RM before a lift counts as stalled (default 4)
+    pub exposures: Option<i64>,
+}
+
 #[derive(Deserialize, JsonSchema)]
 pub struct DateRangeParam {
     /// Start date YYYY-MM-DD
@@ -144,6 +158,14 @@ impl LightweightMcp {
         }
     }
 
+    #[tool(description = "Post-workout report for a session: PRs hit, e1RM gap and reps-to-beat for exercises that missed, volume change vs the previous session of the same template, adherence to template targets, and anomalies such as rep drop-offs or skipped exercises")]
+    async fn session_report(&self, Parameters(p): Parameters<SessionIdParam>) -> String {
+        match self.client.get(&format!("/sessions/{}/report", p.session_id)).await {
+            Ok(data) => json_out(&data),
+            Err(e) => format!("Error: {e}"),
+        }
+    }
+
     // ── Analytics ──
 
     #[tool(description = "Full analytics report: watched exercise e1RM history, trends, biggest movers, and session frequency")]
@@ -187,6 +209,23 @@ impl LightweightMcp {
         }
     }
 
+    #[tool(description = "Estimated 1-15 rep maxes from an exercise's current e1RM, or the loads for 1-15 reps at a given RIR (RPE chart)")]
+    async fn rep_maxes(&self, Parameters(p): Parameters<RepMaxParam>) -> String {
+        let id = match self.client.resolve_exercise(&p.exercise).await {
+            Ok(id) => id,
+            Err(e) => return format!("Error: {e}"),
+        };
+        let rir = p.rir.unwrap_or(0).to_string();
+        match self
+            .client
+            .get_with_query(&format!("/analytics/rep-maxes/{}", id), &[("rir", rir.as_str())])
+            .await
+        {
+            Ok(data) => json_out(&data),
+            Err(e) => format!("Error: {e}"),
+        }
+    }
+
     #[tool(description = "Biggest estimated 1RM gainers and losers over a period")]
     async fn e1rm_movers(&self, Parameters(p): Parameters<DaysParam>) -> String {
         let d = p.days.unwrap_or(30).to_string();
@@ -255,6 +294,19 @@ impl LightweightMcp {
         }
     }
 
+    #[tool(description = "Stalled lifts: no new best e1RM in N sessions and no upward trend, with how long they've stalled, the change in effort (RPE from RIR), and a suggested intervention: deload, rep_range or variation_swap")]
+    async fn plateaus(&self, Parameters(p): Parameters<PlateauParam>) -> String {
+        let n = p.exposures.unwrap_or(4).to_string();
+        match self
+            .client
+            .get_with_query("/analytics/plateaus", &[("exposures", n.as_str())])
+            .await
+        {
+            Ok(data) => json_out(&data),
+            Err(e) => format!("Error: {e}"),
+        }
+    }
+
     #[tool(description = "Show the current watched exercises list (exercises tracked in analytics report)")]
     async fn watchlist(&self) -> String {
         let ids: Vec<i64> = match self.client.get("/preferences/watched_exercises").await {
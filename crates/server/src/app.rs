@@ -1,59 +1,111 @@
 use axum::{middleware, Router};
 use lightweight_core::db::DbPool;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 
+use crate::config::Config;
+use crate::health;
+use crate::metrics::{self, Metrics};
+use crate::push::PushBus;
+use crate::rate_limit::{self, RateLimiter};
 use crate::routes;
+use crate::security_headers::{self, SecurityHeaders};
 use crate::static_files;
-
-/// Per-IP rate limit tracking: (request_count, window_start)
-pub type RateLimitStore = Mutex<HashMap<String, (u32, Instant)>>;
+use crate::telemetry;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DbPool,
-    pub rate_limits: Arc<RateLimitStore>,
+    pub rate_limiter: Arc<RateLimiter>,
     pub http_client: reqwest::Client,
+    pub config: Config,
+    pub metrics: Arc<Metrics>,
+    pub security_headers: Arc<SecurityHeaders>,
+    pub push: Arc<PushBus>,
 }
 
-pub fn create_app(db: DbPool) -> Router {
-    let state = Arc::new(AppState {
-        db,
-        rate_limits: Arc::new(Mutex::new(HashMap::new())),
-        http_client: reqwest::Client::new(),
-    });
-
-    let cors = if std::env::var("LW_CORS_ORIGIN").is_ok() {
-        let origin = std::env::var("LW_CORS_ORIGIN").unwrap();
+pub fn create_app(db: DbPool, config: Config, push: Arc<PushBus>) -> Router {
+    let cors = if let Some(origin) = &config.server.cors_origin {
+        // Checked by `Config::validate`.
         CorsLayer::new()
             .allow_origin(origin.parse::<axum::http::HeaderValue>().unwrap())
             .allow_methods(Any)
             .allow_headers(Any)
+            .expose_headers([telemetry::REQUEST_ID.clone()])
     } else {
         CorsLayer::new()
             .allow_origin(Any)
             .allow_methods(Any)
             .allow_headers(Any)
+            .expose_headers([telemetry::REQUEST_ID.clone()])
     };
 
-    // Public routes (no auth, rate limited)
+    let metrics = Arc::new(Metrics::default());
+    {
+        let metrics = metrics.clone();
+        db.observe_lock_wait(move |waited| metrics.observe_db_lock_wait(waited));
+    }
+    {
+        let push = push.clone();
+        db.observe_changes(move |change| push.publish(change));
+    }
+
+    let security_headers = Arc::new(SecurityHeaders::new(
+        &config.security,
+        &static_files::inline_script_hashes(),
+    ));
+
+    let state = Arc::new(AppState {
+        db,
+        rate_limiter: Arc::new(RateLimiter::new(&config.rate_limit.trusted_proxies)),
+        http_client: reqwest::Client::new(),
+        config,
+        metrics,
+        security_headers,
+        push,
+    });
+
+    // Public routes (no auth, rate limited per client IP)
     let public = routes::public_routes().layer(middleware::from_fn_with_state(
         state.clone(),
-        crate::auth::rate_limit_middleware,
+        rate_limit::limit_public,
     ));
 
-    // Protected routes (require auth)
+    // Protected routes (require auth; writes and analytics rate limited per user).
+    // The last layer added runs first, so auth has set `UserId` before the limiter.
     let protected = routes::protected_routes()
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit::limit_user,
+        ))
         .layer(middleware::from_fn_with_state(
             state.clone(),
             crate::auth::auth_middleware,
         ));
 
     Router::new()
-        .nest("/api/v1", public.merge(protected))
+        .nest("/api/v1", public.merge(protected).merge(routes::docs_routes()))
+        .merge(health::routes())
         .fallback(static_files::static_handler)
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            metrics::track_requests,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            security_headers::security_headers,
+        ))
+        .layer(middleware::from_fn(telemetry::request_span))
         .layer(cors)
         .with_state(state)
 }
+
+/// The full app over a fresh in-memory database, for handler and middleware
+/// tests. Returns the database too so tests can arrange rows.
+#[cfg(test)]
+pub fn test_app(config_toml: &str) -> (Router, DbPool) {
+    let db = lightweight_core::db::init_memory_db().unwrap();
+    let config = Config::from_toml(config_toml).unwrap();
+    let app = create_app(db.clone(), config, Arc::new(PushBus::default()));
+    (app, db)
+}
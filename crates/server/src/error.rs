@@ -0,0 +1,300 @@
+use axum::{
+    extract::rejection::{JsonRejection, PathRejection, QueryRejection},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use lightweight_core::error::AppError;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// JSON error returned by every `/api/v1` handler.
+///
+/// Wire shape (stable — clients match on `code`, never on `message`):
+/// `{ "error": { "code": "bad_request", "message": "...", "details": null } }`
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+    pub details: Option<serde_json::Value>,
+    /// Sent as `Retry-After` (seconds) when set.
+    pub retry_after: Option<u64>,
+}
+
+/// Serialized form of `ApiError`; also the `default` response in the OpenAPI spec.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: ErrorPayload,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorPayload {
+    pub code: String,
+    pub message: String,
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            code,
+            message: message.into(),
+            details: None,
+            retry_after: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// 429 with `Retry-After`; the delay is mirrored in `details` for clients
+    /// that only read the body.
+    pub fn rate_limited(retry_after_secs: u64) -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS, "rate_limited", "Too many requests")
+            .with_retry_after(retry_after_secs)
+    }
+
+    pub fn with_retry_after(mut self, secs: u64) -> Self {
+        self.retry_after = Some(secs);
+        self.with_details(serde_json::json!({ "retry_after_seconds": secs }))
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", "Unauthorized")
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    /// A route switched off in the server config's `[features]` table.
+    pub fn feature_disabled(feature: &str) -> Self {
+        Self::new(
+            StatusCode::NOT_FOUND,
+            "feature_disabled",
+            format!("{} is disabled on this server", feature),
+        )
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", message)
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::Database(err) => {
+                // Never leak SQL to clients — log it and hand back a generic message.
+                tracing::error!("database error: {}", err);
+                ApiError::internal("Internal server error")
+            }
+            AppError::NotFound => ApiError::new(StatusCode::NOT_FOUND, "not_found", "Not found"),
+            AppError::AlreadyExists => {
+                ApiError::new(StatusCode::CONFLICT, "already_exists", "Already exists")
+            }
+            AppError::Unauthorized => ApiError::unauthorized(),
+            AppError::BadRequest(msg) => ApiError::new(StatusCode::BAD_REQUEST, "bad_request", msg),
+            AppError::InvalidInviteCode => ApiError::new(
+                StatusCode::FORBIDDEN,
+                "invalid_invite_code",
+                "Invalid invite code",
+            ),
+            AppError::UsernameTaken => ApiError::new(
+                StatusCode::CONFLICT,
+                "username_taken",
+                "Username already taken",
+            ),
+            AppError::InvalidUsername(reason) => ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "invalid_username",
+                format!("Invalid username: {}", reason),
+            )
+            .with_details(serde_json::json!({ "reason": reason })),
+            AppError::InviteQuotaExceeded => ApiError::new(
+                StatusCode::FORBIDDEN,
+                "invite_quota_exceeded",
+                "Invite quota exceeded",
+            ),
+            AppError::WeakPassword => ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "weak_password",
+                "Password too short",
+            ),
+        }
+    }
+}
+
+impl ApiError {
+    /// A request body, path or query string that didn't parse. Keeps the
+    /// rejection's status (400, 415 or 422) and puts serde's reason in
+    /// `details` as `{"location", "reason"}`.
+    fn validation_failed(status: StatusCode, location: &'static str, reason: String) -> Self {
+        let message = match location {
+            "body" => "Invalid request body",
+            "path" => "Invalid path parameter",
+            _ => "Invalid query string",
+        };
+        Self::new(status, "validation_failed", message)
+            .with_details(serde_json::json!({ "location": location, "reason": reason }))
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::validation_failed(rejection.status(), "body", rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        ApiError::validation_failed(rejection.status(), "path", rejection.body_text())
+    }
+}
+
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::validation_failed(rejection.status(), "query", rejection.body_text())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: ErrorPayload {
+                code: self.code.to_string(),
+                message: self.message,
+                details: self.details,
+            },
+        };
+        let mut response = (self.status, Json(body)).into_response();
+        if let Some(secs) = self.retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, header::HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+
+/// For handlers that query the connection directly (admin views): lifts
+/// a `rusqlite::Error` through `AppError::Database` so it is logged, not leaked.
+pub fn db_error(e: impl Into<AppError>) -> ApiError {
+    ApiError::from(e.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extract::{Json, Path, Query};
+    use axum::{body::Body, http::Request, routing::{get, post}, Router};
+    use tower::ServiceExt;
+
+    #[derive(serde::Deserialize)]
+    struct NewSet {
+        #[allow(dead_code)]
+        reps: i64,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Page {
+        #[allow(dead_code)]
+        limit: i64,
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/missing", get(|| async { Err::<(), _>(ApiError::from(AppError::NotFound)) }))
+            .route("/bad", get(|| async { Err::<(), _>(ApiError::from(AppError::BadRequest("reps must be positive".into()))) }))
+            .route("/limited", get(|| async { Err::<(), _>(ApiError::rate_limited(30)) }))
+            .route("/sets", post(|Json(_): Json<NewSet>| async {}))
+            .route("/sets/:id", get(|Path(_): Path<i64>| async {}))
+            .route("/page", get(|Query(_): Query<Page>| async {}))
+    }
+
+    async fn send(request: Request<Body>) -> (StatusCode, axum::http::HeaderMap, serde_json::Value) {
+        let response = app().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, headers, serde_json::from_slice(&bytes).expect("JSON error body"))
+    }
+
+    fn get_req(uri: &str) -> Request<Body> {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    fn post_json(body: &str) -> Request<Body> {
+        Request::post("/sets")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn not_found_shape() {
+        let (status, _, body) = send(get_req("/missing")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body,
+            serde_json::json!({ "error": { "code": "not_found", "message": "Not found", "details": null } })
+        );
+    }
+
+    #[tokio::test]
+    async fn bad_request_carries_message() {
+        let (status, _, body) = send(get_req("/bad")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "bad_request");
+        assert_eq!(body["error"]["message"], "reps must be positive");
+    }
+
+    #[tokio::test]
+    async fn rate_limited_sets_retry_after() {
+        let (status, headers, body) = send(get_req("/limited")).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(headers[header::RETRY_AFTER], "30");
+        assert_eq!(body["error"]["code"], "rate_limited");
+        assert_eq!(body["error"]["details"]["retry_after_seconds"], 30);
+    }
+
+    #[tokio::test]
+    async fn malformed_body_is_validation_failed() {
+        let (status, _, body) = send(post_json("{\"reps\": ")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "validation_failed");
+        assert_eq!(body["error"]["details"]["location"], "body");
+
+        // Well-formed JSON of the wrong type: serde's reason names the field.
+        let (status, _, body) = send(post_json("{\"reps\": \"five\"}")).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"]["code"], "validation_failed");
+        assert!(body["error"]["details"]["reason"].as_str().unwrap().contains("reps"));
+    }
+
+    #[tokio::test]
+    async fn missing_content_type_is_validation_failed() {
+        let request = Request::post("/sets").body(Body::from("{\"reps\": 5}")).unwrap();
+        let (status, _, body) = send(request).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(body["error"]["code"], "validation_failed");
+    }
+
+    #[tokio::test]
+    async fn bad_path_and_query_are_validation_failed() {
+        let (status, _, body) = send(get_req("/sets/abc")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "validation_failed");
+        assert_eq!(body["error"]["details"]["location"], "path");
+
+        let (status, _, body) = send(get_req("/page?limit=ten")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["details"]["location"], "query");
+    }
+}
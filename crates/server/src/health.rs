@@ -0,0 +1,74 @@
+//! Liveness and readiness probes, served at the root (outside `/api/v1`).
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use std::sync::Arc;
+
+use crate::app::AppState;
+use crate::error::{db_error, ApiError};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/metrics", get(crate::metrics::metrics_handler))
+}
+
+/// The process is up and serving requests. Never touches the database.
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// The database answers queries and every migration on disk has been applied.
+async fn readyz(State(state): State<Arc<AppState>>) -> Result<Json<serde_json::Value>, ApiError> {
+    let conn = state.db.lock().unwrap();
+    conn.query_row("SELECT 1", [], |row| row.get::<_, i64>(0))
+        .map_err(|e| not_ready("Database unreachable").with_details(serde_json::json!({ "reason": e.to_string() })))?;
+
+    let pending = lightweight_core::db::pending_migrations(&conn).map_err(db_error)?;
+    if !pending.is_empty() {
+        return Err(not_ready("Migrations pending")
+            .with_details(serde_json::json!({ "pending_migrations": pending })));
+    }
+
+    Ok(Json(serde_json::json!({ "status": "ready" })))
+}
+
+fn not_ready(message: &str) -> ApiError {
+    ApiError::new(StatusCode::SERVICE_UNAVAILABLE, "not_ready", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn ready_when_migrations_are_applied() {
+        let (app, _db) = crate::app::test_app("");
+        let (status, body) = get_json(app, "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ready");
+    }
+
+    #[tokio::test]
+    async fn not_ready_lists_pending_migrations() {
+        let (app, db) = crate::app::test_app("");
+        db.lock()
+            .unwrap()
+            .execute("DELETE FROM _migrations WHERE name = '022_bodyweights.sql'", [])
+            .unwrap();
+        let (status, body) = get_json(app, "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["error"]["code"], "not_ready");
+        assert_eq!(body["error"]["details"]["pending_migrations"], serde_json::json!(["022_bodyweights.sql"]));
+    }
+}
@@ -0,0 +1,245 @@
+//! In-process job scheduler.
+//!
+//! Jobs are registered in the `jobs` table at startup with their configured
+//! intervals. Every `jobs.poll_secs` the scheduler claims whatever is due (or
+//! was requested with `lw-admin jobs run`), runs it on the blocking pool and
+//! records the outcome, so schedules and failure history survive restarts.
+//! Jobs run one at a time; each takes the database mutex only briefly.
+
+use lightweight_core::db::DbPool;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Job {
+    /// Auto-close idle and empty workouts per user preference (see
+    /// `sessions::close_stale`).
+    CloseStaleSessions,
+    /// Delete expired login tokens.
+    PurgeAuthSessions,
+    /// Passive WAL checkpoint so the `-wal` file doesn't grow unbounded.
+    WalCheckpoint,
+    /// `VACUUM INTO` snapshot under `backup.dir`, pruned to `backup.keep`.
+    Backup,
+    /// Generate last week's digest for users whose local week has rolled over
+    /// (see `digest::generate_due`) and email the ones that asked for it.
+    WeeklyDigests,
+}
+
+impl Job {
+    pub const ALL: [Job; 5] = [
+        Job::CloseStaleSessions,
+        Job::PurgeAuthSessions,
+        Job::WalCheckpoint,
+        Job::Backup,
+        Job::WeeklyDigests,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Job::CloseStaleSessions => "close_stale_sessions",
+            Job::PurgeAuthSessions => "purge_auth_sessions",
+            Job::WalCheckpoint => "wal_checkpoint",
+            Job::Backup => "backup",
+            Job::WeeklyDigests => "weekly_digests",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Job> {
+        Job::ALL.into_iter().find(|job| job.name() == name)
+    }
+
+    /// Seconds between scheduled runs; 0 means manual only.
+    fn interval(self, config: &Config) -> u64 {
+        let jobs = &config.jobs;
+        match self {
+            Job::CloseStaleSessions => jobs.close_stale_sessions_secs,
+            Job::PurgeAuthSessions => jobs.purge_auth_sessions_secs,
+            Job::WalCheckpoint => jobs.wal_checkpoint_secs,
+            Job::Backup if config.backup.dir.is_some() => jobs.backup_secs,
+            Job::Backup => 0,
+            Job::WeeklyDigests => jobs.weekly_digests_secs,
+        }
+    }
+
+    /// Run once, returning a one-line summary for the job log.
+    fn run(self, db: &DbPool, config: &Config) -> Result<String, String> {
+        match self {
+            Job::CloseStaleSessions => lightweight_core::sessions::close_all_stale_sessions(db)
+                .map(|n| format!("closed {} session(s)", n))
+                .map_err(|e| e.to_string()),
+            Job::PurgeAuthSessions => lightweight_core::auth::purge_expired_sessions(db)
+                .map(|n| format!("removed {} expired token(s)", n))
+                .map_err(|e| e.to_string()),
+            Job::WalCheckpoint => {
+                let conn = db.lock().unwrap();
+                conn.query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |row| {
+                    Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(2)?))
+                })
+                .map(|(busy, pages)| {
+                    if busy != 0 {
+                        format!("checkpointed {} page(s), readers still active", pages)
+                    } else {
+                        format!("checkpointed {} page(s)", pages)
+                    }
+                })
+                .map_err(|e| e.to_string())
+            }
+            Job::Backup => {
+                let dir = config.backup.dir.as_ref().ok_or("backup.dir is not set")?;
+                backup_database(db, dir, config.backup.keep)
+                    .map(|path| format!("wrote {}", path.display()))
+            }
+            Job::WeeklyDigests => {
+                let generated =
+                    lightweight_core::digest::generate_due(db, chrono::Utc::now()).map_err(|e| e.to_string())?;
+                if !config.mail.enabled() {
+                    return Ok(format!("generated {} digest(s)", generated));
+                }
+                crate::mail::send_digests(db, &config.mail)
+                    .map(|emailed| format!("generated {} digest(s), emailed {}", generated, emailed))
+                    .map_err(|e| format!("generated {} digest(s), {}", generated, e))
+            }
+        }
+    }
+}
+
+/// Register every job and start the polling loop. Does nothing when
+/// `jobs.enabled` is off.
+pub fn spawn(db: DbPool, config: Config) {
+    if !config.jobs.enabled {
+        tracing::info!("Background jobs disabled");
+        return;
+    }
+    for job in Job::ALL {
+        let interval = job.interval(&config) as i64;
+        if let Err(e) = lightweight_core::jobs::register(&db, job.name(), interval) {
+            tracing::error!(job = job.name(), "Could not register job: {}", e);
+        }
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(config.jobs.poll_secs));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            let (db, config) = (db.clone(), config.clone());
+            if let Err(e) = tokio::task::spawn_blocking(move || run_due(&db, &config)).await {
+                tracing::error!("Job runner panicked: {}", e);
+            }
+        }
+    });
+}
+
+/// Claim and run everything that is due. Blocking.
+fn run_due(db: &DbPool, config: &Config) {
+    let due = match lightweight_core::jobs::claim_due(db) {
+        Ok(due) => due,
+        Err(e) => {
+            tracing::error!("Could not read job schedule: {}", e);
+            return;
+        }
+    };
+    for name in due {
+        let start = Instant::now();
+        let outcome = match Job::from_name(&name) {
+            Some(job) => job.run(db, config),
+            None => Err("unknown job".to_string()),
+        };
+        let elapsed = start.elapsed();
+        match &outcome {
+            Ok(message) => {
+                tracing::info!(job = %name, duration_ms = elapsed.as_millis() as u64, "{}", message)
+            }
+            Err(e) => {
+                tracing::error!(job = %name, duration_ms = elapsed.as_millis() as u64, "job failed: {}", e)
+            }
+        }
+        let outcome = outcome.as_deref().map_err(String::as_str);
+        if let Err(e) =
+            lightweight_core::jobs::record_run(db, &name, outcome, elapsed.as_millis() as i64)
+        {
+            tracing::error!(job = %name, "Could not record job run: {}", e);
+        }
+    }
+}
+
+/// Snapshot the database into `dir` as `lightweight-<timestamp>.db` and prune
+/// to the newest `keep` snapshots.
+pub fn backup_database(db: &DbPool, dir: &Path, keep: usize) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let path = dir.join(format!("lightweight-{}.db", stamp));
+    lightweight_core::db::backup(db, &path).map_err(|e| e.to_string())?;
+
+    // Timestamped names sort chronologically.
+    let mut snapshots: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("lightweight-") && n.ends_with(".db"))
+        })
+        .collect();
+    snapshots.sort();
+    let excess = snapshots.len().saturating_sub(keep);
+    for old in &snapshots[..excess] {
+        if let Err(e) = std::fs::remove_file(old) {
+            tracing::warn!("Could not prune old backup {}: {}", old.display(), e);
+        }
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lightweight_core::jobs;
+
+    fn job<'a>(all: &'a [lightweight_core::models::Job], name: &str) -> &'a lightweight_core::models::Job {
+        all.iter().find(|j| j.name == name).unwrap()
+    }
+
+    #[test]
+    fn runs_due_jobs_and_records_outcome() {
+        let db = lightweight_core::db::init_memory_db().unwrap();
+        let config = Config::default();
+        for job in Job::ALL {
+            jobs::register(&db, job.name(), job.interval(&config) as i64).unwrap();
+        }
+
+        run_due(&db, &config);
+        let all = jobs::list(&db).unwrap();
+        let stale = job(&all, "close_stale_sessions");
+        assert_eq!(stale.last_status.as_deref(), Some("ok"));
+        assert_eq!(stale.run_count, 1);
+        // Backup has no directory configured, so it is manual-only and never ran.
+        assert_eq!(job(&all, "backup").run_count, 0);
+        // Nothing is due again until the interval passes.
+        assert!(jobs::claim_due(&db).unwrap().is_empty());
+    }
+
+    #[test]
+    fn manual_request_and_failure_backoff() {
+        let db = lightweight_core::db::init_memory_db().unwrap();
+        let config = Config::default();
+        jobs::register(&db, "backup", 0).unwrap();
+        db.lock()
+            .unwrap()
+            .execute("UPDATE jobs SET run_requested = 1 WHERE name = 'backup'", [])
+            .unwrap();
+
+        run_due(&db, &config);
+        let all = jobs::list(&db).unwrap();
+        let backup = job(&all, "backup");
+        assert_eq!(backup.last_status.as_deref(), Some("failed"));
+        assert_eq!(backup.last_message.as_deref(), Some("backup.dir is not set"));
+        assert_eq!(backup.consecutive_failures, 1);
+        assert!(!backup.run_requested);
+        assert!(jobs::claim_due(&db).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,60 @@
+//! Outgoing email over SMTP, configured by `[mail]`.
+//!
+//! Only weekly digests are mailed for now, from the `weekly_digests` job, so
+//! sending is blocking and happens on the job runner's thread.
+
+use lettre::message::{Mailbox, MultiPart};
+use lettre::{Message, SmtpTransport, Transport};
+use lightweight_core::db::DbPool;
+
+use crate::config::MailConfig;
+
+/// Digests emailed per run; the rest go out on the next one.
+const BATCH: i64 = 100;
+
+/// Email every digest waiting for it. Returns how many were sent. A digest
+/// that fails to send is left for the next run; the first error is returned
+/// once the rest have been tried.
+pub fn send_digests(db: &DbPool, config: &MailConfig) -> Result<usize, String> {
+    let (Some(url), Some(from)) = (&config.smtp_url, &config.from) else {
+        return Ok(0);
+    };
+    let from: Mailbox = from.parse().map_err(|e| format!("mail.from: {}", e))?;
+    let pending = lightweight_core::digest::pending_emails(db, BATCH).map_err(|e| e.to_string())?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let transport = SmtpTransport::from_url(url).map_err(|e| e.to_string())?.build();
+
+    let mut sent = 0;
+    let mut first_error = None;
+    for email in pending {
+        let outcome = email
+            .to
+            .parse::<Mailbox>()
+            .map_err(|e| format!("{}: {}", email.to, e))
+            .and_then(|to| {
+                Message::builder()
+                    .from(from.clone())
+                    .to(to)
+                    .subject(&email.subject)
+                    .multipart(MultiPart::alternative_plain_html(email.markdown, email.html))
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|message| transport.send(&message).map_err(|e| e.to_string()));
+        match outcome {
+            Ok(_) => {
+                lightweight_core::digest::mark_emailed(db, email.id).map_err(|e| e.to_string())?;
+                sent += 1;
+            }
+            Err(e) => {
+                tracing::warn!(digest_id = email.id, "Could not email digest: {}", e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    match first_error {
+        Some(e) => Err(format!("emailed {}, failed: {}", sent, e)),
+        None => Ok(sent),
+    }
+}
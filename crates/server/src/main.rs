@@ -1,33 +1,119 @@
 mod app;
 mod auth;
+mod config;
+mod error;
+mod extract;
 mod google;
+mod health;
+mod jobs;
+mod mail;
+mod metrics;
+mod push;
+mod rate_limit;
 mod routes;
+mod security_headers;
 mod static_files;
+mod telemetry;
+mod tls;
+mod webhooks;
 
-use tracing_subscriber;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
+    let config = match config::Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprint!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    telemetry::init(&config.logging);
+    let addr = config.bind_addr();
+    let backup = config.backup.clone();
+    let tls_config = config.tls.clone();
 
-    let db_path = std::env::var("LW_DB_PATH").unwrap_or_else(|_| "lightweight.db".to_string());
-    let port = std::env::var("LW_PORT").unwrap_or_else(|_| "3000".to_string());
-    let host = std::env::var("LW_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let addr = format!("{}:{}", host, port);
+    // Load the certificate before touching the database so a bad path fails fast.
+    let resolver = match (&tls_config.cert_path, &tls_config.key_path) {
+        (Some(cert), Some(key)) => match tls::CertResolver::load(cert, key) {
+            Ok(resolver) => Some(Arc::new(resolver)),
+            Err(e) => {
+                eprintln!("invalid TLS certificate: {}", e);
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
 
-    let db = lightweight_core::db::init_db(&db_path).expect("Failed to initialize database");
-    let app = app::create_app(db.clone());
+    let db = lightweight_core::db::init_db(&config.database.path.to_string_lossy())
+        .expect("Failed to initialize database");
+    jobs::spawn(db.clone(), config.clone());
+    webhooks::spawn(db.clone(), &config);
+    let push = Arc::new(push::PushBus::default());
+    let app = app::create_app(db.clone(), config, push.clone());
 
     let listener = tokio::net::TcpListener::bind(&addr)
         .await
         .expect("Failed to bind");
 
-    tracing::info!("Lightweight server running on {}", addr);
+    // Every listener stops on the same signal.
+    let (stop_tx, stop_rx) = tokio::sync::watch::channel(());
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = stop_tx.send(());
+    });
+    let stopped = move || {
+        let mut rx = stop_rx.clone();
+        async move {
+            let _ = rx.changed().await;
+        }
+    };
+    // Live event streams never finish on their own; end them so graceful
+    // shutdown doesn't wait on them.
+    {
+        let stopped = stopped();
+        tokio::spawn(async move {
+            stopped.await;
+            push.close();
+        });
+    }
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
+    match resolver {
+        Some(resolver) => {
+            tls::spawn_reloader(
+                resolver.clone(),
+                Duration::from_secs(tls_config.reload_interval_secs),
+            );
+            if let Some(port) = tls_config.redirect_http_port {
+                let redirect_addr = SocketAddr::new(addr.ip(), port);
+                let redirect = tokio::net::TcpListener::bind(redirect_addr)
+                    .await
+                    .expect("Failed to bind HTTP redirect listener");
+                tracing::info!("Redirecting http://{} to HTTPS", redirect_addr);
+                let shutdown = stopped();
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(redirect, tls::redirect_app(addr.port()))
+                        .with_graceful_shutdown(shutdown)
+                        .await
+                    {
+                        tracing::error!("HTTP redirect listener failed: {}", e);
+                    }
+                });
+            }
+            tracing::info!("Lightweight server running on https://{}", addr);
+            tls::serve(listener, tls::acceptor(resolver), app, stopped()).await;
+        }
+        None => {
+            tracing::info!("Lightweight server running on {}", addr);
+            // Connect info gives the rate limiter the TCP peer address.
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(stopped())
+                .await
+                .unwrap();
+        }
+    }
 
     // Server has stopped accepting connections — checkpoint the WAL
     tracing::info!("Shutting down: checkpointing WAL...");
@@ -38,6 +124,14 @@ async fn main() {
             Err(e) => tracing::error!("WAL checkpoint failed: {}", e),
         }
     }
+    if backup.on_shutdown {
+        if let Some(dir) = &backup.dir {
+            match jobs::backup_database(&db, dir, backup.keep) {
+                Ok(path) => tracing::info!("Backup written to {}", path.display()),
+                Err(e) => tracing::error!("Backup failed: {}", e),
+            }
+        }
+    }
     tracing::info!("Shutdown complete");
 }
 
@@ -0,0 +1,337 @@
+//! In-process Prometheus metrics, rendered in the text exposition format at
+//! `GET /metrics`. Everything is kept in memory and resets on restart.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::app::AppState;
+use crate::error::{db_error, ApiError};
+
+/// Request latency buckets, seconds.
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+/// DB mutex wait buckets, seconds. Uncontended acquisition is well under 100µs.
+const LOCK_WAIT_BUCKETS: &[f64] = &[0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0];
+/// Sync/import batch size buckets, items per request.
+const BATCH_BUCKETS: &[f64] = &[1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0];
+
+struct Histogram {
+    bounds: &'static [f64],
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Histogram {
+            bounds,
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(self.counts.iter_mut()) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    /// Append `name_bucket`/`name_sum`/`name_count` samples. `labels` is either
+    /// empty or a rendered `key="value",...` list without braces.
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"{bound}\"}} {count}");
+        }
+        let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {}", self.count);
+        let braces = if labels.is_empty() { String::new() } else { format!("{{{labels}}}") };
+        let _ = writeln!(out, "{name}_sum{braces} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{braces} {}", self.count);
+    }
+}
+
+/// (method, route template) — the route is axum's matched path, never the raw
+/// URI, so ids don't explode label cardinality.
+type RouteKey = (String, String);
+
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    latency: Mutex<BTreeMap<RouteKey, Histogram>>,
+    db_lock_wait: Mutex<Histogram>,
+    sync_batches: Mutex<BTreeMap<&'static str, Histogram>>,
+    rate_limited: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics {
+            requests: Mutex::new(BTreeMap::new()),
+            latency: Mutex::new(BTreeMap::new()),
+            db_lock_wait: Mutex::new(Histogram::new(LOCK_WAIT_BUCKETS)),
+            sync_batches: Mutex::new(BTreeMap::new()),
+            rate_limited: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl Metrics {
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        *self
+            .requests
+            .lock()
+            .unwrap()
+            .entry((method.to_string(), route.to_string(), status))
+            .or_insert(0) += 1;
+        self.latency
+            .lock()
+            .unwrap()
+            .entry((method.to_string(), route.to_string()))
+            .or_insert_with(|| Histogram::new(LATENCY_BUCKETS))
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn observe_db_lock_wait(&self, waited: Duration) {
+        self.db_lock_wait.lock().unwrap().observe(waited.as_secs_f64());
+    }
+
+    /// `kind` is the sync endpoint: `sessions`, `templates` or `import`.
+    pub fn observe_sync_batch(&self, kind: &'static str, items: usize) {
+        self.sync_batches
+            .lock()
+            .unwrap()
+            .entry(kind)
+            .or_insert_with(|| Histogram::new(BATCH_BUCKETS))
+            .observe(items as f64);
+    }
+
+    /// `limiter` names the limit that rejected the request: `auth` or `export`.
+    pub fn inc_rate_limited(&self, limiter: &'static str) {
+        *self.rate_limited.lock().unwrap().entry(limiter).or_insert(0) += 1;
+    }
+
+    fn render(&self, gauges: &Gauges) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP lightweight_http_requests_total HTTP requests by route and status.\n");
+        out.push_str("# TYPE lightweight_http_requests_total counter\n");
+        for ((method, route, status), count) in self.requests.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "lightweight_http_requests_total{{method=\"{method}\",route=\"{route}\",status=\"{status}\"}} {count}"
+            );
+        }
+
+        out.push_str("# HELP lightweight_http_request_duration_seconds Request latency by route.\n");
+        out.push_str("# TYPE lightweight_http_request_duration_seconds histogram\n");
+        for ((method, route), hist) in self.latency.lock().unwrap().iter() {
+            hist.render(
+                &mut out,
+                "lightweight_http_request_duration_seconds",
+                &format!("method=\"{method}\",route=\"{route}\""),
+            );
+        }
+
+        out.push_str("# HELP lightweight_db_lock_wait_seconds Time spent waiting for the SQLite connection mutex.\n");
+        out.push_str("# TYPE lightweight_db_lock_wait_seconds histogram\n");
+        self.db_lock_wait
+            .lock()
+            .unwrap()
+            .render(&mut out, "lightweight_db_lock_wait_seconds", "");
+
+        out.push_str("# HELP lightweight_sync_batch_size Items per sync/import request.\n");
+        out.push_str("# TYPE lightweight_sync_batch_size histogram\n");
+        for (kind, hist) in self.sync_batches.lock().unwrap().iter() {
+            hist.render(&mut out, "lightweight_sync_batch_size", &format!("kind=\"{kind}\""));
+        }
+
+        out.push_str("# HELP lightweight_rate_limited_total Requests rejected by a rate limit.\n");
+        out.push_str("# TYPE lightweight_rate_limited_total counter\n");
+        for (limiter, count) in self.rate_limited.lock().unwrap().iter() {
+            let _ = writeln!(out, "lightweight_rate_limited_total{{limiter=\"{limiter}\"}} {count}");
+        }
+
+        out.push_str("# HELP lightweight_active_workout_sessions Workout sessions currently active or paused.\n");
+        out.push_str("# TYPE lightweight_active_workout_sessions gauge\n");
+        let _ = writeln!(out, "lightweight_active_workout_sessions {}", gauges.active_workouts);
+
+        out.push_str("# HELP lightweight_auth_sessions Unexpired login sessions.\n");
+        out.push_str("# TYPE lightweight_auth_sessions gauge\n");
+        let _ = writeln!(out, "lightweight_auth_sessions {}", gauges.auth_sessions);
+
+        out
+    }
+}
+
+/// Point-in-time values read from the database at scrape time.
+struct Gauges {
+    active_workouts: i64,
+    auth_sessions: i64,
+}
+
+/// Middleware recording count and latency for every request, labelled by the
+/// matched route (`fallback` for static assets and unknown paths).
+pub async fn track_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "fallback".to_string());
+    let method = request.method().to_string();
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    state
+        .metrics
+        .observe_request(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
+
+/// `GET /metrics`. Requires `Authorization: Bearer <metrics.token>`; disabled
+/// entirely when no token is configured.
+pub async fn metrics_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let expected = state
+        .config
+        .metrics
+        .token
+        .as_deref()
+        .ok_or_else(|| ApiError::feature_disabled("Metrics"))?;
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if !constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+        return Err(ApiError::unauthorized());
+    }
+
+    let gauges = {
+        let conn = state.db.lock().unwrap();
+        let active_workouts = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sessions WHERE status IN ('active', 'paused')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(db_error)?;
+        let auth_sessions = conn
+            .query_row(
+                "SELECT COUNT(*) FROM auth_sessions WHERE expires_at > datetime('now')",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(db_error)?;
+        Gauges { active_workouts, auth_sessions }
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(&gauges),
+    )
+        .into_response())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::{Request, StatusCode}};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn scrape(config: &str, token: Option<&str>) -> (StatusCode, String) {
+        let (app, _db) = crate::app::test_app(config);
+        let mut request = Request::get("/metrics");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(bytes.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn disabled_without_a_token() {
+        let (status, body) = scrape("", Some("anything")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body.contains("feature_disabled"));
+    }
+
+    #[tokio::test]
+    async fn wrong_token_is_unauthorized() {
+        let config = "[metrics]\ntoken = \"s3cret\"\n";
+        assert_eq!(scrape(config, Some("guess")).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(scrape(config, None).await.0, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn right_token_gets_the_exposition() {
+        let (status, body) = scrape("[metrics]\ntoken = \"s3cret\"\n", Some("s3cret")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("# TYPE lightweight_http_requests_total counter"));
+        assert!(body.contains("lightweight_active_workout_sessions 0"));
+    }
+
+    #[test]
+    fn request_counter_and_histogram_exposition() {
+        let metrics = Metrics::default();
+        metrics.observe_request("GET", "/api/v1/sessions/:id", 200, Duration::from_millis(30));
+        metrics.observe_request("GET", "/api/v1/sessions/:id", 200, Duration::from_millis(3));
+        metrics.observe_db_lock_wait(Duration::from_micros(50));
+        let text = metrics.render(&Gauges { active_workouts: 2, auth_sessions: 5 });
+
+        let labels = "method=\"GET\",route=\"/api/v1/sessions/:id\"";
+        assert!(text.contains(&format!("lightweight_http_requests_total{{{labels},status=\"200\"}} 2\n")));
+        let duration = "lightweight_http_request_duration_seconds";
+        // 3 ms lands in every bucket from 5 ms up, 30 ms from 50 ms up.
+        assert!(text.contains(&format!("{duration}_bucket{{{labels},le=\"0.005\"}} 1\n")));
+        assert!(text.contains(&format!("{duration}_bucket{{{labels},le=\"0.025\"}} 1\n")));
+        assert!(text.contains(&format!("{duration}_bucket{{{labels},le=\"0.05\"}} 2\n")));
+        assert!(text.contains(&format!("{duration}_bucket{{{labels},le=\"+Inf\"}} 2\n")));
+        assert!(text.contains(&format!("{duration}_sum{{{labels}}} 0.033\n")));
+        assert!(text.contains(&format!("{duration}_count{{{labels}}} 2\n")));
+
+        assert!(text.contains("lightweight_db_lock_wait_seconds_bucket{le=\"0.0001\"} 1\n"));
+        assert!(text.contains("lightweight_db_lock_wait_seconds_count 1\n"));
+        assert!(text.contains("lightweight_active_workout_sessions 2\n"));
+        assert!(text.contains("lightweight_auth_sessions 5\n"));
+    }
+
+    #[test]
+    fn db_lock_wait_is_observed_through_the_pool() {
+        let metrics = Arc::new(Metrics::default());
+        let db = lightweight_core::db::init_memory_db().unwrap();
+        {
+            let metrics = metrics.clone();
+            db.observe_lock_wait(move |waited| metrics.observe_db_lock_wait(waited));
+        }
+        drop(db.lock().unwrap());
+        drop(db.lock().unwrap());
+        let text = metrics.render(&Gauges { active_workouts: 0, auth_sessions: 0 });
+        assert!(text.contains("lightweight_db_lock_wait_seconds_count 2\n"), "{}", text);
+    }
+}
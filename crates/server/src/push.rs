@@ -0,0 +1,242 @@
+//! Push channel: fans core [`Change`]s out to connected devices as
+//! Server-Sent Events.
+//!
+//! Core mutations report changes through `Db::observe_changes`; they land on
+//! one broadcast channel and every stream keeps only what its [`Scope`]
+//! covers. Each change gets an increasing id, and the most recent ones are
+//! kept so a client reconnecting with `Last-Event-ID` gets what it missed.
+//! When that isn't possible (too far behind, or the id is from before a
+//! restart) the stream sends `resync` and the client should refetch. Streams
+//! end when the server shuts down so graceful shutdown isn't held open by
+//! idle connections.
+
+use axum::response::sse::Event;
+use futures_util::stream::{self, Stream, StreamExt};
+use lightweight_core::changes::Change;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{broadcast, watch};
+
+/// Changes buffered per subscriber before it is considered lagging.
+const CAPACITY: usize = 256;
+/// Recent changes kept for `Last-Event-ID` replay.
+const REPLAY: usize = 1024;
+
+struct Published {
+    id: u64,
+    change: Change,
+}
+
+struct Recent {
+    next_id: u64,
+    changes: VecDeque<Arc<Published>>,
+}
+
+/// Which changes a stream carries.
+#[derive(Debug, Clone, Copy)]
+pub enum Scope {
+    /// Everything for the user.
+    Account(i64),
+    /// One session of the user.
+    Session(i64, i64),
+}
+
+impl Scope {
+    fn covers(self, change: &Change) -> bool {
+        match self {
+            Scope::Account(user_id) => change.user_id == user_id,
+            Scope::Session(user_id, session_id) => {
+                change.user_id == user_id && change.session_id == session_id
+            }
+        }
+    }
+}
+
+pub struct PushBus {
+    changes: broadcast::Sender<Arc<Published>>,
+    recent: Mutex<Recent>,
+    closed: watch::Sender<bool>,
+}
+
+impl Default for PushBus {
+    fn default() -> Self {
+        // Ids start at the boot time in milliseconds, so ids handed out
+        // before a restart are always older than anything replayable.
+        let boot = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        PushBus {
+            changes: broadcast::channel(CAPACITY).0,
+            recent: Mutex::new(Recent { next_id: boot, changes: VecDeque::new() }),
+            closed: watch::channel(false).0,
+        }
+    }
+}
+
+impl PushBus {
+    pub fn publish(&self, change: &Change) {
+        let mut recent = self.recent.lock().unwrap();
+        let published = Arc::new(Published { id: recent.next_id, change: change.clone() });
+        recent.next_id += 1;
+        if recent.changes.len() == REPLAY {
+            recent.changes.pop_front();
+        }
+        recent.changes.push_back(published.clone());
+        // Sent under the lock so a new subscriber sees each change exactly
+        // once: either in its replay or live. No subscribers is not an error.
+        let _ = self.changes.send(published);
+    }
+
+    /// End every open stream.
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// Events in `scope`. A fresh connection (or one that can't be resumed)
+    /// starts with `initial`; a resumed one with the changes after
+    /// `last_event_id`. Then live changes until the bus closes.
+    pub fn stream(
+        &self,
+        scope: Scope,
+        last_event_id: Option<u64>,
+        initial: Vec<Change>,
+    ) -> impl Stream<Item = Result<Event, Infallible>> {
+        let (receiver, opening) = {
+            let recent = self.recent.lock().unwrap();
+            let receiver = self.changes.subscribe();
+            let oldest = recent.changes.front().map_or(recent.next_id, |p| p.id);
+            let opening: Vec<Event> = match last_event_id {
+                // The id comes from the client, so no arithmetic on it.
+                Some(last) if last >= oldest.saturating_sub(1) && last < recent.next_id => recent
+                    .changes
+                    .iter()
+                    .filter(|p| p.id > last && scope.covers(&p.change))
+                    .map(|p| event(p))
+                    .collect(),
+                resumed => resumed
+                    .map(|_| resync())
+                    .into_iter()
+                    .chain(initial.iter().map(snapshot))
+                    .collect(),
+            };
+            (receiver, opening)
+        };
+
+        let live = stream::unfold(
+            (receiver, self.closed.subscribe()),
+            move |(mut changes, mut closed)| async move {
+                loop {
+                    // Biased so changes already queued are delivered before closing.
+                    let received = tokio::select! {
+                        biased;
+                        received = changes.recv() => received,
+                        _ = closed.wait_for(|c| *c) => return None,
+                    };
+                    let event = match received {
+                        Ok(published) if scope.covers(&published.change) => event(&published),
+                        Ok(_) => continue,
+                        Err(broadcast::error::RecvError::Lagged(missed)) => {
+                            tracing::warn!(?scope, missed, "Push subscriber lagged");
+                            resync()
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    };
+                    return Some((event, (changes, closed)));
+                }
+            },
+        );
+        stream::iter(opening).chain(live).map(Ok)
+    }
+}
+
+fn event(published: &Published) -> Event {
+    snapshot(&published.change).id(published.id.to_string())
+}
+
+/// A change without an id, for state sent on connect.
+fn snapshot(change: &Change) -> Event {
+    Event::default()
+        .event(change.kind())
+        .json_data(change)
+        .unwrap_or_else(|_| resync())
+}
+
+fn resync() -> Event {
+    Event::default().event("resync").data("{}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lightweight_core::changes::ChangeEvent;
+
+    fn set_deleted(user_id: i64, session_id: i64, set_id: i64) -> Change {
+        Change::new(user_id, session_id, ChangeEvent::SetDeleted { session_exercise_id: 1, set_id })
+    }
+
+    async fn count(events: impl Stream<Item = Result<Event, Infallible>>) -> usize {
+        events.collect::<Vec<_>>().await.len()
+    }
+
+    #[tokio::test]
+    async fn delivers_only_scoped_changes_and_ends_on_close() {
+        let bus = PushBus::default();
+        let account = bus.stream(Scope::Account(1), None, vec![set_deleted(1, 10, 0)]);
+        let session = bus.stream(Scope::Session(1, 11), None, vec![]);
+        bus.publish(&set_deleted(2, 20, 1));
+        bus.publish(&set_deleted(1, 10, 2));
+        bus.publish(&set_deleted(1, 11, 3));
+        bus.close();
+
+        // Snapshot plus both of user 1's changes; user 2's is filtered.
+        assert_eq!(count(account).await, 3);
+        assert_eq!(count(session).await, 1);
+    }
+
+    #[tokio::test]
+    async fn resumes_from_last_event_id() {
+        let bus = PushBus::default();
+        let first = bus.recent.lock().unwrap().next_id;
+        for set_id in 0..3 {
+            bus.publish(&set_deleted(1, 10, set_id));
+        }
+
+        // Saw the first change, missed the other two.
+        let resumed = bus.stream(Scope::Session(1, 10), Some(first), vec![set_deleted(1, 10, 99)]);
+        // An id from before a restart can't be replayed.
+        let stale = bus.stream(Scope::Session(1, 10), Some(1), vec![set_deleted(1, 10, 99)]);
+        bus.close();
+
+        assert_eq!(count(resumed).await, 2);
+        // `resync` followed by the snapshot.
+        assert_eq!(count(stale).await, 2);
+    }
+
+    #[tokio::test]
+    async fn replays_only_within_the_buffer() {
+        let bus = PushBus::default();
+        let first = bus.recent.lock().unwrap().next_id;
+        for set_id in 0..REPLAY as i64 + 2 {
+            bus.publish(&set_deleted(1, 10, set_id));
+        }
+        let oldest = first + 2;
+        let stream = |last| bus.stream(Scope::Session(1, 10), Some(last), vec![set_deleted(1, 10, 99)]);
+
+        // Just before the oldest kept change: everything kept is replayed.
+        let whole = stream(oldest - 1);
+        // Evicted, so there is a gap: `resync` and the snapshot.
+        let evicted = stream(first);
+        // Ids past anything handed out, including one that can't be incremented.
+        let future = stream(oldest + REPLAY as u64);
+        let max = stream(u64::MAX);
+        bus.close();
+
+        assert_eq!(count(whole).await, REPLAY);
+        assert_eq!(count(evicted).await, 2);
+        assert_eq!(count(future).await, 2);
+        assert_eq!(count(max).await, 2);
+    }
+}
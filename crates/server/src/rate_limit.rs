@@ -0,0 +1,319 @@
+//! Token-bucket rate limiting.
+//!
+//! Public routes are limited per client IP, authenticated writes and heavy
+//! analytics per user. Each route class has its own bucket size and refill
+//! rate (`[rate_limit.*]` in the server config). The client IP is the TCP peer
+//! unless that peer is a configured trusted proxy, in which case
+//! `X-Forwarded-For` is walked right-to-left past trusted hops — so a client
+//! can't pick its own bucket by forging the header.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::app::AppState;
+use crate::auth::UserId;
+use crate::config::{BucketConfig, RateLimitConfig};
+use crate::error::ApiError;
+
+/// Buckets are swept once the map grows past this many keys.
+const SWEEP_THRESHOLD: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouteClass {
+    /// Public POSTs: login, register, invite join, beta signup. Per IP.
+    Auth,
+    /// Public GETs: config, invite validation. Per IP.
+    Public,
+    /// Authenticated POST/PUT/PATCH/DELETE. Per user.
+    Write,
+    /// Authenticated analytics and export reads. Per user.
+    Analytics,
+}
+
+impl RouteClass {
+    pub fn name(self) -> &'static str {
+        match self {
+            RouteClass::Auth => "auth",
+            RouteClass::Public => "public",
+            RouteClass::Write => "write",
+            RouteClass::Analytics => "analytics",
+        }
+    }
+
+    fn limit(self, config: &RateLimitConfig) -> &BucketConfig {
+        match self {
+            RouteClass::Auth => &config.auth,
+            RouteClass::Public => &config.public,
+            RouteClass::Write => &config.write,
+            RouteClass::Analytics => &config.analytics,
+        }
+    }
+
+    fn for_public(method: &Method) -> RouteClass {
+        if method == Method::GET {
+            RouteClass::Public
+        } else {
+            RouteClass::Auth
+        }
+    }
+
+    /// `None` for ordinary authenticated reads, which are not limited.
+    fn for_protected(method: &Method, path: &str) -> Option<RouteClass> {
+        let path = path.strip_prefix("/api/v1").unwrap_or(path);
+        if !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+            Some(RouteClass::Write)
+        } else if path.starts_with("/analytics/") || path.starts_with("/export/") {
+            Some(RouteClass::Analytics)
+        } else {
+            None
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+    /// When the bucket will be full again; a full bucket can be forgotten.
+    full_at: Instant,
+}
+
+/// An address or CIDR block (`10.0.0.0/8`, `::1`).
+#[derive(Debug, Clone, Copy)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn parse(s: &str) -> Option<Cidr> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u8>().ok()?)),
+            None => (s.parse::<IpAddr>().ok()?, None),
+        };
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Cidr { addr: addr.to_canonical(), prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<(RouteClass, String), Bucket>>,
+    trusted_proxies: Vec<Cidr>,
+}
+
+impl RateLimiter {
+    /// `trusted_proxies` has already been validated by `Config::validate`.
+    pub fn new(trusted_proxies: &[String]) -> Self {
+        RateLimiter {
+            buckets: Mutex::new(HashMap::new()),
+            trusted_proxies: trusted_proxies.iter().filter_map(|s| Cidr::parse(s)).collect(),
+        }
+    }
+
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|net| net.contains(ip))
+    }
+
+    /// The address to rate-limit on. `X-Forwarded-For` only counts when the
+    /// TCP peer is a trusted proxy; the first untrusted hop from the right is
+    /// the client.
+    pub fn client_ip(&self, peer: Option<IpAddr>, headers: &HeaderMap) -> String {
+        let Some(peer) = peer else {
+            return "unknown".to_string();
+        };
+        if !self.is_trusted(peer) {
+            return peer.to_canonical().to_string();
+        }
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>();
+
+        let mut client = peer;
+        for hop in forwarded.iter().rev() {
+            match hop.parse::<IpAddr>() {
+                Ok(ip) if self.is_trusted(ip) => client = ip,
+                Ok(ip) => return ip.to_canonical().to_string(),
+                // Garbage in the chain: stop at the last hop we could vouch for.
+                Err(_) => break,
+            }
+        }
+        client.to_canonical().to_string()
+    }
+
+    /// Take one token. `Err` carries the seconds until one is available.
+    pub fn check(
+        &self,
+        class: RouteClass,
+        key: &str,
+        limit: &BucketConfig,
+        now: Instant,
+    ) -> Result<(), u64> {
+        let capacity = limit.burst as f64;
+        let rate = limit.per_minute as f64 / 60.0;
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > SWEEP_THRESHOLD {
+            buckets.retain(|_, b| b.full_at > now);
+        }
+
+        let bucket = buckets.entry((class, key.to_string())).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+            full_at: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.updated_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated_at = now;
+
+        let result = if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64)
+        };
+        bucket.full_at = now + std::time::Duration::from_secs_f64((capacity - bucket.tokens) / rate);
+        result
+    }
+}
+
+fn enforce(state: &AppState, class: RouteClass, key: &str) -> Result<(), ApiError> {
+    let limit = class.limit(&state.config.rate_limit);
+    state
+        .rate_limiter
+        .check(class, key, limit, Instant::now())
+        .map_err(|retry_after| {
+            state.metrics.inc_rate_limited(class.name());
+            tracing::warn!(class = class.name(), key, retry_after, "rate limited");
+            ApiError::rate_limited(retry_after)
+        })
+}
+
+/// Per-IP limits for the public (unauthenticated) routes.
+pub async fn limit_public(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let ip = state.rate_limiter.client_ip(peer, request.headers());
+    enforce(&state, RouteClass::for_public(request.method()), &ip)?;
+    Ok(next.run(request).await)
+}
+
+/// Per-user limits for authenticated writes and analytics. Runs after
+/// `auth_middleware`, which supplies the `UserId`.
+pub async fn limit_user(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let class = RouteClass::for_protected(request.method(), request.uri().path());
+    if let (Some(class), Some(UserId(user_id))) = (class, request.extensions().get::<UserId>()) {
+        enforce(&state, class, &format!("user:{}", user_id))?;
+    }
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use std::time::Duration;
+
+    fn xff(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn forwarded_for_ignored_from_untrusted_peer() {
+        let limiter = RateLimiter::new(&["10.0.0.0/8".to_string()]);
+        let peer = "203.0.113.9".parse().ok();
+        assert_eq!(limiter.client_ip(peer, &xff("1.2.3.4")), "203.0.113.9");
+    }
+
+    #[test]
+    fn forwarded_for_walks_past_trusted_hops() {
+        let limiter = RateLimiter::new(&["10.0.0.0/8".to_string(), "127.0.0.1".to_string()]);
+        let peer = "127.0.0.1".parse().ok();
+        // Client forged the leftmost entry; the proxy appended the real address.
+        let headers = xff("6.6.6.6, 198.51.100.7, 10.1.2.3");
+        assert_eq!(limiter.client_ip(peer, &headers), "198.51.100.7");
+    }
+
+    #[test]
+    fn missing_peer_is_unknown() {
+        let limiter = RateLimiter::new(&[]);
+        assert_eq!(limiter.client_ip(None, &xff("1.2.3.4")), "unknown");
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let limiter = RateLimiter::new(&[]);
+        let limit = BucketConfig { per_minute: 60, burst: 2 };
+        let t0 = Instant::now();
+        assert!(limiter.check(RouteClass::Auth, "k", &limit, t0).is_ok());
+        assert!(limiter.check(RouteClass::Auth, "k", &limit, t0).is_ok());
+        assert_eq!(limiter.check(RouteClass::Auth, "k", &limit, t0), Err(1));
+        // Other keys and classes have their own buckets.
+        assert!(limiter.check(RouteClass::Write, "k", &limit, t0).is_ok());
+        assert!(limiter.check(RouteClass::Auth, "other", &limit, t0).is_ok());
+        assert!(limiter
+            .check(RouteClass::Auth, "k", &limit, t0 + Duration::from_secs(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn classifies_protected_routes() {
+        assert_eq!(
+            RouteClass::for_protected(&Method::POST, "/sessions/sync"),
+            Some(RouteClass::Write)
+        );
+        assert_eq!(
+            RouteClass::for_protected(&Method::GET, "/api/v1/analytics/summary"),
+            Some(RouteClass::Analytics)
+        );
+        assert_eq!(RouteClass::for_protected(&Method::GET, "/sessions"), None);
+    }
+
+    #[test]
+    fn cidr_matching() {
+        let net = Cidr::parse("192.168.0.0/16").unwrap();
+        assert!(net.contains("192.168.4.5".parse().unwrap()));
+        assert!(net.contains("::ffff:192.168.4.5".parse().unwrap()));
+        assert!(!net.contains("192.169.0.1".parse().unwrap()));
+        assert!(Cidr::parse("10.0.0.0/33").is_none());
+        assert!(Cidr::parse("nonsense").is_none());
+    }
+}
@@ -1,17 +1,20 @@
-use axum::{extract::{Path, State}, http::StatusCode, Extension, Json, Router, routing::get};
+use axum::{extract::State, http::StatusCode, Extension, Router, routing::get};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::{db_error, ApiError};
+use crate::extract::{Json, Path, Query};
 
 const ADMIN_USER_ID: i64 = 1;
 
-fn require_admin(user_id: i64) -> Result<(), StatusCode> {
+fn require_admin(user_id: i64) -> Result<(), ApiError> {
     if user_id == ADMIN_USER_ID {
         Ok(())
     } else {
-        Err(StatusCode::FORBIDDEN)
+        Err(ApiError::forbidden("Admin access required"))
     }
 }
 
@@ -25,8 +28,8 @@ pub fn routes() -> Router<Arc<AppState>> {
 
 // ── Response types ──
 
-#[derive(Serialize)]
-struct AdminBetaSignup {
+#[derive(Serialize, ToSchema)]
+pub(super) struct AdminBetaSignup {
     id: i64,
     email: String,
     username: Option<String>,
@@ -36,8 +39,8 @@ struct AdminBetaSignup {
     created_at: String,
 }
 
-#[derive(Serialize)]
-struct AdminUser {
+#[derive(Serialize, ToSchema)]
+pub(super) struct AdminUser {
     id: i64,
     username: Option<String>,
     email: Option<String>,
@@ -47,8 +50,8 @@ struct AdminUser {
     last_workout: Option<String>,
 }
 
-#[derive(Serialize)]
-struct AdminActivity {
+#[derive(Serialize, ToSchema)]
+pub(super) struct AdminActivity {
     date: String,
     username: String,
     workout_name: String,
@@ -58,29 +61,36 @@ struct AdminActivity {
 
 // ── Request types ──
 
-#[derive(Deserialize)]
-struct AddBetaRequest {
+#[derive(Deserialize, ToSchema)]
+pub(super) struct AddBetaRequest {
     email: String,
     platform: String,
     referrer: Option<String>,
 }
 
-#[derive(Deserialize)]
-struct UpdateBetaStatusRequest {
+#[derive(Deserialize, ToSchema)]
+pub(super) struct UpdateBetaStatusRequest {
     status: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ActivityParams {
     days: Option<u32>,
 }
 
 // ── Handlers ──
 
+#[utoipa::path(
+    get,
+    path = "/admin/beta",
+    tag = "admin",
+    responses((status = 200, body = Vec<AdminBetaSignup>))
+)]
 async fn beta(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<AdminBetaSignup>>, StatusCode> {
+) -> Result<Json<Vec<AdminBetaSignup>>, ApiError> {
     require_admin(user_id)?;
     let conn = state.db.lock().unwrap();
 
@@ -91,7 +101,7 @@ async fn beta(
              LEFT JOIN users u ON u.id = b.user_id
              ORDER BY b.created_at DESC",
         )
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     let rows = stmt
         .query_map([], |row| {
@@ -105,18 +115,25 @@ async fn beta(
                 created_at: row.get(6)?,
             })
         })
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(db_error)?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     Ok(Json(rows))
 }
 
+#[utoipa::path(
+    post,
+    path = "/admin/beta",
+    tag = "admin",
+    request_body = AddBetaRequest,
+    responses((status = 201, body = AdminBetaSignup))
+)]
 async fn add_beta(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<AddBetaRequest>,
-) -> Result<(StatusCode, Json<AdminBetaSignup>), StatusCode> {
+) -> Result<(StatusCode, Json<AdminBetaSignup>), ApiError> {
     require_admin(user_id)?;
 
     let id = lightweight_core::beta::admin_add_signup(
@@ -125,10 +142,7 @@ async fn add_beta(
         &body.platform,
         body.referrer.as_deref(),
     )
-    .map_err(|e| match e {
-        lightweight_core::error::AppError::AlreadyExists => StatusCode::CONFLICT,
-        _ => StatusCode::INTERNAL_SERVER_ERROR,
-    })?;
+    .map_err(ApiError::from)?;
 
     let conn = state.db.lock().unwrap();
     let signup = conn
@@ -150,32 +164,42 @@ async fn add_beta(
                 })
             },
         )
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     Ok((StatusCode::CREATED, Json(signup)))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/admin/beta/{id}",
+    tag = "admin",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = UpdateBetaStatusRequest,
+    responses((status = 204, description = "No content"))
+)]
 async fn update_beta_status(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
     Json(body): Json<UpdateBetaStatusRequest>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, ApiError> {
     require_admin(user_id)?;
 
-    lightweight_core::beta::admin_update_status(&state.db, id, &body.status).map_err(|e| match e {
-        lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-        lightweight_core::error::AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
-        _ => StatusCode::INTERNAL_SERVER_ERROR,
-    })?;
+    lightweight_core::beta::admin_update_status(&state.db, id, &body.status).map_err(ApiError::from)?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/users",
+    tag = "admin",
+    responses((status = 200, body = Vec<AdminUser>))
+)]
 async fn users(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<AdminUser>>, StatusCode> {
+) -> Result<Json<Vec<AdminUser>>, ApiError> {
     require_admin(user_id)?;
     let conn = state.db.lock().unwrap();
 
@@ -196,7 +220,7 @@ async fn users(
              FROM users u
              ORDER BY u.id",
         )
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     let rows = stmt
         .query_map([], |row| {
@@ -210,18 +234,25 @@ async fn users(
                 last_workout: row.get(6)?,
             })
         })
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(db_error)?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     Ok(Json(rows))
 }
 
+#[utoipa::path(
+    get,
+    path = "/admin/activity",
+    tag = "admin",
+    params(ActivityParams),
+    responses((status = 200, body = Vec<AdminActivity>))
+)]
 async fn activity(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-    axum::extract::Query(params): axum::extract::Query<ActivityParams>,
-) -> Result<Json<Vec<AdminActivity>>, StatusCode> {
+    Query(params): Query<ActivityParams>,
+) -> Result<Json<Vec<AdminActivity>>, ApiError> {
     require_admin(user_id)?;
     let days = params.days.unwrap_or(7);
     let conn = state.db.lock().unwrap();
@@ -246,7 +277,7 @@ async fn activity(
                AND s.status = 'completed'
              ORDER BY s.started_at DESC",
         )
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     let rows = stmt
         .query_map([&modifier], |row| {
@@ -258,9 +289,9 @@ async fn activity(
                 set_count: row.get(4)?,
             })
         })
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .map_err(db_error)?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(db_error)?;
 
     Ok(Json(rows))
 }
@@ -1,14 +1,25 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::State,
     http::StatusCode,
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
 use serde::Deserialize;
+use utoipa::IntoParams;
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use lightweight_core::analytics::{
+    AnalyticsSummary, DayActivity, DayPR, DayTemplateActivity, E1rmMover, E1rmSpiderPoint,
+    ExerciseE1rm, ExercisePRData, ExerciseSummary, ExerciseWeeklyVolume, RepMaxes, Report, RestAnalytics, RestPerformanceBucket,
+    StaleExercise, StalledExercise, StrengthScores,
+    WeeklyFrequency, WeeklyVolume,
+};
+use lightweight_core::error::AppError;
+use lightweight_core::preferences::E1rmSpiderPrefs;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
@@ -17,142 +28,281 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/analytics/heatmap-prs", get(heatmap_prs))
         .route("/analytics/exercises", get(exercises_with_data))
         .route("/analytics/e1rm/:exercise_id", get(e1rm_progression))
+        .route("/analytics/rep-maxes/:exercise_id", get(rep_maxes))
         .route("/analytics/e1rm-spider", get(e1rm_spider))
         .route("/analytics/volume", get(weekly_volume))
         .route("/analytics/frequency", get(session_frequency))
         .route("/analytics/e1rm-movers", get(e1rm_movers))
         .route("/analytics/stale-exercises", get(stale_exercises))
+        .route("/analytics/plateaus", get(plateaus))
+        .route("/analytics/strength-scores", get(strength_scores))
         .route("/analytics/session-prs/:session_id", get(session_prs))
         .route("/analytics/exercise-volume", get(exercise_volume))
+        .route("/analytics/rest", get(rest_intervals))
+        .route("/analytics/rest-performance", get(rest_performance))
         .route("/analytics/summary", get(analytics_summary))
         .route("/analytics/report", get(analytics_report))
         .route("/preferences/e1rm-spider", get(get_e1rm_spider_prefs).put(set_e1rm_spider_prefs))
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/heatmap",
+    tag = "analytics",
+    responses((status = 200, body = Vec<DayActivity>))
+)]
 async fn heatmap(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<lightweight_core::analytics::DayActivity>>, StatusCode> {
+) -> Result<Json<Vec<DayActivity>>, ApiError> {
     lightweight_core::analytics::activity_heatmap(&state.db, user_id, 365)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/heatmap-templates",
+    tag = "analytics",
+    responses((status = 200, body = Vec<DayTemplateActivity>))
+)]
 async fn heatmap_templates(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<lightweight_core::analytics::DayTemplateActivity>>, StatusCode> {
+) -> Result<Json<Vec<DayTemplateActivity>>, ApiError> {
     lightweight_core::analytics::activity_heatmap_by_template(&state.db, user_id, 365)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/heatmap-prs",
+    tag = "analytics",
+    responses((status = 200, body = Vec<DayPR>))
+)]
 async fn heatmap_prs(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<lightweight_core::analytics::DayPR>>, StatusCode> {
+) -> Result<Json<Vec<DayPR>>, ApiError> {
     lightweight_core::analytics::heatmap_prs(&state.db, user_id, 365)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/exercises",
+    tag = "analytics",
+    responses((status = 200, body = Vec<ExerciseSummary>))
+)]
 async fn exercises_with_data(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<lightweight_core::analytics::ExerciseSummary>>, StatusCode> {
+) -> Result<Json<Vec<ExerciseSummary>>, ApiError> {
     lightweight_core::analytics::exercises_with_data(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct DateRangeQuery {
     since: Option<String>,
     until: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/e1rm/{exercise_id}",
+    tag = "analytics",
+    params(("exercise_id" = i64, Path, description = "Exercise id"), DateRangeQuery),
+    responses((status = 200, body = ExerciseE1rm))
+)]
 async fn e1rm_progression(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(exercise_id): Path<i64>,
     Query(query): Query<DateRangeQuery>,
-) -> Result<Json<lightweight_core::analytics::ExerciseE1rm>, StatusCode> {
+) -> Result<Json<ExerciseE1rm>, ApiError> {
     lightweight_core::analytics::e1rm_progression(
         &state.db, user_id, exercise_id,
         query.since.as_deref(), query.until.as_deref(),
     )
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct RepMaxQuery {
+    rir: Option<i64>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/rep-maxes/{exercise_id}",
+    tag = "analytics",
+    params(("exercise_id" = i64, Path, description = "Exercise id"), RepMaxQuery),
+    responses((status = 200, body = RepMaxes))
+)]
+async fn rep_maxes(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(exercise_id): Path<i64>,
+    Query(query): Query<RepMaxQuery>,
+) -> Result<Json<RepMaxes>, ApiError> {
+    lightweight_core::analytics::rep_maxes(&state.db, user_id, exercise_id, query.rir.unwrap_or(0))
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/analytics/volume",
+    tag = "analytics",
+    params(DateRangeQuery),
+    responses((status = 200, body = Vec<WeeklyVolume>))
+)]
 async fn weekly_volume(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Query(query): Query<DateRangeQuery>,
-) -> Result<Json<Vec<lightweight_core::analytics::WeeklyVolume>>, StatusCode> {
+) -> Result<Json<Vec<WeeklyVolume>>, ApiError> {
     lightweight_core::analytics::weekly_volume(
         &state.db, user_id,
         query.since.as_deref(), query.until.as_deref(),
     )
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/analytics/strength-scores",
+    tag = "analytics",
+    params(DateRangeQuery),
+    responses((status = 200, body = StrengthScores))
+)]
+async fn strength_scores(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Query(query): Query<DateRangeQuery>,
+) -> Result<Json<StrengthScores>, ApiError> {
+    lightweight_core::analytics::strength_scores(
+        &state.db, user_id,
+        query.since.as_deref(), query.until.as_deref(),
+    )
+        .map(Json)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/frequency",
+    tag = "analytics",
+    responses((status = 200, body = Vec<WeeklyFrequency>))
+)]
 async fn session_frequency(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<lightweight_core::analytics::WeeklyFrequency>>, StatusCode> {
+) -> Result<Json<Vec<WeeklyFrequency>>, ApiError> {
     lightweight_core::analytics::session_frequency(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct MoversQuery {
     days: Option<i64>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/e1rm-movers",
+    tag = "analytics",
+    params(MoversQuery),
+    responses((status = 200, body = Vec<E1rmMover>))
+)]
 async fn e1rm_movers(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Query(query): Query<MoversQuery>,
-) -> Result<Json<Vec<lightweight_core::analytics::E1rmMover>>, StatusCode> {
+) -> Result<Json<Vec<E1rmMover>>, ApiError> {
     let days = query.days.unwrap_or(30);
     lightweight_core::analytics::e1rm_movers(&state.db, user_id, days)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct StaleQuery {
     days: Option<i64>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/stale-exercises",
+    tag = "analytics",
+    params(StaleQuery),
+    responses((status = 200, body = Vec<StaleExercise>))
+)]
 async fn stale_exercises(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Query(query): Query<StaleQuery>,
-) -> Result<Json<Vec<lightweight_core::analytics::StaleExercise>>, StatusCode> {
+) -> Result<Json<Vec<StaleExercise>>, ApiError> {
     let days = query.days.unwrap_or(30);
     lightweight_core::analytics::stale_exercises(&state.db, user_id, days)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PlateauQuery {
+    exposures: Option<i64>,
 }
 
-#[derive(Deserialize)]
+#[utoipa::path(
+    get,
+    path = "/analytics/plateaus",
+    tag = "analytics",
+    params(PlateauQuery),
+    responses((status = 200, body = Vec<StalledExercise>))
+)]
+async fn plateaus(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Query(query): Query<PlateauQuery>,
+) -> Result<Json<Vec<StalledExercise>>, ApiError> {
+    let exposures = query.exposures.unwrap_or(lightweight_core::analytics::DEFAULT_STALL_EXPOSURES);
+    lightweight_core::analytics::plateaus(&state.db, user_id, exposures)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct E1rmSpiderQuery {
     exercise_ids: String,
     weeks: Option<i64>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/e1rm-spider",
+    tag = "analytics",
+    params(E1rmSpiderQuery),
+    responses((status = 200, body = Vec<E1rmSpiderPoint>))
+)]
 async fn e1rm_spider(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Query(query): Query<E1rmSpiderQuery>,
-) -> Result<Json<Vec<lightweight_core::analytics::E1rmSpiderPoint>>, StatusCode> {
+) -> Result<Json<Vec<E1rmSpiderPoint>>, ApiError> {
     let exercise_ids: Vec<i64> = query.exercise_ids
         .split(',')
         .filter_map(|s| s.trim().parse().ok())
@@ -160,54 +310,121 @@ async fn e1rm_spider(
     let weeks = query.weeks.unwrap_or(4);
     lightweight_core::analytics::e1rm_spider(&state.db, user_id, &exercise_ids, weeks)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/session-prs/{session_id}",
+    tag = "analytics",
+    params(("session_id" = i64, Path, description = "Session id")),
+    responses((status = 200, body = Vec<ExercisePRData>))
+)]
 async fn session_prs(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(session_id): Path<i64>,
-) -> Result<Json<Vec<lightweight_core::analytics::ExercisePRData>>, StatusCode> {
+) -> Result<Json<Vec<ExercisePRData>>, ApiError> {
     lightweight_core::analytics::session_prs(&state.db, user_id, session_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ExerciseVolumeQuery {
     exercise_id: Option<i64>,
     since: Option<String>,
     until: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/exercise-volume",
+    tag = "analytics",
+    params(ExerciseVolumeQuery),
+    responses((status = 200, body = Vec<ExerciseWeeklyVolume>))
+)]
 async fn exercise_volume(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Query(query): Query<ExerciseVolumeQuery>,
-) -> Result<Json<Vec<lightweight_core::analytics::ExerciseWeeklyVolume>>, StatusCode> {
+) -> Result<Json<Vec<ExerciseWeeklyVolume>>, ApiError> {
     lightweight_core::analytics::exercise_volume(
         &state.db, user_id, query.exercise_id,
         query.since.as_deref(), query.until.as_deref(),
     )
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/analytics/rest",
+    tag = "analytics",
+    params(ExerciseVolumeQuery),
+    responses((status = 200, body = RestAnalytics))
+)]
+async fn rest_intervals(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Query(query): Query<ExerciseVolumeQuery>,
+) -> Result<Json<RestAnalytics>, ApiError> {
+    lightweight_core::analytics::rest_intervals(
+        &state.db, user_id, query.exercise_id,
+        query.since.as_deref(), query.until.as_deref(),
+    )
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/analytics/rest-performance",
+    tag = "analytics",
+    params(ExerciseVolumeQuery),
+    responses((status = 200, body = Vec<RestPerformanceBucket>))
+)]
+async fn rest_performance(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Query(query): Query<ExerciseVolumeQuery>,
+) -> Result<Json<Vec<RestPerformanceBucket>>, ApiError> {
+    lightweight_core::analytics::rest_performance(
+        &state.db, user_id, query.exercise_id,
+        query.since.as_deref(), query.until.as_deref(),
+    )
+        .map(Json)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/analytics/summary",
+    tag = "analytics",
+    responses((status = 200, body = Vec<AnalyticsSummary>))
+)]
 async fn analytics_summary(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<lightweight_core::analytics::AnalyticsSummary>>, StatusCode> {
+) -> Result<Json<Vec<AnalyticsSummary>>, ApiError> {
     lightweight_core::analytics::summary(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
 const WATCHED_EXERCISES_KEY: &str = "watched_exercises";
 
+#[utoipa::path(
+    get,
+    path = "/analytics/report",
+    tag = "analytics",
+    responses((status = 200, body = Report))
+)]
 async fn analytics_report(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<lightweight_core::analytics::Report>, StatusCode> {
+) -> Result<Json<Report>, ApiError> {
     // Read watched exercise IDs from preferences
     let watched_ids: Vec<i64> = match lightweight_core::preferences::get_preference(
         &state.db, user_id, WATCHED_EXERCISES_KEY,
@@ -218,33 +435,46 @@ async fn analytics_report(
 
     lightweight_core::analytics::report(&state.db, user_id, &watched_ids)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
 const E1RM_SPIDER_PREF_KEY: &str = "e1rm_spider_exercises";
 
+#[utoipa::path(
+    get,
+    path = "/preferences/e1rm-spider",
+    tag = "preferences",
+    responses((status = 200, body = E1rmSpiderPrefs))
+)]
 async fn get_e1rm_spider_prefs(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<lightweight_core::preferences::E1rmSpiderPrefs>, StatusCode> {
+) -> Result<Json<E1rmSpiderPrefs>, ApiError> {
     match lightweight_core::preferences::get_preference(&state.db, user_id, E1RM_SPIDER_PREF_KEY) {
         Ok(Some(val)) => {
-            let prefs: lightweight_core::preferences::E1rmSpiderPrefs =
-                serde_json::from_str(&val).unwrap_or(lightweight_core::preferences::E1rmSpiderPrefs { exercise_ids: vec![] });
+            let prefs: E1rmSpiderPrefs =
+                serde_json::from_str(&val).unwrap_or(E1rmSpiderPrefs { exercise_ids: vec![] });
             Ok(Json(prefs))
         }
-        Ok(None) => Ok(Json(lightweight_core::preferences::E1rmSpiderPrefs { exercise_ids: vec![] })),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Ok(None) => Ok(Json(E1rmSpiderPrefs { exercise_ids: vec![] })),
+        Err(e) => Err(ApiError::from(e)),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/preferences/e1rm-spider",
+    tag = "preferences",
+    request_body = E1rmSpiderPrefs,
+    responses((status = 204, description = "No content"))
+)]
 async fn set_e1rm_spider_prefs(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-    Json(prefs): Json<lightweight_core::preferences::E1rmSpiderPrefs>,
-) -> Result<StatusCode, StatusCode> {
-    let val = serde_json::to_string(&prefs).map_err(|_| StatusCode::BAD_REQUEST)?;
+    Json(prefs): Json<E1rmSpiderPrefs>,
+) -> Result<StatusCode, ApiError> {
+    let val = serde_json::to_string(&prefs).map_err(|e| ApiError::from(AppError::BadRequest(e.to_string())))?;
     lightweight_core::preferences::set_preference(&state.db, user_id, E1RM_SPIDER_PREF_KEY, &val)
         .map(|_| StatusCode::NO_CONTENT)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
@@ -1,7 +1,10 @@
-use axum::{extract::State, http::StatusCode, Json, Router, routing::post};
+use axum::{extract::State, http::StatusCode, Router, routing::post};
 use std::sync::Arc;
 
 use crate::app::AppState;
+use crate::error::ApiError;
+use crate::extract::Json;
+use lightweight_core::error::AppError;
 use lightweight_core::models::{AuthResponse, BetaJoinRequest, BetaJoinResponse, BetaRegisterRequest, BetaSignupRequest, BetaSignupResponse};
 
 pub fn public_routes() -> Router<Arc<AppState>> {
@@ -12,20 +15,30 @@ pub fn public_routes() -> Router<Arc<AppState>> {
 }
 
 /// Email-only beta join (no account creation, works in any browser)
+#[utoipa::path(
+    post,
+    path = "/beta/join",
+    tag = "beta",
+    request_body = BetaJoinRequest,
+    responses((status = 201, body = BetaJoinResponse)),
+    security(())
+)]
 async fn beta_join(
     State(state): State<Arc<AppState>>,
     Json(body): Json<BetaJoinRequest>,
-) -> Result<(StatusCode, Json<BetaJoinResponse>), StatusCode> {
+) -> Result<(StatusCode, Json<BetaJoinResponse>), ApiError> {
+    super::require_feature(state.config.features.beta, "Beta signup")?;
+
     let inserted = lightweight_core::beta::record_join(
         &state.db,
         &body.email,
         &body.platform,
         body.referrer.as_deref(),
     )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .map_err(ApiError::from)?;
 
     if !inserted {
-        return Err(StatusCode::CONFLICT);
+        return Err(ApiError::from(AppError::AlreadyExists));
     }
 
     Ok((
@@ -38,25 +51,40 @@ async fn beta_join(
 }
 
 /// Google Sign-In beta signup (Android flow)
+#[utoipa::path(
+    post,
+    path = "/beta/signup",
+    tag = "beta",
+    request_body = BetaSignupRequest,
+    responses((status = 201, body = BetaSignupResponse)),
+    security(())
+)]
 async fn beta_signup(
     State(state): State<Arc<AppState>>,
     Json(body): Json<BetaSignupRequest>,
-) -> Result<(StatusCode, Json<BetaSignupResponse>), StatusCode> {
-    let google_client_id = std::env::var("LW_GOOGLE_CLIENT_ID")
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+) -> Result<(StatusCode, Json<BetaSignupResponse>), ApiError> {
+    super::require_feature(state.config.features.beta, "Beta signup")?;
 
     let claims = crate::google::verify_google_id_token(
         &state.http_client,
         &body.id_token,
-        &google_client_id,
+        super::google_client_id(&state)?,
     )
     .await
-    .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    .map_err(|e| ApiError::unauthorized().with_details(serde_json::json!({ "reason": e })))?;
 
-    let email = claims.email.clone().ok_or(StatusCode::BAD_REQUEST)?;
+    let email = claims
+        .email
+        .clone()
+        .ok_or_else(|| ApiError::from(AppError::BadRequest("Google account has no email".into())))?;
 
-    let auth = lightweight_core::auth::google_auth(&state.db, &claims.sub, Some(&email))
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let auth = lightweight_core::auth::google_auth(
+        &state.db,
+        &claims.sub,
+        Some(&email),
+        state.config.auth.session_lifetime_days,
+    )
+        .map_err(ApiError::from)?;
 
     super::tag_session_platform(&state, &auth.token, &body.platform);
 
@@ -67,7 +95,7 @@ async fn beta_signup(
         &body.platform,
         body.referrer.as_deref(),
     )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .map_err(ApiError::from)?;
 
     Ok((
         StatusCode::CREATED,
@@ -81,10 +109,20 @@ async fn beta_signup(
 }
 
 /// Username/password beta registration (non-Android flow)
+#[utoipa::path(
+    post,
+    path = "/beta/register",
+    tag = "beta",
+    request_body = BetaRegisterRequest,
+    responses((status = 201, body = AuthResponse)),
+    security(())
+)]
 async fn beta_register(
     State(state): State<Arc<AppState>>,
     Json(body): Json<BetaRegisterRequest>,
-) -> Result<(StatusCode, Json<AuthResponse>), StatusCode> {
+) -> Result<(StatusCode, Json<AuthResponse>), ApiError> {
+    super::require_feature(state.config.features.beta, "Beta signup")?;
+
     let auth = lightweight_core::auth::register(
         &state.db,
         &body.username,
@@ -92,13 +130,9 @@ async fn beta_register(
         None,
         None,
         body.email.as_deref(),
+        state.config.auth.session_lifetime_days,
     )
-    .map_err(|e| match e {
-        lightweight_core::error::AppError::UsernameTaken => StatusCode::CONFLICT,
-        lightweight_core::error::AppError::InvalidUsername(_) => StatusCode::BAD_REQUEST,
-        lightweight_core::error::AppError::WeakPassword => StatusCode::BAD_REQUEST,
-        _ => StatusCode::INTERNAL_SERVER_ERROR,
-    })?;
+    .map_err(ApiError::from)?;
 
     // Record beta signup — use email if provided, otherwise username as identifier
     let signup_email = body.email.as_deref().unwrap_or(&body.username);
@@ -109,7 +143,7 @@ async fn beta_register(
         &body.platform,
         body.referrer.as_deref(),
     )
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .map_err(ApiError::from)?;
 
     super::tag_session_platform(&state, &auth.token, &body.platform);
 
@@ -0,0 +1,82 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, put},
+    Extension, Router,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use std::sync::Arc;
+
+use crate::app::AppState;
+use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use lightweight_core::models::{Bodyweight, RecordBodyweight};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/bodyweights", get(list_bodyweights))
+        .route("/bodyweights/:date", put(record_bodyweight).delete(delete_bodyweight))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct BodyweightQuery {
+    since: Option<String>,
+    until: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/bodyweights",
+    tag = "bodyweight",
+    params(BodyweightQuery),
+    responses((status = 200, body = Vec<Bodyweight>))
+)]
+async fn list_bodyweights(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Query(query): Query<BodyweightQuery>,
+) -> Result<Json<Vec<Bodyweight>>, ApiError> {
+    lightweight_core::bodyweight::list(&state.db, user_id, query.since.as_deref(), query.until.as_deref())
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Record the bodyweight for a local date, replacing that date's entry.
+#[utoipa::path(
+    put,
+    path = "/bodyweights/{date}",
+    tag = "bodyweight",
+    params(("date" = String, Path, description = "Local date (YYYY-MM-DD)")),
+    request_body = RecordBodyweight,
+    responses((status = 200, body = Bodyweight))
+)]
+async fn record_bodyweight(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(date): Path<String>,
+    Json(body): Json<RecordBodyweight>,
+) -> Result<Json<Bodyweight>, ApiError> {
+    lightweight_core::bodyweight::record(&state.db, user_id, &date, body.weight_kg)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/bodyweights/{date}",
+    tag = "bodyweight",
+    params(("date" = String, Path, description = "Local date (YYYY-MM-DD)")),
+    responses((status = 204, description = "No content"))
+)]
+async fn delete_bodyweight(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(date): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::bodyweight::delete(&state.db, user_id, &date)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
+}
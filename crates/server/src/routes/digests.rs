@@ -0,0 +1,120 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Extension, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::app::AppState;
+use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use lightweight_core::digest::DigestRecord;
+use lightweight_core::models::GenerateDigest;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/digests", get(list_digests).post(generate_digest))
+        .route("/digests/:id", get(get_digest))
+        .route("/digests/:id/markdown", get(digest_markdown))
+        .route("/digests/:id/html", get(digest_html))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DigestsQuery {
+    /// Most recent weeks to return (default 12).
+    limit: Option<i64>,
+}
+
+/// Stored weekly digests, newest week first.
+#[utoipa::path(
+    get,
+    path = "/digests",
+    tag = "digests",
+    params(DigestsQuery),
+    responses((status = 200, body = Vec<DigestRecord>))
+)]
+async fn list_digests(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Query(q): Query<DigestsQuery>,
+) -> Result<Json<Vec<DigestRecord>>, ApiError> {
+    let limit = q.limit.unwrap_or(12).clamp(1, 520);
+    lightweight_core::digest::list(&state.db, user_id, limit)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Build (or rebuild) a week's digest now and send it to `digest.ready`
+/// webhooks. Scheduled digests are generated without this.
+#[utoipa::path(
+    post,
+    path = "/digests",
+    tag = "digests",
+    request_body = GenerateDigest,
+    responses((status = 201, body = DigestRecord))
+)]
+async fn generate_digest(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    body: Option<Json<GenerateDigest>>,
+) -> Result<(StatusCode, Json<DigestRecord>), ApiError> {
+    let Json(body) = body.unwrap_or_default();
+    lightweight_core::digest::generate(&state.db, user_id, body.week_start.as_deref())
+        .map(|d| (StatusCode::CREATED, Json(d)))
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/digests/{id}",
+    tag = "digests",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = DigestRecord))
+)]
+async fn get_digest(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<Json<DigestRecord>, ApiError> {
+    lightweight_core::digest::get(&state.db, user_id, id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    get,
+    path = "/digests/{id}/markdown",
+    tag = "digests",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, description = "Digest as Markdown", content_type = "text/markdown", body = String))
+)]
+async fn digest_markdown(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let markdown = lightweight_core::digest::rendered(&state.db, user_id, id, "markdown")?;
+    Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], markdown))
+}
+
+#[utoipa::path(
+    get,
+    path = "/digests/{id}/html",
+    tag = "digests",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, description = "Digest as a standalone HTML page", content_type = "text/html", body = String))
+)]
+async fn digest_html(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<impl IntoResponse, ApiError> {
+    let html = lightweight_core::digest::rendered(&state.db, user_id, id, "html")?;
+    Ok(([(header::CONTENT_TYPE, "text/html; charset=utf-8")], html))
+}
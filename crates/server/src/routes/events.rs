@@ -0,0 +1,80 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Extension, Router,
+};
+use futures_util::stream::Stream;
+use lightweight_core::changes::{Change, ChangeEvent};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use crate::app::AppState;
+use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::Path;
+use crate::push::Scope;
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/events", get(account_events))
+        .route("/sessions/:id/events", get(session_events))
+}
+
+/// Id of the last event a reconnecting client saw.
+fn last_event_id(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+}
+
+/// Live changes for the signed-in account as Server-Sent Events. A fresh
+/// connection opens with the active session's rest timer, if one is set.
+#[utoipa::path(
+    get,
+    path = "/events",
+    tag = "events",
+    params(("Last-Event-ID" = Option<u64>, Header, description = "Resume after this event")),
+    responses((status = 200, description = "Event stream of session changes and `resync`", content_type = "text/event-stream", body = String))
+)]
+async fn account_events(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let initial = lightweight_core::rest_timer::get_active(&state.db, user_id)?
+        .map(|timer| Change::new(user_id, timer.session_id, ChangeEvent::RestTimer { timer: Some(timer) }))
+        .into_iter()
+        .collect();
+    let events = state.push.stream(Scope::Account(user_id), last_event_id(&headers), initial);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Live changes to one session: sets added, updated and deleted, exercises
+/// added, reordered and removed, status changes and the rest timer.
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}/events",
+    tag = "events",
+    params(
+        ("id" = i64, Path, description = "Session id"),
+        ("Last-Event-ID" = Option<u64>, Header, description = "Resume after this event")
+    ),
+    responses((status = 200, description = "Event stream of session changes and `resync`", content_type = "text/event-stream", body = String))
+)]
+async fn session_events(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    // Also checks the session belongs to the user.
+    let initial = lightweight_core::rest_timer::get(&state.db, user_id, id)?
+        .map(|timer| Change::new(user_id, id, ChangeEvent::RestTimer { timer: Some(timer) }))
+        .into_iter()
+        .collect();
+    let events = state.push.stream(Scope::Session(user_id, id), last_event_id(&headers), initial);
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
@@ -1,13 +1,15 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
 use lightweight_core::models::*;
 
 pub fn routes() -> Router<Arc<AppState>> {
@@ -16,63 +18,87 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/exercises/:id", get(get_exercise).put(update_exercise).delete(archive_exercise))
 }
 
+#[utoipa::path(
+    get,
+    path = "/exercises",
+    tag = "exercises",
+    responses((status = 200, body = Vec<Exercise>))
+)]
 async fn list_exercises(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<Exercise>>, StatusCode> {
+) -> Result<Json<Vec<Exercise>>, ApiError> {
     lightweight_core::exercises::list(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/exercises/{id}",
+    tag = "exercises",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Exercise))
+)]
 async fn get_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<Exercise>, StatusCode> {
+) -> Result<Json<Exercise>, ApiError> {
     lightweight_core::exercises::get(&state.db, user_id, id)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/exercises",
+    tag = "exercises",
+    request_body = CreateExercise,
+    responses((status = 201, body = Exercise))
+)]
 async fn create_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<CreateExercise>,
-) -> Result<(StatusCode, Json<Exercise>), StatusCode> {
+) -> Result<(StatusCode, Json<Exercise>), ApiError> {
     lightweight_core::exercises::create(&state.db, user_id, &body)
         .map(|e| (StatusCode::CREATED, Json(e)))
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::AlreadyExists => StatusCode::CONFLICT,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    put,
+    path = "/exercises/{id}",
+    tag = "exercises",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = UpdateExercise,
+    responses((status = 200, body = Exercise))
+)]
 async fn update_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
     Json(body): Json<UpdateExercise>,
-) -> Result<Json<Exercise>, StatusCode> {
+) -> Result<Json<Exercise>, ApiError> {
     lightweight_core::exercises::update(&state.db, user_id, id, &body)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/exercises/{id}",
+    tag = "exercises",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 204, description = "No content"))
+)]
 async fn archive_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> StatusCode {
-    match lightweight_core::exercises::archive(&state.db, user_id, id) {
-        Ok(()) => StatusCode::NO_CONTENT,
-        Err(lightweight_core::error::AppError::NotFound) => StatusCode::NOT_FOUND,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::exercises::archive(&state.db, user_id, id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
 }
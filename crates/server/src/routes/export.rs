@@ -3,12 +3,15 @@ use axum::{
     http::{header, StatusCode},
     response::IntoResponse,
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::Json;
+use lightweight_core::export::ExportMeta;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
@@ -16,23 +19,37 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/export/sessions", get(export_sessions))
 }
 
+#[utoipa::path(
+    get,
+    path = "/export/meta",
+    tag = "export",
+    responses((status = 200, body = ExportMeta))
+)]
 async fn export_meta(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<lightweight_core::export::ExportMeta>, StatusCode> {
+) -> Result<Json<ExportMeta>, ApiError> {
+    super::require_feature(state.config.features.export, "Export")?;
     lightweight_core::export::export_meta(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
 const RATE_LIMIT_KEY: &str = "last_export_at";
-const RATE_LIMIT_SECONDS: i64 = 7 * 24 * 60 * 60; // 1 week
 
+#[utoipa::path(
+    get,
+    path = "/export/sessions",
+    tag = "export",
+    responses((status = 200, description = "CSV of every logged set", content_type = "text/csv", body = String))
+)]
 async fn export_sessions(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<impl IntoResponse, ApiError> {
+    super::require_feature(state.config.features.export, "Export")?;
     // Rate limit check (skipped in debug/dev builds)
+    let interval = state.config.rate_limit.export_interval_secs;
     if !cfg!(debug_assertions) {
         if let Ok(Some(last_export)) =
             lightweight_core::preferences::get_preference(&state.db, user_id, RATE_LIMIT_KEY)
@@ -40,15 +57,21 @@ async fn export_sessions(
             if let Ok(last_ts) = chrono::NaiveDateTime::parse_from_str(&last_export, "%Y-%m-%d %H:%M:%S") {
                 let now = chrono::Utc::now().naive_utc();
                 let elapsed = (now - last_ts).num_seconds();
-                if elapsed < RATE_LIMIT_SECONDS {
-                    return Err(StatusCode::TOO_MANY_REQUESTS);
+                if elapsed < interval {
+                    state.metrics.inc_rate_limited("export");
+                    return Err(ApiError::new(
+                        StatusCode::TOO_MANY_REQUESTS,
+                        "rate_limited",
+                        "Export rate limit reached",
+                    )
+                    .with_retry_after((interval - elapsed) as u64));
                 }
             }
         }
     }
 
     let csv = lightweight_core::export::export_csv(&state.db, user_id)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(ApiError::from)?;
 
     // Record export time
     let now = chrono::Utc::now().naive_utc().format("%Y-%m-%d %H:%M:%S").to_string();
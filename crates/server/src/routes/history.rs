@@ -1,13 +1,14 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::State,
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
 use lightweight_core::models::*;
 
 pub fn routes() -> Router<Arc<AppState>> {
@@ -17,35 +18,53 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/sessions/:id/exercise-previous", get(session_exercise_previous))
 }
 
+#[utoipa::path(
+    get,
+    path = "/exercises/{id}/history",
+    tag = "history",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = ExerciseHistory))
+)]
 async fn exercise_history(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<ExerciseHistory>, StatusCode> {
+) -> Result<Json<ExerciseHistory>, ApiError> {
     lightweight_core::sessions::exercise_history(&state.db, user_id, id, 10)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}/exercise-previous",
+    tag = "history",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Vec<ExercisePreviousSets>))
+)]
 async fn session_exercise_previous(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<Vec<ExercisePreviousSets>>, StatusCode> {
+) -> Result<Json<Vec<ExercisePreviousSets>>, ApiError> {
     lightweight_core::sessions::session_exercise_previous(&state.db, user_id, id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/templates/{id}/previous",
+    tag = "history",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Option<Session>))
+)]
 async fn template_previous(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<Option<Session>>, StatusCode> {
+) -> Result<Json<Option<Session>>, ApiError> {
     lightweight_core::sessions::template_previous(&state.db, user_id, id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
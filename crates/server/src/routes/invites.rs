@@ -1,13 +1,15 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
 use lightweight_core::models::*;
 
 pub fn routes() -> Router<Arc<AppState>> {
@@ -21,48 +23,78 @@ pub fn public_routes() -> Router<Arc<AppState>> {
     )
 }
 
+#[utoipa::path(
+    post,
+    path = "/invites",
+    tag = "invites",
+    responses((status = 201, body = Invite))
+)]
 async fn create_invite(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<(StatusCode, Json<Invite>), StatusCode> {
+) -> Result<(StatusCode, Json<Invite>), ApiError> {
+    super::require_feature(state.config.features.invites, "Invites")?;
     lightweight_core::invites::create_invite(&state.db, user_id)
         .map(|i| (StatusCode::CREATED, Json(i)))
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::InviteQuotaExceeded => StatusCode::FORBIDDEN,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/invites",
+    tag = "invites",
+    responses((status = 200, body = InviteList))
+)]
 async fn list_invites(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<InviteList>, StatusCode> {
+) -> Result<Json<InviteList>, ApiError> {
+    super::require_feature(state.config.features.invites, "Invites")?;
     lightweight_core::invites::list_invites(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/join/{code}",
+    tag = "invites",
+    params(("code" = String, Path, description = "Invite code")),
+    responses((status = 200, body = InviteValidation)),
+    security(())
+)]
 async fn validate_join_code(
     State(state): State<Arc<AppState>>,
     Path(code): Path<String>,
-) -> Result<Json<InviteValidation>, StatusCode> {
+) -> Result<Json<InviteValidation>, ApiError> {
+    super::require_feature(state.config.features.invites, "Invites")?;
     lightweight_core::invites::validate_invite_code(&state.db, &code)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/join/{code}",
+    tag = "invites",
+    params(("code" = String, Path, description = "Invite code")),
+    request_body = JoinRequest,
+    responses((status = 201, body = AuthResponse)),
+    security(())
+)]
 async fn register_via_invite(
     State(state): State<Arc<AppState>>,
     Path(code): Path<String>,
     Json(body): Json<JoinRequest>,
-) -> Result<(StatusCode, Json<AuthResponse>), StatusCode> {
-    lightweight_core::invites::register_with_invite(&state.db, &code, &body.username, &body.password)
+) -> Result<(StatusCode, Json<AuthResponse>), ApiError> {
+    super::require_feature(state.config.features.invites, "Invites")?;
+    lightweight_core::invites::register_with_invite(
+        &state.db,
+        &code,
+        &body.username,
+        &body.password,
+        state.config.auth.session_lifetime_days,
+    )
         .map(|r| (StatusCode::CREATED, Json(r)))
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::InvalidInviteCode => StatusCode::FORBIDDEN,
-            lightweight_core::error::AppError::UsernameTaken => StatusCode::CONFLICT,
-            lightweight_core::error::AppError::InvalidUsername(_) => StatusCode::BAD_REQUEST,
-            lightweight_core::error::AppError::WeakPassword => StatusCode::BAD_REQUEST,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
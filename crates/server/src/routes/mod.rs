@@ -1,13 +1,18 @@
 mod admin;
 mod analytics;
 mod beta;
+mod bodyweight;
+mod digests;
+mod events;
 mod exercises;
 mod export;
 mod history;
 mod invites;
+mod openapi;
 mod preferences;
 mod sessions;
 mod templates;
+mod webhooks;
 
 use axum::{routing::{get, post}, Router};
 use std::sync::Arc;
@@ -24,9 +29,21 @@ pub fn public_routes() -> Router<Arc<AppState>> {
         .merge(beta::public_routes())
 }
 
-async fn get_config() -> Json<serde_json::Value> {
-    let google_client_id = std::env::var("LW_GOOGLE_CLIENT_ID").unwrap_or_default();
-    Json(serde_json::json!({ "google_client_id": google_client_id }))
+#[utoipa::path(
+    get,
+    path = "/config",
+    tag = "auth",
+    responses((status = 200, body = ConfigResponse)),
+    security(())
+)]
+async fn get_config(State(state): State<Arc<AppState>>) -> Json<ConfigResponse> {
+    let google_client_id = state.config.auth.google_client_id.clone().unwrap_or_default();
+    Json(ConfigResponse { google_client_id })
+}
+
+/// Routes outside both the rate limiter and auth (the OpenAPI spec).
+pub fn docs_routes() -> Router<Arc<AppState>> {
+    openapi::routes()
 }
 
 pub fn protected_routes() -> Router<Arc<AppState>> {
@@ -37,9 +54,13 @@ pub fn protected_routes() -> Router<Arc<AppState>> {
         .merge(exercises::routes())
         .merge(templates::routes())
         .merge(sessions::routes())
+        .merge(events::routes())
         .merge(history::routes())
         .merge(analytics::routes())
         .merge(preferences::routes())
+        .merge(bodyweight::routes())
+        .merge(webhooks::routes())
+        .merge(digests::routes())
         .merge(export::routes())
         .merge(invites::routes())
         .merge(admin::routes())
@@ -47,11 +68,28 @@ pub fn protected_routes() -> Router<Arc<AppState>> {
 
 // ── Auth handlers ──
 
-use axum::{extract::State, http::{HeaderMap, StatusCode}, Extension, Json};
+use axum::{extract::State, http::{HeaderMap, StatusCode}, Extension};
 use crate::auth::AuthToken;
+use crate::error::ApiError;
+use crate::extract::Json;
 use lightweight_core::models::{AuthResponse, GoogleAuthRequest, LoginRequest, RegisterRequest};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct ConfigResponse {
+    google_client_id: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(super) struct MeResponse {
+    user_id: i64,
+    username: Option<String>,
+    email: Option<String>,
+    created_at: String,
+}
 
-pub(super) fn detect_platform(headers: &HeaderMap) -> &'static str {
+pub(crate) fn detect_platform(headers: &HeaderMap) -> &'static str {
     if let Some(ua) = headers.get("user-agent").and_then(|v| v.to_str().ok()) {
         if ua.contains("okhttp") || ua.contains("Android") {
             return "android";
@@ -60,6 +98,24 @@ pub(super) fn detect_platform(headers: &HeaderMap) -> &'static str {
     "web"
 }
 
+/// Reject the request when a `[features]` toggle is off.
+pub(super) fn require_feature(enabled: bool, feature: &str) -> Result<(), ApiError> {
+    if enabled {
+        Ok(())
+    } else {
+        Err(ApiError::feature_disabled(feature))
+    }
+}
+
+pub(super) fn google_client_id(state: &AppState) -> Result<&str, ApiError> {
+    state
+        .config
+        .auth
+        .google_client_id
+        .as_deref()
+        .ok_or_else(|| ApiError::internal("Google sign-in is not configured"))
+}
+
 pub(crate) fn tag_session_platform(state: &AppState, token: &str, platform: &str) {
     if let Ok(conn) = state.db.lock() {
         let _ = conn.execute(
@@ -69,72 +125,110 @@ pub(crate) fn tag_session_platform(state: &AppState, token: &str, platform: &str
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses((status = 201, body = AuthResponse)),
+    security(())
+)]
 async fn auth_register(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<RegisterRequest>,
-) -> Result<(StatusCode, Json<AuthResponse>), StatusCode> {
-    let invite_code = std::env::var("LW_INVITE_CODE").ok();
+) -> Result<(StatusCode, Json<AuthResponse>), ApiError> {
+    require_feature(state.config.features.registration, "Registration")?;
     let result = lightweight_core::auth::register(
         &state.db,
         &body.username,
         &body.password,
         body.invite_code.as_deref(),
-        invite_code.as_deref(),
+        state.config.auth.invite_code.as_deref(),
         body.email.as_deref(),
+        state.config.auth.session_lifetime_days,
     )
-    .map_err(|e| match e {
-        lightweight_core::error::AppError::UsernameTaken => StatusCode::CONFLICT,
-        lightweight_core::error::AppError::InvalidInviteCode => StatusCode::FORBIDDEN,
-        lightweight_core::error::AppError::InvalidUsername(_) => StatusCode::BAD_REQUEST,
-        lightweight_core::error::AppError::WeakPassword => StatusCode::BAD_REQUEST,
-        _ => StatusCode::INTERNAL_SERVER_ERROR,
-    })?;
+    .map_err(ApiError::from)?;
     tag_session_platform(&state, &result.token, detect_platform(&headers));
     Ok((StatusCode::CREATED, Json(result)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/google",
+    tag = "auth",
+    request_body = GoogleAuthRequest,
+    responses((status = 201, body = AuthResponse)),
+    security(())
+)]
 async fn auth_google(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<GoogleAuthRequest>,
-) -> Result<(StatusCode, Json<AuthResponse>), StatusCode> {
-    let google_client_id = std::env::var("LW_GOOGLE_CLIENT_ID")
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
+) -> Result<(StatusCode, Json<AuthResponse>), ApiError> {
     let claims = crate::google::verify_google_id_token(
         &state.http_client,
         &body.id_token,
-        &google_client_id,
+        google_client_id(&state)?,
     )
     .await
-    .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    .map_err(|e| ApiError::unauthorized().with_details(serde_json::json!({ "reason": e })))?;
 
-    let result = lightweight_core::auth::google_auth(&state.db, &claims.sub, claims.email.as_deref())
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let result = lightweight_core::auth::google_auth(
+        &state.db,
+        &claims.sub,
+        claims.email.as_deref(),
+        state.config.auth.session_lifetime_days,
+    )
+        .map_err(ApiError::from)?;
     tag_session_platform(&state, &result.token, detect_platform(&headers));
     Ok((StatusCode::CREATED, Json(result)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses((status = 200, body = AuthResponse)),
+    security(())
+)]
 async fn auth_login(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     Json(body): Json<LoginRequest>,
-) -> Result<Json<AuthResponse>, StatusCode> {
-    let result = lightweight_core::auth::login(&state.db, &body.username, &body.password)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+) -> Result<Json<AuthResponse>, ApiError> {
+    let result = lightweight_core::auth::login(
+        &state.db,
+        &body.username,
+        &body.password,
+        state.config.auth.session_lifetime_days,
+    )
+        .map_err(ApiError::from)?;
     tag_session_platform(&state, &result.token, detect_platform(&headers));
     Ok(Json(result))
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/check",
+    tag = "auth",
+    responses((status = 200, description = "Token is valid"))
+)]
 async fn auth_check() -> StatusCode {
     StatusCode::OK
 }
 
+#[utoipa::path(
+    get,
+    path = "/auth/me",
+    tag = "auth",
+    responses((status = 200, body = MeResponse))
+)]
 async fn auth_me(
     State(state): State<Arc<AppState>>,
     Extension(crate::auth::UserId(user_id)): Extension<crate::auth::UserId>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<MeResponse>, ApiError> {
     let conn = state.db.lock().unwrap();
     let result: Result<(Option<String>, Option<String>, String), _> = conn.query_row(
         "SELECT username, email, created_at FROM users WHERE id = ?1",
@@ -142,22 +236,27 @@ async fn auth_me(
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
     );
     match result {
-        Ok((username, email, created_at)) => Ok(Json(serde_json::json!({
-            "user_id": user_id,
-            "username": username,
-            "email": email,
-            "created_at": created_at,
-        }))),
-        Err(_) => Err(StatusCode::NOT_FOUND),
+        Ok((username, email, created_at)) => Ok(Json(MeResponse {
+            user_id,
+            username,
+            email,
+            created_at,
+        })),
+        Err(_) => Err(ApiError::from(lightweight_core::error::AppError::NotFound)),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    responses((status = 204, description = "No content"))
+)]
 async fn auth_logout(
     State(state): State<Arc<AppState>>,
     Extension(AuthToken(token)): Extension<AuthToken>,
-) -> StatusCode {
-    match lightweight_core::auth::logout(&state.db, &token) {
-        Ok(()) => StatusCode::NO_CONTENT,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::auth::logout(&state.db, &token)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
 }
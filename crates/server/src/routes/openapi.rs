@@ -0,0 +1,246 @@
+//! OpenAPI description of the `/api/v1` surface.
+//!
+//! The spec is generated from the handler annotations and checked in at
+//! `docs/openapi.json`. Regenerate the snapshot after changing a route or a
+//! request/response type with `LW_UPDATE_OPENAPI=1 cargo test -p lightweight-server`.
+
+use axum::{routing::get, Json, Router};
+use std::sync::Arc;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{RefOr, ResponseBuilder};
+use utoipa::{Modify, OpenApi};
+
+use crate::app::AppState;
+use crate::error::{ErrorPayload, ErrorResponse};
+use lightweight_core::analytics;
+use lightweight_core::digest;
+use lightweight_core::models;
+use lightweight_core::session_report;
+
+use super::{admin, analytics as analytics_routes, beta, bodyweight, digests, events, exercises, export, history, invites, preferences, sessions, templates, webhooks};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Lightweight API"),
+    servers((url = "/api/v1")),
+    paths(
+        super::get_config,
+        super::auth_register,
+        super::auth_google,
+        super::auth_login,
+        super::auth_check,
+        super::auth_me,
+        super::auth_logout,
+        exercises::list_exercises,
+        exercises::get_exercise,
+        exercises::create_exercise,
+        exercises::update_exercise,
+        exercises::archive_exercise,
+        templates::list_templates,
+        templates::get_template,
+        templates::create_template,
+        templates::update_template,
+        templates::list_versions,
+        templates::get_version,
+        templates::sync_templates,
+        templates::archive_template,
+        sessions::list_sessions,
+        sessions::get_active_session,
+        sessions::get_session,
+        sessions::create_session,
+        sessions::update_session,
+        sessions::delete_session,
+        sessions::reopen_session,
+        sessions::session_timeline,
+        sessions::session_report,
+        sessions::get_rest_timer,
+        sessions::adjust_rest_timer,
+        sessions::skip_rest_timer,
+        events::account_events,
+        events::session_events,
+        sessions::add_exercise,
+        sessions::update_exercise,
+        sessions::remove_exercise,
+        sessions::add_set,
+        sessions::update_set,
+        sessions::delete_set,
+        sessions::sync_sessions,
+        sessions::import_sessions,
+        history::exercise_history,
+        history::session_exercise_previous,
+        history::template_previous,
+        analytics_routes::heatmap,
+        analytics_routes::heatmap_templates,
+        analytics_routes::heatmap_prs,
+        analytics_routes::exercises_with_data,
+        analytics_routes::e1rm_progression,
+        analytics_routes::rep_maxes,
+        analytics_routes::weekly_volume,
+        analytics_routes::session_frequency,
+        analytics_routes::e1rm_movers,
+        analytics_routes::stale_exercises,
+        analytics_routes::plateaus,
+        analytics_routes::strength_scores,
+        analytics_routes::e1rm_spider,
+        analytics_routes::session_prs,
+        analytics_routes::exercise_volume,
+        analytics_routes::rest_intervals,
+        analytics_routes::rest_performance,
+        analytics_routes::analytics_summary,
+        analytics_routes::analytics_report,
+        analytics_routes::get_e1rm_spider_prefs,
+        analytics_routes::set_e1rm_spider_prefs,
+        preferences::get_pref,
+        preferences::set_pref,
+        bodyweight::list_bodyweights,
+        bodyweight::record_bodyweight,
+        bodyweight::delete_bodyweight,
+        webhooks::list_webhooks,
+        webhooks::create_webhook,
+        webhooks::update_webhook,
+        webhooks::delete_webhook,
+        webhooks::list_deliveries,
+        webhooks::ping_webhook,
+        digests::list_digests,
+        digests::generate_digest,
+        digests::get_digest,
+        digests::digest_markdown,
+        digests::digest_html,
+        export::export_meta,
+        export::export_sessions,
+        invites::create_invite,
+        invites::list_invites,
+        invites::validate_join_code,
+        invites::register_via_invite,
+        beta::beta_join,
+        beta::beta_signup,
+        beta::beta_register,
+        admin::beta,
+        admin::add_beta,
+        admin::update_beta_status,
+        admin::users,
+        admin::activity
+    ),
+    components(schemas(
+        ErrorResponse, ErrorPayload,
+        super::ConfigResponse, super::MeResponse,
+        preferences::PrefResponse, preferences::PrefBody,
+        admin::AdminBetaSignup, admin::AdminUser, admin::AdminActivity,
+        admin::AddBetaRequest, admin::UpdateBetaStatusRequest,
+        lightweight_core::export::ExportMeta,
+        lightweight_core::preferences::E1rmSpiderPrefs,
+        lightweight_core::preferences::CompetitionLifts,
+        lightweight_core::preferences::EquipmentProfile, lightweight_core::preferences::PlateCount,
+        models::Bodyweight, models::RecordBodyweight,
+        models::Exercise, models::CreateExercise, models::UpdateExercise, models::Template,
+        models::TemplateSnapshot, models::TemplateExercise, models::CreateTemplate,
+        models::CreateTemplateExercise, models::UpdateTemplate, models::SyncTemplate,
+        models::SyncTemplateExercise, models::TemplateSyncResult, models::Session,
+        models::SessionSummary, models::SessionExerciseWithSets, models::Set,
+        models::SessionStatus, models::SetType,
+        models::SessionTimeline, models::SessionEvent, models::PauseInterval,
+        models::RestTimer, models::AdjustRestTimer,
+        session_report::SessionReport, session_report::ExerciseReport, session_report::VolumeChange,
+        session_report::Adherence, session_report::Anomaly, analytics::SessionPR,
+        models::Webhook, models::WebhookCreated, models::CreateWebhook, models::UpdateWebhook,
+        models::WebhookDelivery,
+        digest::DigestRecord, digest::Digest, digest::DigestSession, digest::MuscleGroupSets,
+        digest::Streak, models::GenerateDigest,
+        models::CreateSession, models::UpdateSession, models::AddSessionExercise,
+        models::UpdateSessionExercise, models::CreateSet, models::UpdateSet,
+        models::SyncSession, models::SyncExercise, models::SyncSet, models::SyncResult,
+        models::ImportSession, models::ImportExercise, models::ImportSet, models::ImportResult,
+        models::LoginRequest, models::RegisterRequest, models::GoogleAuthRequest,
+        models::AuthResponse, models::Invite, models::InviteList, models::InviteValidation,
+        models::JoinRequest, models::BetaSignupRequest, models::BetaRegisterRequest,
+        models::BetaJoinRequest, models::BetaJoinResponse, models::BetaSignupResponse,
+        models::BetaSignup, models::ExerciseHistory, models::ExerciseHistoryEntry,
+        models::ExercisePreviousSets,
+        analytics::DayActivity, analytics::E1rmDataPoint, analytics::PersonalRecord,
+        analytics::ExerciseE1rm, analytics::ExercisePRs, analytics::E1rmSpiderPoint,
+        analytics::E1rmMover, analytics::StaleExercise, analytics::StalledExercise,
+        analytics::StrengthScores, analytics::StrengthScorePoint, analytics::RepMaxes, analytics::RepMax, analytics::DayTemplateActivity,
+        analytics::ExerciseSummary, analytics::WeeklyVolume, analytics::WeeklyFrequency,
+        analytics::ExercisePRData, analytics::DayPR, analytics::ExerciseWeeklyVolume,
+        analytics::RestAnalytics, analytics::SessionRest, analytics::ExerciseRest,
+        analytics::RestPerformanceBucket,
+        analytics::AnalyticsSummary, analytics::Report, analytics::WatchedExercise
+    )),
+    modifiers(&Security, &ErrorResponses),
+    security(("bearer" = [])),
+    tags(
+        (name = "auth"), (name = "exercises"), (name = "templates"), (name = "sessions"),
+        (name = "history"), (name = "analytics"), (name = "preferences"), (name = "bodyweight"), (name = "export"),
+        (name = "invites"), (name = "beta"), (name = "admin"), (name = "events"),
+        (name = "webhooks"), (name = "digests")
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the bearer token scheme used by every protected route.
+struct Security;
+
+impl Modify for Security {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// Every handler can fail with an `ErrorResponse`; document it once as the
+/// `default` response instead of repeating it on each path.
+struct ErrorResponses;
+
+impl Modify for ErrorResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let response = ResponseBuilder::new()
+            .description("Error")
+            .content(
+                "application/json",
+                utoipa::openapi::ContentBuilder::new()
+                    .schema(utoipa::openapi::Ref::from_schema_name("ErrorResponse"))
+                    .build(),
+            )
+            .build();
+        for item in openapi.paths.paths.values_mut() {
+            for op in item.operations.values_mut() {
+                op.responses
+                    .responses
+                    .entry("default".to_string())
+                    .or_insert_with(|| RefOr::T(response.clone()));
+            }
+        }
+    }
+}
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/openapi.json", get(openapi_json))
+}
+
+async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../docs/openapi.json");
+
+    #[test]
+    fn spec_matches_checked_in_snapshot() {
+        let generated = ApiDoc::openapi().to_pretty_json().unwrap() + "\n";
+        if std::env::var_os("LW_UPDATE_OPENAPI").is_some() {
+            std::fs::write(SNAPSHOT, &generated).unwrap();
+            return;
+        }
+        let on_disk = std::fs::read_to_string(SNAPSHOT).unwrap_or_default();
+        assert!(
+            on_disk == generated,
+            "docs/openapi.json is out of date; regenerate with LW_UPDATE_OPENAPI=1 cargo test -p lightweight-server"
+        );
+    }
+}
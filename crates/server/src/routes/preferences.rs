@@ -1,50 +1,69 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
     routing::get,
-    Extension, Json, Router,
+    Extension, Router,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
+use lightweight_core::error::AppError;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
         .route("/preferences/:key", get(get_pref).put(set_pref))
 }
 
-#[derive(Serialize)]
-struct PrefResponse {
+#[derive(Serialize, ToSchema)]
+pub(super) struct PrefResponse {
     key: String,
     value: String,
 }
 
-#[derive(Deserialize)]
-struct PrefBody {
+#[derive(Deserialize, ToSchema)]
+pub(super) struct PrefBody {
     value: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/preferences/{key}",
+    tag = "preferences",
+    params(("key" = String, Path, description = "Preference key")),
+    responses((status = 200, body = PrefResponse))
+)]
 async fn get_pref(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(key): Path<String>,
-) -> Result<Json<PrefResponse>, StatusCode> {
+) -> Result<Json<PrefResponse>, ApiError> {
     match lightweight_core::preferences::get_preference(&state.db, user_id, &key) {
         Ok(Some(value)) => Ok(Json(PrefResponse { key, value })),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Ok(None) => Err(ApiError::from(AppError::NotFound)),
+        Err(e) => Err(ApiError::from(e)),
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/preferences/{key}",
+    tag = "preferences",
+    params(("key" = String, Path, description = "Preference key")),
+    request_body = PrefBody,
+    responses((status = 204, description = "No content"))
+)]
 async fn set_pref(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(key): Path<String>,
     Json(body): Json<PrefBody>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<StatusCode, ApiError> {
     lightweight_core::preferences::set_preference(&state.db, user_id, &key, &body.value)
         .map(|_| StatusCode::NO_CONTENT)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
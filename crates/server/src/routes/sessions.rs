@@ -1,14 +1,17 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::State,
     http::StatusCode,
     routing::{get, post, put},
-    Extension, Json, Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
 use lightweight_core::models::*;
+use lightweight_core::session_report::SessionReport;
 
 pub fn routes() -> Router<Arc<AppState>> {
     Router::new()
@@ -17,188 +20,371 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/sessions/import", post(import_sessions))
         .route("/sessions/sync", post(sync_sessions))
         .route("/sessions/:id", get(get_session).put(update_session).delete(delete_session))
+        .route("/sessions/:id/reopen", post(reopen_session))
+        .route("/sessions/:id/timeline", get(session_timeline))
+        .route("/sessions/:id/report", get(session_report))
+        .route("/sessions/:id/rest-timer", get(get_rest_timer))
+        .route("/sessions/:id/rest-timer/adjust", post(adjust_rest_timer))
+        .route("/sessions/:id/rest-timer/skip", post(skip_rest_timer))
         .route("/sessions/:sid/exercises", post(add_exercise))
         .route("/sessions/:sid/exercises/:seid", put(update_exercise).delete(remove_exercise))
         .route("/sessions/:sid/exercises/:seid/sets", post(add_set))
         .route("/sets/:id", put(update_set).delete(delete_set))
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions",
+    tag = "sessions",
+    params(SessionListParams),
+    responses((status = 200, body = Vec<SessionSummary>))
+)]
 async fn list_sessions(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Query(params): Query<SessionListParams>,
-) -> Result<Json<Vec<SessionSummary>>, StatusCode> {
+) -> Result<Json<Vec<SessionSummary>>, ApiError> {
     lightweight_core::sessions::list(&state.db, user_id, &params)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/active",
+    tag = "sessions",
+    responses((status = 200, body = Option<Session>))
+)]
 async fn get_active_session(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Option<Session>>, StatusCode> {
+) -> Result<Json<Option<Session>>, ApiError> {
     lightweight_core::sessions::get_active(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Session))
+)]
 async fn get_session(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<Session>, StatusCode> {
+) -> Result<Json<Session>, ApiError> {
     lightweight_core::sessions::get(&state.db, user_id, id)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions",
+    tag = "sessions",
+    request_body = CreateSession,
+    responses((status = 201, body = Session))
+)]
 async fn create_session(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<CreateSession>,
-) -> Result<(StatusCode, Json<Session>), StatusCode> {
+) -> Result<(StatusCode, Json<Session>), ApiError> {
     lightweight_core::sessions::create(&state.db, user_id, &body)
         .map(|s| (StatusCode::CREATED, Json(s)))
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    put,
+    path = "/sessions/{id}",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = UpdateSession,
+    responses((status = 200, body = Session))
+)]
 async fn update_session(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
     Json(body): Json<UpdateSession>,
-) -> Result<Json<Session>, StatusCode> {
+) -> Result<Json<Session>, ApiError> {
     lightweight_core::sessions::update(&state.db, user_id, id, &body)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            lightweight_core::error::AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/sessions/{id}",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 204, description = "No content"))
+)]
 async fn delete_session(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> StatusCode {
-    match lightweight_core::sessions::delete(&state.db, user_id, id) {
-        Ok(()) => StatusCode::NO_CONTENT,
-        Err(lightweight_core::error::AppError::NotFound) => StatusCode::NOT_FOUND,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::sessions::delete(&state.db, user_id, id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
 }
 
+/// Reopen a session the server auto-closed. Discarding one is a plain DELETE.
+#[utoipa::path(
+    post,
+    path = "/sessions/{id}/reopen",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Session))
+)]
+async fn reopen_session(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<Json<Session>, ApiError> {
+    lightweight_core::sessions::reopen(&state.db, user_id, id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Event log plus derived pause and active durations.
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}/timeline",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = SessionTimeline))
+)]
+async fn session_timeline(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<Json<SessionTimeline>, ApiError> {
+    lightweight_core::session_events::timeline(&state.db, user_id, id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Post-workout report: PRs, misses with reps-to-beat, volume against the
+/// template's previous session, adherence to targets and anomalies.
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}/report",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = SessionReport))
+)]
+async fn session_report(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<Json<SessionReport>, ApiError> {
+    lightweight_core::session_report::report(&state.db, user_id, id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// The session's rest timer, or null if none is set.
+#[utoipa::path(
+    get,
+    path = "/sessions/{id}/rest-timer",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Option<RestTimer>))
+)]
+async fn get_rest_timer(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<Json<Option<RestTimer>>, ApiError> {
+    lightweight_core::rest_timer::get(&state.db, user_id, id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{id}/rest-timer/adjust",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = AdjustRestTimer,
+    responses((status = 200, body = RestTimer))
+)]
+async fn adjust_rest_timer(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+    Json(body): Json<AdjustRestTimer>,
+) -> Result<Json<RestTimer>, ApiError> {
+    lightweight_core::rest_timer::adjust(&state.db, user_id, id, body.seconds)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{id}/rest-timer/skip",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = RestTimer))
+)]
+async fn skip_rest_timer(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<Json<RestTimer>, ApiError> {
+    lightweight_core::rest_timer::skip(&state.db, user_id, id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    post,
+    path = "/sessions/{sid}/exercises",
+    tag = "sessions",
+    params(("sid" = i64, Path, description = "Session id")),
+    request_body = AddSessionExercise,
+    responses((status = 201, body = SessionExerciseWithSets))
+)]
 async fn add_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(sid): Path<i64>,
     Json(body): Json<AddSessionExercise>,
-) -> Result<(StatusCode, Json<SessionExerciseWithSets>), StatusCode> {
+) -> Result<(StatusCode, Json<SessionExerciseWithSets>), ApiError> {
     lightweight_core::sessions::add_exercise(&state.db, user_id, sid, &body)
         .map(|e| (StatusCode::CREATED, Json(e)))
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    put,
+    path = "/sessions/{sid}/exercises/{seid}",
+    tag = "sessions",
+    params(("sid" = i64, Path, description = "Session id"), ("seid" = i64, Path, description = "Session exercise id")),
+    request_body = UpdateSessionExercise,
+    responses((status = 200, description = "Updated"))
+)]
 async fn update_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path((sid, seid)): Path<(i64, i64)>,
     Json(body): Json<UpdateSessionExercise>,
-) -> StatusCode {
-    match lightweight_core::sessions::update_exercise(&state.db, user_id, sid, seid, &body) {
-        Ok(()) => StatusCode::OK,
-        Err(lightweight_core::error::AppError::NotFound) => StatusCode::NOT_FOUND,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::sessions::update_exercise(&state.db, user_id, sid, seid, &body)
+        .map(|_| StatusCode::OK)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/sessions/{sid}/exercises/{seid}",
+    tag = "sessions",
+    params(("sid" = i64, Path, description = "Session id"), ("seid" = i64, Path, description = "Session exercise id")),
+    responses((status = 204, description = "No content"))
+)]
 async fn remove_exercise(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path((sid, seid)): Path<(i64, i64)>,
-) -> StatusCode {
-    match lightweight_core::sessions::remove_exercise(&state.db, user_id, sid, seid) {
-        Ok(()) => StatusCode::NO_CONTENT,
-        Err(lightweight_core::error::AppError::NotFound) => StatusCode::NOT_FOUND,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::sessions::remove_exercise(&state.db, user_id, sid, seid)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/{sid}/exercises/{seid}/sets",
+    tag = "sessions",
+    params(("sid" = i64, Path, description = "Session id"), ("seid" = i64, Path, description = "Session exercise id")),
+    request_body = CreateSet,
+    responses((status = 201, body = Set))
+)]
 async fn add_set(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path((_sid, seid)): Path<(i64, i64)>,
     Json(body): Json<CreateSet>,
-) -> Result<(StatusCode, Json<Set>), StatusCode> {
+) -> Result<(StatusCode, Json<Set>), ApiError> {
     lightweight_core::sessions::add_set(&state.db, user_id, seid, &body)
         .map(|s| (StatusCode::CREATED, Json(s)))
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    put,
+    path = "/sets/{id}",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = UpdateSet,
+    responses((status = 200, body = Set))
+)]
 async fn update_set(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
     Json(body): Json<UpdateSet>,
-) -> Result<Json<Set>, StatusCode> {
+) -> Result<Json<Set>, ApiError> {
     lightweight_core::sessions::update_set(&state.db, user_id, id, &body)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/sets/{id}",
+    tag = "sessions",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 204, description = "No content"))
+)]
 async fn delete_set(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> StatusCode {
-    match lightweight_core::sessions::delete_set(&state.db, user_id, id) {
-        Ok(()) => StatusCode::NO_CONTENT,
-        Err(lightweight_core::error::AppError::NotFound) => StatusCode::NOT_FOUND,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::sessions::delete_set(&state.db, user_id, id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/sync",
+    tag = "sessions",
+    request_body = Vec<SyncSession>,
+    responses((status = 200, body = SyncResult))
+)]
 async fn sync_sessions(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<Vec<SyncSession>>,
-) -> Result<Json<SyncResult>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<SyncResult>, ApiError> {
+    state.metrics.observe_sync_batch("sessions", body.len());
     lightweight_core::sessions::sync_sessions(&state.db, user_id, body)
         .map(Json)
-        .map_err(|e| {
-            let (status, msg) = match &e {
-                lightweight_core::error::AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-            };
-            (status, Json(serde_json::json!({ "error": msg })))
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/sessions/import",
+    tag = "sessions",
+    request_body = Vec<ImportSession>,
+    responses((status = 200, body = ImportResult))
+)]
 async fn import_sessions(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<Vec<ImportSession>>,
-) -> Result<Json<ImportResult>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<ImportResult>, ApiError> {
+    state.metrics.observe_sync_batch("import", body.len());
     lightweight_core::sessions::import_sessions(&state.db, user_id, body)
         .map(Json)
-        .map_err(|e| {
-            let (status, msg) = match &e {
-                lightweight_core::error::AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-            };
-            (status, Json(serde_json::json!({ "error": msg })))
-        })
+        .map_err(ApiError::from)
 }
@@ -1,13 +1,15 @@
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
     routing::{get, post},
-    Extension, Json, Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
 use crate::app::AppState;
 use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path};
 use lightweight_core::models::*;
 
 pub fn routes() -> Router<Arc<AppState>> {
@@ -19,105 +21,140 @@ pub fn routes() -> Router<Arc<AppState>> {
         .route("/templates/:id/versions/:version", get(get_version))
 }
 
+#[utoipa::path(
+    get,
+    path = "/templates",
+    tag = "templates",
+    responses((status = 200, body = Vec<Template>))
+)]
 async fn list_templates(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
-) -> Result<Json<Vec<Template>>, StatusCode> {
+) -> Result<Json<Vec<Template>>, ApiError> {
     lightweight_core::templates::list(&state.db, user_id)
         .map(Json)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/templates/{id}",
+    tag = "templates",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Template))
+)]
 async fn get_template(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<Template>, StatusCode> {
+) -> Result<Json<Template>, ApiError> {
     lightweight_core::templates::get(&state.db, user_id, id)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/templates",
+    tag = "templates",
+    request_body = CreateTemplate,
+    responses((status = 201, body = Template))
+)]
 async fn create_template(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<CreateTemplate>,
-) -> Result<(StatusCode, Json<Template>), StatusCode> {
+) -> Result<(StatusCode, Json<Template>), ApiError> {
     lightweight_core::templates::create(&state.db, user_id, &body)
         .map(|t| (StatusCode::CREATED, Json(t)))
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::AlreadyExists => StatusCode::CONFLICT,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    put,
+    path = "/templates/{id}",
+    tag = "templates",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = UpdateTemplate,
+    responses((status = 200, body = Template))
+)]
 async fn update_template(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
     Json(body): Json<UpdateTemplate>,
-) -> Result<Json<Template>, StatusCode> {
+) -> Result<Json<Template>, ApiError> {
     lightweight_core::templates::update(&state.db, user_id, id, &body)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/templates/{id}/versions",
+    tag = "templates",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 200, body = Vec<TemplateSnapshot>))
+)]
 async fn list_versions(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> Result<Json<Vec<lightweight_core::models::TemplateSnapshot>>, StatusCode> {
+) -> Result<Json<Vec<lightweight_core::models::TemplateSnapshot>>, ApiError> {
     lightweight_core::templates::list_versions(&state.db, user_id, id)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    get,
+    path = "/templates/{id}/versions/{version}",
+    tag = "templates",
+    params(("id" = i64, Path, description = "Resource id"),
+        ("version" = i64, Path, description = "Template version")),
+    responses((status = 200, body = TemplateSnapshot))
+)]
 async fn get_version(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path((id, version)): Path<(i64, i64)>,
-) -> Result<Json<lightweight_core::models::TemplateSnapshot>, StatusCode> {
+) -> Result<Json<lightweight_core::models::TemplateSnapshot>, ApiError> {
     lightweight_core::templates::get_version(&state.db, user_id, id, version)
         .map(Json)
-        .map_err(|e| match e {
-            lightweight_core::error::AppError::NotFound => StatusCode::NOT_FOUND,
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    post,
+    path = "/templates/sync",
+    tag = "templates",
+    request_body = Vec<SyncTemplate>,
+    responses((status = 200, body = TemplateSyncResult))
+)]
 async fn sync_templates(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Json(body): Json<Vec<SyncTemplate>>,
-) -> Result<Json<TemplateSyncResult>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<TemplateSyncResult>, ApiError> {
+    state.metrics.observe_sync_batch("templates", body.len());
     lightweight_core::templates::sync_templates(&state.db, user_id, body)
         .map(Json)
-        .map_err(|e| {
-            let (status, msg) = match &e {
-                lightweight_core::error::AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
-            };
-            (status, Json(serde_json::json!({ "error": msg })))
-        })
+        .map_err(ApiError::from)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/templates/{id}",
+    tag = "templates",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 204, description = "No content"))
+)]
 async fn archive_template(
     State(state): State<Arc<AppState>>,
     Extension(UserId(user_id)): Extension<UserId>,
     Path(id): Path<i64>,
-) -> StatusCode {
-    match lightweight_core::templates::archive(&state.db, user_id, id) {
-        Ok(()) => StatusCode::NO_CONTENT,
-        Err(lightweight_core::error::AppError::NotFound) => StatusCode::NOT_FOUND,
-        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
-    }
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::templates::archive(&state.db, user_id, id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
 }
@@ -0,0 +1,136 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::{get, post, put},
+    Extension, Router,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use std::sync::Arc;
+
+use crate::app::AppState;
+use crate::auth::UserId;
+use crate::error::ApiError;
+use crate::extract::{Json, Path, Query};
+use lightweight_core::models::{CreateWebhook, UpdateWebhook, Webhook, WebhookCreated, WebhookDelivery};
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/:id", put(update_webhook).delete(delete_webhook))
+        .route("/webhooks/:id/deliveries", get(list_deliveries))
+        .route("/webhooks/:id/ping", post(ping_webhook))
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    responses((status = 200, body = Vec<Webhook>))
+)]
+async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+) -> Result<Json<Vec<Webhook>>, ApiError> {
+    lightweight_core::webhooks::list(&state.db, user_id)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Subscribe a URL to events. The signing secret is only returned here.
+#[utoipa::path(
+    post,
+    path = "/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhook,
+    responses((status = 201, body = WebhookCreated))
+)]
+async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Json(body): Json<CreateWebhook>,
+) -> Result<(StatusCode, Json<WebhookCreated>), ApiError> {
+    lightweight_core::webhooks::create(&state.db, user_id, &body)
+        .map(|w| (StatusCode::CREATED, Json(w)))
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    put,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = i64, Path, description = "Resource id")),
+    request_body = UpdateWebhook,
+    responses((status = 200, body = Webhook))
+)]
+async fn update_webhook(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+    Json(body): Json<UpdateWebhook>,
+) -> Result<Json<Webhook>, ApiError> {
+    lightweight_core::webhooks::update(&state.db, user_id, id, &body)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/webhooks/{id}",
+    tag = "webhooks",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 204, description = "No content"))
+)]
+async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::webhooks::delete(&state.db, user_id, id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DeliveriesQuery {
+    /// Most recent attempts to return (default 50).
+    limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(("id" = i64, Path, description = "Resource id"), DeliveriesQuery),
+    responses((status = 200, body = Vec<WebhookDelivery>))
+)]
+async fn list_deliveries(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+    Query(q): Query<DeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, ApiError> {
+    let limit = q.limit.unwrap_or(50).clamp(1, 500);
+    lightweight_core::webhooks::deliveries(&state.db, user_id, id, limit)
+        .map(Json)
+        .map_err(ApiError::from)
+}
+
+/// Queue a `ping` delivery to check the receiver and its signature check.
+#[utoipa::path(
+    post,
+    path = "/webhooks/{id}/ping",
+    tag = "webhooks",
+    params(("id" = i64, Path, description = "Resource id")),
+    responses((status = 204, description = "Ping queued"))
+)]
+async fn ping_webhook(
+    State(state): State<Arc<AppState>>,
+    Extension(UserId(user_id)): Extension<UserId>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, ApiError> {
+    lightweight_core::webhooks::ping(&state.db, user_id, id)
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(ApiError::from)
+}
@@ -0,0 +1,170 @@
+//! Browser hardening headers on every response (API, static assets, probes).
+//! A header already set by the handler is left alone.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::app::AppState;
+use crate::config::SecurityConfig;
+
+/// Default policy for the embedded SPA. Google Identity Services and Google
+/// Fonts are the only third parties; inline scripts are allowed by hash only.
+fn default_csp(script_hashes: &[String]) -> String {
+    let mut script_src = vec!["'self'", "https://accounts.google.com/gsi/client"];
+    script_src.extend(script_hashes.iter().map(String::as_str));
+    [
+        "default-src 'self'".to_string(),
+        format!("script-src {}", script_src.join(" ")),
+        "style-src 'self' 'unsafe-inline' https://fonts.googleapis.com https://accounts.google.com/gsi/style"
+            .to_string(),
+        "font-src 'self' https://fonts.gstatic.com".to_string(),
+        "img-src 'self' data: blob:".to_string(),
+        "connect-src 'self' https://accounts.google.com/gsi/".to_string(),
+        "frame-src https://accounts.google.com/gsi/".to_string(),
+        "frame-ancestors 'none'".to_string(),
+        "base-uri 'self'".to_string(),
+        "form-action 'self'".to_string(),
+        "object-src 'none'".to_string(),
+    ]
+    .join("; ")
+}
+
+/// The fixed header set, built once at startup.
+pub struct SecurityHeaders(Vec<(HeaderName, HeaderValue)>);
+
+impl SecurityHeaders {
+    pub fn new(config: &SecurityConfig, script_hashes: &[String]) -> Self {
+        let csp = config
+            .csp
+            .clone()
+            .unwrap_or_else(|| default_csp(script_hashes));
+
+        let mut headers = vec![
+            ("content-security-policy", csp),
+            ("x-content-type-options", "nosniff".to_string()),
+            ("x-frame-options", "DENY".to_string()),
+            ("referrer-policy", "strict-origin-when-cross-origin".to_string()),
+            (
+                "permissions-policy",
+                "camera=(), microphone=(), geolocation=(), payment=(), usb=(), interest-cohort=()"
+                    .to_string(),
+            ),
+        ];
+        if config.hsts {
+            let mut hsts = format!("max-age={}", config.hsts_max_age_secs);
+            if config.hsts_include_subdomains {
+                hsts.push_str("; includeSubDomains");
+            }
+            headers.push(("strict-transport-security", hsts));
+        }
+
+        SecurityHeaders(
+            headers
+                .into_iter()
+                // `Config::validate` rejects a CSP that isn't a valid header value.
+                .filter_map(|(name, value)| {
+                    Some((HeaderName::from_static(name), HeaderValue::from_str(&value).ok()?))
+                })
+                .collect(),
+        )
+    }
+}
+
+pub async fn security_headers(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    for (name, value) in &state.security_headers.0 {
+        if !headers.contains_key(name) {
+            headers.insert(name.clone(), value.clone());
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::StatusCode, Router};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn get(app: &Router, uri: &str) -> Response {
+        app.clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+        response.headers().get(name).map(|v| v.to_str().unwrap())
+    }
+
+    #[tokio::test]
+    async fn api_and_static_responses_are_hardened() {
+        let (app, _) = crate::app::test_app("");
+        // An API error, and the SPA fallback (404 if the frontend isn't built).
+        let api = get(&app, "/api/v1/sessions").await;
+        assert_eq!(api.status(), StatusCode::UNAUTHORIZED);
+        let spa = get(&app, "/history").await;
+        assert!(matches!(
+            spa.status(),
+            StatusCode::OK | StatusCode::NOT_FOUND
+        ));
+
+        for response in [&api, &spa] {
+            let csp = header(response, "content-security-policy").unwrap();
+            assert!(
+                csp.starts_with("default-src 'self'; script-src 'self'"),
+                "{}",
+                csp
+            );
+            assert!(csp.contains("frame-ancestors 'none'"));
+            assert_eq!(header(response, "x-content-type-options"), Some("nosniff"));
+            assert_eq!(header(response, "x-frame-options"), Some("DENY"));
+            assert_eq!(
+                header(response, "referrer-policy"),
+                Some("strict-origin-when-cross-origin")
+            );
+            assert!(header(response, "permissions-policy")
+                .unwrap()
+                .contains("camera=()"));
+            assert_eq!(header(response, "strict-transport-security"), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn hsts_only_when_enabled() {
+        let (app, _) = crate::app::test_app("[security]\nhsts = true\nhsts_max_age_secs = 600\n");
+        for uri in ["/healthz", "/history"] {
+            assert_eq!(
+                header(&get(&app, uri).await, "strict-transport-security"),
+                Some("max-age=600")
+            );
+        }
+
+        let (app, _) =
+            crate::app::test_app("[security]\nhsts = true\nhsts_include_subdomains = true\n");
+        assert_eq!(
+            header(&get(&app, "/healthz").await, "strict-transport-security"),
+            Some("max-age=31536000; includeSubDomains")
+        );
+    }
+
+    #[tokio::test]
+    async fn configured_csp_replaces_the_default() {
+        let (app, _) = crate::app::test_app("[security]\ncsp = \"default-src 'none'\"\n");
+        assert_eq!(
+            header(&get(&app, "/healthz").await, "content-security-policy"),
+            Some("default-src 'none'")
+        );
+    }
+}
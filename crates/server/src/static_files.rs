@@ -1,30 +1,180 @@
 use axum::{
-    http::{header, StatusCode, Uri},
-    response::{Html, IntoResponse, Response},
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    response::{IntoResponse, Response},
 };
-use rust_embed::Embed;
+use base64::Engine;
+use rust_embed::{EmbeddedFile, Embed};
+use sha2::{Digest, Sha256};
 
 #[derive(Embed)]
 #[folder = "../../frontend/dist/"]
 struct Asset;
 
-pub async fn static_handler(uri: Uri) -> Response {
+/// Vite writes content-hashed bundles here; their names change whenever their
+/// bytes do, so they can be cached forever.
+const FINGERPRINTED_DIR: &str = "assets/";
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// Everything else (index.html, privacy.html, images in `public/`) must be
+/// revalidated; the ETag keeps that to a 304.
+const REVALIDATE: &str = "no-cache";
+
+/// Precompressed siblings written by `frontend/scripts/compress.mjs`, in order
+/// of preference.
+const ENCODINGS: &[(&str, &str)] = &[("br", ".br"), ("gzip", ".gz")];
+
+pub async fn static_handler(uri: Uri, headers: HeaderMap) -> Response {
     let path = uri.path().trim_start_matches('/');
 
-    // Try exact file match
-    if let Some(content) = Asset::get(path) {
-        let mime = mime_guess::from_path(path).first_or_octet_stream();
-        (
-            StatusCode::OK,
+    // Exact file match, else SPA fallback: serve index.html for client-side routing
+    let path = if !path.is_empty() && Asset::get(path).is_some() {
+        path
+    } else {
+        "index.html"
+    };
+
+    let accept = headers
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let Some((file, encoding)) = negotiate(path, accept) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let etag = etag(&file);
+    let cache_control = if path.starts_with(FINGERPRINTED_DIR) {
+        IMMUTABLE
+    } else {
+        REVALIDATE
+    };
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+
+    let mut response = if if_none_match(&headers, &etag) {
+        StatusCode::NOT_MODIFIED.into_response()
+    } else {
+        let mut response = (
             [(header::CONTENT_TYPE, mime.as_ref())],
-            content.data.into_owned(),
+            file.data.into_owned(),
         )
-            .into_response()
+            .into_response();
+        if let Some(encoding) = encoding {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_ENCODING, HeaderValue::from_static(encoding));
+        }
+        response
+    };
+
+    let h = response.headers_mut();
+    h.insert(header::CACHE_CONTROL, HeaderValue::from_static(cache_control));
+    h.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        h.insert(header::ETAG, value);
     }
-    // SPA fallback: serve index.html for client-side routing
-    else if let Some(content) = Asset::get("index.html") {
-        Html(String::from_utf8_lossy(&content.data).to_string()).into_response()
-    } else {
-        StatusCode::NOT_FOUND.into_response()
+    response
+}
+
+/// Pick the best precompressed variant the client accepts, falling back to
+/// the identity file.
+fn negotiate(path: &str, accept_encoding: &str) -> Option<(EmbeddedFile, Option<&'static str>)> {
+    for (encoding, suffix) in ENCODINGS {
+        if accepts(accept_encoding, encoding) {
+            if let Some(file) = Asset::get(&format!("{}{}", path, suffix)) {
+                return Some((file, Some(encoding)));
+            }
+        }
+    }
+    Asset::get(path).map(|file| (file, None))
+}
+
+/// `Accept-Encoding` contains `encoding` (or `*`) without `q=0`.
+fn accepts(accept_encoding: &str, encoding: &str) -> bool {
+    accept_encoding.split(',').any(|part| {
+        let mut params = part.split(';').map(str::trim);
+        let name = params.next().unwrap_or("");
+        let refused = params.any(|p| {
+            p.strip_prefix("q=")
+                .and_then(|q| q.parse::<f32>().ok())
+                .is_some_and(|q| q == 0.0)
+        });
+        (name.eq_ignore_ascii_case(encoding) || name == "*") && !refused
+    })
+}
+
+/// Strong ETag from the embedded file's content hash. Each encoding is its own
+/// embedded file, so variants get distinct tags.
+fn etag(file: &EmbeddedFile) -> String {
+    format!("\"{}\"", hex::encode(&file.metadata.sha256_hash()[..16]))
+}
+
+fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| {
+            v.split(',')
+                .map(|t| t.trim().trim_start_matches("W/"))
+                .any(|t| t == etag || t == "*")
+        })
+}
+
+/// CSP `'sha256-…'` sources for the inline `<script>` blocks in the embedded
+/// index.html (the theme bootstrap), so the policy needs no `'unsafe-inline'`.
+pub fn inline_script_hashes() -> Vec<String> {
+    Asset::get("index.html")
+        .map(|file| script_hashes(&String::from_utf8_lossy(&file.data)))
+        .unwrap_or_default()
+}
+
+fn script_hashes(html: &str) -> Vec<String> {
+    let mut hashes = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<script") {
+        rest = &rest[start..];
+        let Some(tag_end) = rest.find('>') else { break };
+        let tag = &rest[..tag_end];
+        let body_start = tag_end + 1;
+        let Some(len) = rest[body_start..].find("</script>") else { break };
+        let body = &rest[body_start..body_start + len];
+        if !tag.contains("src=") && !body.trim().is_empty() {
+            let digest = Sha256::digest(body.as_bytes());
+            hashes.push(format!(
+                "'sha256-{}'",
+                base64::engine::general_purpose::STANDARD.encode(digest)
+            ));
+        }
+        rest = &rest[body_start + len..];
+    }
+    hashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_encoding_parsing() {
+        assert!(accepts("gzip, deflate, br", "br"));
+        assert!(accepts("br;q=0.8, gzip", "gzip"));
+        assert!(!accepts("br;q=0, gzip", "br"));
+        assert!(accepts("*", "gzip"));
+        assert!(!accepts("", "gzip"));
+    }
+
+    #[test]
+    fn if_none_match_lists_and_weak_tags() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"a\", W/\"b\""));
+        assert!(if_none_match(&headers, "\"b\""));
+        assert!(!if_none_match(&headers, "\"c\""));
+    }
+
+    #[test]
+    fn hashes_only_inline_scripts() {
+        let html = r#"<script type="module" src="/x.js"></script><script>alert(1)</script>"#;
+        // echo -n 'alert(1)' | openssl dgst -sha256 -binary | base64
+        assert_eq!(
+            script_hashes(html),
+            vec!["'sha256-bhHHL3z2vDgxUt0W3dWQOrprscmda2Y5pLsLg4GF+pI='".to_string()]
+        );
     }
 }
@@ -0,0 +1,217 @@
+//! Logging setup and the per-request tracing span.
+//!
+//! Every request runs inside a `request` span carrying `request_id`, `method`,
+//! `route` and `platform`; the auth middleware adds `user_id`, and `status` and
+//! `duration_ms` are filled in when the response is ready. Anything logged
+//! while handling the request (including database errors surfaced through
+//! `ApiError`) is emitted inside that span, so one request ID finds every line.
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use rand::Rng;
+use std::time::Instant;
+use tracing::{field, Instrument};
+use tracing_subscriber::EnvFilter;
+
+use crate::config::{LogFormat, LoggingConfig};
+
+pub static REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Install the global subscriber. `RUST_LOG` wins over `logging.filter`.
+pub fn init(config: &LoggingConfig) {
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(&config.filter))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+
+    match config.format {
+        LogFormat::Text => tracing_subscriber::fmt().with_env_filter(filter).init(),
+        LogFormat::Json => tracing_subscriber::fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_current_span(true)
+            .with_span_list(false)
+            .init(),
+    }
+}
+
+/// Middleware: adopt the caller's `X-Request-Id` (or mint one), run the request
+/// inside its span, echo the ID back on the response and log one summary line.
+pub async fn request_span(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(generate_request_id);
+
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "fallback".to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        route = %route,
+        platform = crate::routes::detect_platform(request.headers()),
+        user_id = field::Empty,
+        status = field::Empty,
+        duration_ms = field::Empty,
+    );
+
+    // Normalise the header so handlers and downstream services see the same ID.
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        request.headers_mut().insert(REQUEST_ID.clone(), value);
+    }
+
+    let start = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+    let status = response.status();
+
+    span.record("status", status.as_u16());
+    span.record("duration_ms", start.elapsed().as_millis() as u64);
+    span.in_scope(|| {
+        if status.is_server_error() {
+            tracing::error!("request failed");
+        } else {
+            tracing::info!("request completed");
+        }
+    });
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID.clone(), value);
+    }
+    response
+}
+
+/// Accept client IDs only if they are short, printable ASCII — they end up in logs.
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b))
+}
+
+fn generate_request_id() -> String {
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    hex::encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+    use tracing::field::{Field, Visit};
+    use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+    use super::*;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/items/:id", get(|| async { StatusCode::CREATED }))
+            .layer(middleware::from_fn(request_span))
+    }
+
+    async fn send(request_id: Option<&str>) -> Response {
+        let mut request = Request::get("/items/7");
+        if let Some(id) = request_id {
+            request = request.header(&REQUEST_ID, id);
+        }
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    fn echoed(response: &Response) -> String {
+        response.headers()[&REQUEST_ID]
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn is_generated(id: &str) -> bool {
+        id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit())
+    }
+
+    #[tokio::test]
+    async fn valid_request_id_is_echoed() {
+        assert_eq!(
+            echoed(&send(Some("client-42.retry:1")).await),
+            "client-42.retry:1"
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_or_oversized_request_id_is_replaced() {
+        assert!(is_generated(&echoed(&send(None).await)));
+        assert!(is_generated(&echoed(&send(Some("has spaces")).await)));
+        let long = "a".repeat(129);
+        assert!(is_generated(&echoed(&send(Some(&long)).await)));
+        assert!(is_valid_request_id(&"a".repeat(128)));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("id\u{7f}"));
+    }
+
+    /// Collects the fields recorded on `request` spans.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<BTreeMap<String, String>>>);
+
+    struct Fields<'a>(&'a mut BTreeMap<String, String>);
+
+    impl Visit for Fields<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S: tracing::Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Capture {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _: &tracing::span::Id,
+            _: Context<'_, S>,
+        ) {
+            if attrs.metadata().name() == "request" {
+                attrs.record(&mut Fields(&mut self.0.lock().unwrap()));
+            }
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: Context<'_, S>,
+        ) {
+            if ctx.span(id).is_some_and(|span| span.name() == "request") {
+                values.record(&mut Fields(&mut self.0.lock().unwrap()));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn span_records_route_and_status() {
+        let capture = Capture::default();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let response = send(Some("trace-me")).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let fields = capture.0.lock().unwrap();
+        assert_eq!(fields["request_id"], "trace-me");
+        assert_eq!(fields["method"], "GET");
+        assert_eq!(fields["route"], "/items/:id");
+        assert_eq!(fields["status"], "201");
+        assert!(fields.contains_key("duration_ms"));
+    }
+}
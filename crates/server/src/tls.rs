@@ -0,0 +1,392 @@
+//! Built-in HTTPS for deployments without a TLS-terminating proxy.
+//!
+//! The certificate and key are read from `[tls]` paths at startup and swapped
+//! in place on `SIGHUP` or when either file's modification time changes, so a
+//! renewed certificate (certbot, acme.sh, ...) is picked up without dropping
+//! connections. A failed reload logs the error and keeps serving the old pair.
+//! Optionally a second, plain-HTTP listener redirects everything to HTTPS.
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::IntoResponse,
+    Router,
+};
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::ServerConfig;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+
+/// How long in-flight connections get to finish after shutdown is signalled.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+/// Serves the current certificate to every handshake; `reload` swaps it.
+pub struct CertResolver {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    provider: Arc<CryptoProvider>,
+    current: RwLock<Arc<CertifiedKey>>,
+}
+
+impl fmt::Debug for CertResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CertResolver")
+            .field("cert_path", &self.cert_path)
+            .field("key_path", &self.key_path)
+            .finish()
+    }
+}
+
+impl CertResolver {
+    pub fn load(cert_path: &Path, key_path: &Path) -> Result<Self, String> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let current = read_pair(&provider, cert_path, key_path)?;
+        Ok(CertResolver {
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            provider,
+            current: RwLock::new(Arc::new(current)),
+        })
+    }
+
+    /// Re-read both files. On error the previous certificate stays in use.
+    pub fn reload(&self) -> Result<(), String> {
+        let pair = read_pair(&self.provider, &self.cert_path, &self.key_path)?;
+        *self.current.write().unwrap() = Arc::new(pair);
+        Ok(())
+    }
+
+    fn modified(&self) -> (Option<SystemTime>, Option<SystemTime>) {
+        let mtime = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+        (mtime(&self.cert_path), mtime(&self.key_path))
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, _hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.current.read().unwrap().clone())
+    }
+}
+
+fn read_pair(
+    provider: &CryptoProvider,
+    cert_path: &Path,
+    key_path: &Path,
+) -> Result<CertifiedKey, String> {
+    let cert_pem =
+        std::fs::read(cert_path).map_err(|e| format!("{}: {}", cert_path.display(), e))?;
+    let key_pem = std::fs::read(key_path).map_err(|e| format!("{}: {}", key_path.display(), e))?;
+
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("{}: no certificates found", cert_path.display()));
+    }
+    let key = PrivateKeyDer::from_pem_slice(&key_pem)
+        .map_err(|e| format!("{}: {}", key_path.display(), e))?;
+    let key = provider
+        .key_provider
+        .load_private_key(key)
+        .map_err(|e| format!("{}: {}", key_path.display(), e))?;
+
+    let pair = CertifiedKey::new(certs, key);
+    pair.keys_match().map_err(|e| {
+        format!(
+            "{} does not match {}: {}",
+            key_path.display(),
+            cert_path.display(),
+            e
+        )
+    })?;
+    Ok(pair)
+}
+
+pub fn acceptor(resolver: Arc<CertResolver>) -> TlsAcceptor {
+    let mut config = ServerConfig::builder_with_provider(resolver.provider.clone())
+        .with_safe_default_protocol_versions()
+        .expect("ring provider supports the default protocol versions")
+        .with_no_client_auth()
+        .with_cert_resolver(resolver);
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    TlsAcceptor::from(Arc::new(config))
+}
+
+/// Reload on `SIGHUP`, and every `interval` if either file has changed.
+/// A zero interval disables polling.
+pub fn spawn_reloader(resolver: Arc<CertResolver>, interval: Duration) {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        let mut hangup =
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()).ok();
+        let mut seen = resolver.modified();
+
+        loop {
+            let sighup = async {
+                #[cfg(unix)]
+                if let Some(signal) = hangup.as_mut() {
+                    signal.recv().await;
+                    return;
+                }
+                std::future::pending::<()>().await
+            };
+            let tick = async {
+                if interval.is_zero() {
+                    std::future::pending::<()>().await
+                }
+                tokio::time::sleep(interval).await
+            };
+
+            let reason = tokio::select! {
+                _ = sighup => "SIGHUP",
+                _ = tick => {
+                    let now = resolver.modified();
+                    if now == seen {
+                        continue;
+                    }
+                    seen = now;
+                    "file change"
+                }
+            };
+            match resolver.reload() {
+                Ok(()) => tracing::info!(reason, "TLS certificate reloaded"),
+                Err(e) => tracing::error!(
+                    reason,
+                    "TLS certificate reload failed, keeping the old one: {}",
+                    e
+                ),
+            }
+        }
+    });
+}
+
+/// Accept TLS connections until `shutdown` resolves, then give open
+/// connections `SHUTDOWN_GRACE` to finish. Each request carries
+/// `ConnectInfo<SocketAddr>` like `into_make_service_with_connect_info`.
+pub async fn serve(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    app: Router,
+    shutdown: impl Future<Output = ()>,
+) {
+    let builder = auto::Builder::new(TokioExecutor::new());
+    let graceful = hyper_util::server::graceful::GracefulShutdown::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let (tcp, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(conn) => conn,
+                Err(e) => {
+                    tracing::warn!("accept failed: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let acceptor = acceptor.clone();
+        let builder = builder.clone();
+        let watcher = graceful.watcher();
+        let app = app.clone();
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(tcp).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::debug!(%peer, "TLS handshake failed: {}", e);
+                    return;
+                }
+            };
+            let service = hyper::service::service_fn(move |mut request: hyper::Request<_>| {
+                request.extensions_mut().insert(ConnectInfo(peer));
+                app.clone().oneshot(request.map(Body::new))
+            });
+            let conn = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            if let Err(e) = watcher.watch(conn.into_owned()).await {
+                tracing::debug!(%peer, "connection error: {}", e);
+            }
+        });
+    }
+
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = tokio::time::sleep(SHUTDOWN_GRACE) => {
+            tracing::warn!("Timed out waiting for TLS connections to close");
+        }
+    }
+}
+
+/// Plain-HTTP app that sends every request to the same host and path on the
+/// HTTPS port. 308 keeps the method and body for API clients.
+pub fn redirect_app(https_port: u16) -> Router {
+    Router::new().fallback(move |uri: Uri, headers: HeaderMap| async move {
+        match https_url(&headers, &uri, https_port) {
+            Some(location) => (
+                StatusCode::PERMANENT_REDIRECT,
+                [(header::LOCATION, location)],
+            )
+                .into_response(),
+            None => (StatusCode::BAD_REQUEST, "Host header required").into_response(),
+        }
+    })
+}
+
+fn https_url(headers: &HeaderMap, uri: &Uri, https_port: u16) -> Option<String> {
+    let host = headers.get(header::HOST)?.to_str().ok()?;
+    let authority: axum::http::uri::Authority = host.parse().ok()?;
+    let host = authority.host();
+    // `Authority::host` keeps the brackets around IPv6 literals.
+    let port = if https_port == 443 {
+        String::new()
+    } else {
+        format!(":{}", https_port)
+    };
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    Some(format!("https://{}{}{}", host, port, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use rustls::pki_types::ServerName;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Fresh directory under the system temp dir, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("lw-tls-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Write a new self-signed `localhost` pair and return its DER certificate.
+    fn write_self_signed(dir: &Path) -> CertificateDer<'static> {
+        let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        std::fs::write(dir.join("cert.pem"), generated.cert.pem()).unwrap();
+        std::fs::write(dir.join("key.pem"), generated.key_pair.serialize_pem()).unwrap();
+        generated.cert.der().clone()
+    }
+
+    /// Handshake trusting only `trusted`, then issue `GET /`. Returns the
+    /// certificate the server presented and the raw response.
+    async fn fetch(
+        addr: SocketAddr,
+        trusted: &CertificateDer<'static>,
+    ) -> (CertificateDer<'static>, String) {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(trusted.clone()).unwrap();
+        let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+        let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+
+        let tcp = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let mut stream = connector
+            .connect(ServerName::try_from("localhost").unwrap(), tcp)
+            .await
+            .unwrap();
+        let presented = stream.get_ref().1.peer_certificates().unwrap()[0]
+            .clone()
+            .into_owned();
+
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response).await;
+        (presented, response)
+    }
+
+    #[tokio::test]
+    async fn serves_https_and_reloads_certificate() {
+        let dir = TempDir::new("reload");
+        let first = write_self_signed(&dir.0);
+        let resolver =
+            Arc::new(CertResolver::load(&dir.0.join("cert.pem"), &dir.0.join("key.pem")).unwrap());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/", get(|| async { "ok" }));
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve(listener, acceptor(resolver.clone()), app, async {
+            let _ = stopped.await;
+        }));
+
+        let (presented, response) = fetch(addr, &first).await;
+        assert_eq!(presented, first);
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("ok"), "{}", response);
+
+        let second = write_self_signed(&dir.0);
+        resolver.reload().unwrap();
+        let (presented, _) = fetch(addr, &second).await;
+        assert_eq!(presented, second);
+
+        // A broken key leaves the last good pair in place.
+        std::fs::write(dir.0.join("key.pem"), "not a key").unwrap();
+        assert!(resolver.reload().is_err());
+        let (presented, _) = fetch(addr, &second).await;
+        assert_eq!(presented, second);
+
+        stop.send(()).unwrap();
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn rejects_mismatched_key() {
+        let dir = TempDir::new("mismatch");
+        write_self_signed(&dir.0);
+        let cert = std::fs::read(dir.0.join("cert.pem")).unwrap();
+        write_self_signed(&dir.0);
+        std::fs::write(dir.0.join("cert.pem"), cert).unwrap();
+        let err = CertResolver::load(&dir.0.join("cert.pem"), &dir.0.join("key.pem")).unwrap_err();
+        assert!(err.contains("does not match"), "{}", err);
+    }
+
+    #[test]
+    fn redirect_target() {
+        let mut headers = HeaderMap::new();
+        let uri: Uri = "/api/v1/sessions?limit=5".parse().unwrap();
+        assert_eq!(https_url(&headers, &uri, 443), None);
+
+        headers.insert(header::HOST, "lw.example.com:80".parse().unwrap());
+        assert_eq!(
+            https_url(&headers, &uri, 443).unwrap(),
+            "https://lw.example.com/api/v1/sessions?limit=5"
+        );
+        headers.insert(header::HOST, "[::1]:8080".parse().unwrap());
+        assert_eq!(
+            https_url(&headers, &uri, 8443).unwrap(),
+            "https://[::1]:8443/api/v1/sessions?limit=5"
+        );
+    }
+}
@@ -0,0 +1,483 @@
+//! Outgoing webhook delivery.
+//!
+//! Core queues deliveries in the `webhook_outbox` table; every
+//! `webhooks.poll_secs` this worker POSTs whatever is due and records the
+//! outcome, which schedules retries. Each body is signed with the
+//! subscription's secret:
+//!
+//! ```text
+//! X-Lightweight-Signature: sha256=<hex HMAC-SHA256(secret, body)>
+//! ```
+//!
+//! Subscription URLs come from users, so unless
+//! `webhooks.allow_private_targets` is on, a delivery is refused when its
+//! host is, or resolves to, a loopback, link-local, private or otherwise
+//! internal address. The check happens when connecting, so a name that
+//! later starts resolving somewhere internal is caught too, and redirects
+//! are not followed.
+
+use lightweight_core::db::DbPool;
+use lightweight_core::webhooks::PendingDelivery;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, WebhooksConfig};
+
+/// Deliveries sent per poll; the rest wait for the next one.
+const BATCH: i64 = 50;
+/// Response bodies are not stored, but errors keep this much for context.
+const MAX_ERROR_LEN: usize = 200;
+
+/// Start the delivery loop. Does nothing when `webhooks.enabled` is off.
+pub fn spawn(db: DbPool, config: &Config) {
+    if !config.webhooks.enabled {
+        tracing::info!("Webhook delivery disabled");
+        return;
+    }
+    let sender = match Sender::new(&config.webhooks) {
+        Ok(sender) => sender,
+        Err(e) => {
+            tracing::error!("Could not build the webhook HTTP client: {}", e);
+            return;
+        }
+    };
+    let poll = Duration::from_secs(config.webhooks.poll_secs);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(poll);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            deliver_due(&db, &sender).await;
+        }
+    });
+}
+
+/// The HTTP client and the limits every delivery goes out under.
+struct Sender {
+    client: reqwest::Client,
+    timeout: Duration,
+    allow_private_targets: bool,
+}
+
+impl Sender {
+    /// Unless private targets are allowed, names resolve through
+    /// [`PublicOnly`] and redirects are not followed, since a redirect to an
+    /// IP literal would skip the resolver.
+    fn new(config: &WebhooksConfig) -> reqwest::Result<Sender> {
+        let mut builder = reqwest::Client::builder();
+        if !config.allow_private_targets {
+            builder = builder
+                .dns_resolver(Arc::new(PublicOnly))
+                .redirect(reqwest::redirect::Policy::none());
+        }
+        Ok(Sender {
+            client: builder.build()?,
+            timeout: Duration::from_secs(config.timeout_secs),
+            allow_private_targets: config.allow_private_targets,
+        })
+    }
+
+    /// Why `url` may not be sent to, for an IP-literal host the resolver
+    /// never sees.
+    fn refuse(&self, url: &str) -> Option<String> {
+        if self.allow_private_targets {
+            return None;
+        }
+        let url = reqwest::Url::parse(url).ok()?;
+        let ip: IpAddr = url
+            .host_str()?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse()
+            .ok()?;
+        (!is_public(ip)).then(|| format!("{} is an internal address", ip))
+    }
+}
+
+/// Resolver that drops internal addresses and fails when none are left.
+struct PublicOnly;
+
+impl Resolve for PublicOnly {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} resolves only to internal addresses", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// False for loopback, link-local, private, shared (CGNAT), unspecified,
+/// broadcast, multicast and documentation addresses, and for IPv6 forms of
+/// any of them. IPv6 addresses that carry an IPv4 one (mapped, compatible,
+/// NAT64, 6to4) are judged by that address; Teredo and site-local are
+/// refused outright.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            let s = ip.segments();
+            let v4 = |hi: u16, lo: u16| {
+                IpAddr::V4(Ipv4Addr::from((u32::from(hi) << 16) | u32::from(lo)))
+            };
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(mapped));
+            }
+            // ::a.b.c.d (which also covers :: and ::1) and 64:ff9b::a.b.c.d.
+            if s[..6] == [0; 6] || s[..6] == [0x64, 0xff9b, 0, 0, 0, 0] {
+                return is_public(v4(s[6], s[7]));
+            }
+            // 6to4: 2002:AABB:CCDD::/48.
+            if s[0] == 0x2002 {
+                return is_public(v4(s[1], s[2]));
+            }
+            !(ip.is_multicast()
+                || s[0] & 0xfe00 == 0xfc00
+                || s[0] & 0xffc0 == 0xfe80
+                || s[0] & 0xffc0 == 0xfec0
+                || (s[0] == 0x2001 && s[1] == 0)
+                || (s[0] == 0x2001 && s[1] == 0xdb8)
+                || (s[0] == 0x64 && s[1] == 0xff9b && s[2] == 1))
+        }
+    }
+}
+
+/// Send everything that is due, concurrently, and record each attempt.
+async fn deliver_due(db: &DbPool, sender: &Sender) {
+    let due = {
+        let db = db.clone();
+        tokio::task::spawn_blocking(move || lightweight_core::webhooks::due(&db, BATCH)).await
+    };
+    let due = match due {
+        Ok(Ok(due)) => due,
+        Ok(Err(e)) => {
+            tracing::error!("Could not read webhook outbox: {}", e);
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Webhook outbox read panicked: {}", e);
+            return;
+        }
+    };
+
+    let attempts = due.into_iter().map(|delivery| async move {
+        let start = Instant::now();
+        let (status, error) = send(sender, &delivery).await;
+        let duration_ms = start.elapsed().as_millis() as i64;
+        if let Some(error) = &error {
+            tracing::warn!(
+                webhook_id = delivery.webhook_id,
+                outbox_id = delivery.outbox_id,
+                event = %delivery.event,
+                "Webhook delivery failed: {}",
+                error
+            );
+        }
+        (delivery.outbox_id, status, error, duration_ms)
+    });
+    let results = futures_util::future::join_all(attempts).await;
+
+    let db = db.clone();
+    let recorded = tokio::task::spawn_blocking(move || {
+        for (outbox_id, status, error, duration_ms) in results {
+            if let Err(e) = lightweight_core::webhooks::record_attempt(
+                &db,
+                outbox_id,
+                status,
+                error.as_deref(),
+                duration_ms,
+            ) {
+                tracing::error!(outbox_id, "Could not record webhook attempt: {}", e);
+            }
+        }
+    })
+    .await;
+    if let Err(e) = recorded {
+        tracing::error!("Recording webhook attempts panicked: {}", e);
+    }
+}
+
+/// POST one delivery. Returns the response status, if any, and an error for
+/// anything other than a 2xx.
+async fn send(sender: &Sender, delivery: &PendingDelivery) -> (Option<u16>, Option<String>) {
+    if let Some(refused) = sender.refuse(&delivery.url) {
+        return (None, Some(refused));
+    }
+    let response = sender
+        .client
+        .post(&delivery.url)
+        .timeout(sender.timeout)
+        .header("content-type", "application/json")
+        .header("user-agent", "Lightweight-Webhooks")
+        .header("x-lightweight-event", &delivery.event)
+        .header("x-lightweight-delivery", delivery.outbox_id.to_string())
+        .header(
+            "x-lightweight-signature",
+            format!("sha256={}", sign(&delivery.secret, delivery.body.as_bytes())),
+        )
+        .body(delivery.body.clone())
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => (Some(response.status().as_u16()), None),
+        Ok(response) => {
+            let status = response.status();
+            (Some(status.as_u16()), Some(format!("HTTP {}", status)))
+        }
+        Err(e) => {
+            // reqwest's own message leaves out the cause, e.g. the resolver's
+            // refusal, so append the source chain.
+            let mut error = e.to_string();
+            let mut source = std::error::Error::source(&e);
+            while let Some(cause) = source {
+                error.push_str(": ");
+                error.push_str(&cause.to_string());
+                source = cause.source();
+            }
+            clip(&mut error);
+            (None, Some(error))
+        }
+    }
+}
+
+/// Cut `error` to at most [`MAX_ERROR_LEN`] bytes without splitting a
+/// character.
+fn clip(error: &mut String) {
+    if error.len() > MAX_ERROR_LEN {
+        let mut end = MAX_ERROR_LEN;
+        while !error.is_char_boundary(end) {
+            end -= 1;
+        }
+        error.truncate(end);
+    }
+}
+
+/// HMAC-SHA256 (RFC 2104) as lowercase hex.
+fn sign(secret: &str, body: &[u8]) -> String {
+    const BLOCK: usize = 64;
+    let mut key = [0u8; BLOCK];
+    if secret.len() > BLOCK {
+        key[..32].copy_from_slice(&Sha256::digest(secret.as_bytes()));
+    } else {
+        key[..secret.len()].copy_from_slice(secret.as_bytes());
+    }
+    let pad = |byte: u8| key.map(|k| k ^ byte);
+    let inner = Sha256::new().chain_update(pad(0x36)).chain_update(body).finalize();
+    let outer = Sha256::new().chain_update(pad(0x5c)).chain_update(inner).finalize();
+    hex::encode(outer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::HeaderMap, http::StatusCode, routing::post, Router};
+    use lightweight_core::models::CreateWebhook;
+    use std::sync::{Arc, Mutex};
+
+    type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+    /// Local receiver answering every POST with `status`.
+    async fn receiver(status: StatusCode) -> (String, Received) {
+        let received: Received = Arc::default();
+        let app = Router::new()
+            .route(
+                "/hook",
+                post(|State((received, status)): State<(Received, StatusCode)>, headers: HeaderMap, body: String| async move {
+                    received.lock().unwrap().push((headers, body));
+                    status
+                }),
+            )
+            .with_state((received.clone(), status));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/hook", addr), received)
+    }
+
+    /// The receivers are on loopback, so these tests opt in to it.
+    fn local_sender() -> Sender {
+        Sender::new(&WebhooksConfig {
+            allow_private_targets: true,
+            ..WebhooksConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn subscribe(db: &DbPool, url: String) -> (i64, String) {
+        db.lock()
+            .unwrap()
+            .execute("INSERT INTO users (id, username, password_hash) VALUES (900, 'hooks', 'x')", [])
+            .unwrap();
+        let created = lightweight_core::webhooks::create(
+            db,
+            900,
+            &CreateWebhook { url, events: vec!["template.updated".to_string()], description: None },
+        )
+        .unwrap();
+        (created.webhook.id, created.secret)
+    }
+
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        // Test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn errors_are_clipped_on_a_char_boundary() {
+        // 199 ASCII bytes, then a 3-byte character straddling the limit.
+        let mut error = format!("{}€ and more", "x".repeat(MAX_ERROR_LEN - 1));
+        clip(&mut error);
+        assert_eq!(error, "x".repeat(MAX_ERROR_LEN - 1));
+
+        let mut error = "ü".repeat(MAX_ERROR_LEN);
+        clip(&mut error);
+        assert_eq!(error.len(), MAX_ERROR_LEN);
+        let mut short = "fine".to_string();
+        clip(&mut short);
+        assert_eq!(short, "fine");
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_payload_and_logs_it() {
+        let db = lightweight_core::db::init_memory_db().unwrap();
+        let (url, received) = receiver(StatusCode::NO_CONTENT).await;
+        let (id, secret) = subscribe(&db, url);
+        lightweight_core::webhooks::ping(&db, 900, id).unwrap();
+
+        deliver_due(&db, &local_sender()).await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        let (headers, body) = &received[0];
+        assert_eq!(headers["x-lightweight-event"], "ping");
+        assert_eq!(
+            headers["x-lightweight-signature"].to_str().unwrap(),
+            format!("sha256={}", sign(&secret, body.as_bytes()))
+        );
+        let log = lightweight_core::webhooks::deliveries(&db, 900, id, 10).unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].status_code, Some(204));
+        assert_eq!(log[0].outbox_status, "delivered");
+        assert!(lightweight_core::webhooks::due(&db, 10).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_delivery_is_retried_later() {
+        let db = lightweight_core::db::init_memory_db().unwrap();
+        let (url, received) = receiver(StatusCode::INTERNAL_SERVER_ERROR).await;
+        let (id, _) = subscribe(&db, url);
+        lightweight_core::webhooks::ping(&db, 900, id).unwrap();
+
+        deliver_due(&db, &local_sender()).await;
+
+        assert_eq!(received.lock().unwrap().len(), 1);
+        let log = lightweight_core::webhooks::deliveries(&db, 900, id, 10).unwrap();
+        assert_eq!(log[0].status_code, Some(500));
+        assert_eq!(log[0].outbox_status, "pending");
+        // Backed off, so not due again yet.
+        assert!(lightweight_core::webhooks::due(&db, 10).unwrap().is_empty());
+        let hooks = lightweight_core::webhooks::list(&db, 900).unwrap();
+        assert_eq!(hooks[0].pending, 1);
+    }
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+            "::127.0.0.1",
+            "::10.0.0.1",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+            "64:ff9b:1::808:808",
+            "2002:7f00:1::1",
+            "2002:c0a8:101::",
+            "fec0::1",
+            "2001:0:4136:e378::1",
+            "2001:db8::1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "93.184.216.34",
+            "1.1.1.1",
+            "2606:4700:4700::1111",
+            "64:ff9b::808:808",
+            "2002:808:808::1",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn internal_targets_are_refused_by_default() {
+        let db = lightweight_core::db::init_memory_db().unwrap();
+        let (url, received) = receiver(StatusCode::NO_CONTENT).await;
+        let by_name = url.replace("127.0.0.1", "localhost");
+        let (id, _) = subscribe(&db, url);
+        lightweight_core::webhooks::ping(&db, 900, id).unwrap();
+        let by_name = lightweight_core::webhooks::create(
+            &db,
+            900,
+            &CreateWebhook {
+                url: by_name,
+                events: vec!["template.updated".to_string()],
+                description: None,
+            },
+        )
+        .unwrap()
+        .webhook
+        .id;
+        lightweight_core::webhooks::ping(&db, 900, by_name).unwrap();
+
+        deliver_due(&db, &Sender::new(&WebhooksConfig::default()).unwrap()).await;
+
+        assert!(received.lock().unwrap().is_empty());
+        let log = lightweight_core::webhooks::deliveries(&db, 900, id, 10).unwrap();
+        assert_eq!(log[0].status_code, None);
+        assert_eq!(
+            log[0].error.as_deref(),
+            Some("127.0.0.1 is an internal address")
+        );
+        let log = lightweight_core::webhooks::deliveries(&db, 900, by_name, 10).unwrap();
+        assert!(log[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("localhost resolves only to internal addresses"));
+    }
+}
@@ -0,0 +1,38 @@
+// Write .br and .gz siblings for compressible files in dist/ so the server can
+// serve them precompressed (see crates/server/src/static_files.rs).
+// Uses only node:zlib; runs as the last step of `npm run build`.
+import { readdirSync, readFileSync, statSync, writeFileSync } from 'node:fs'
+import { join, extname } from 'node:path'
+import { brotliCompressSync, constants, gzipSync } from 'node:zlib'
+
+const DIST = new URL('../dist/', import.meta.url).pathname
+const EXTENSIONS = new Set(['.js', '.css', '.html', '.svg', '.json', '.txt', '.map', '.webmanifest'])
+const MIN_SIZE = 1024
+
+function* walk(dir) {
+  for (const name of readdirSync(dir)) {
+    const path = join(dir, name)
+    if (statSync(path).isDirectory()) yield* walk(path)
+    else yield path
+  }
+}
+
+let written = 0
+for (const path of walk(DIST)) {
+  if (!EXTENSIONS.has(extname(path))) continue
+  const data = readFileSync(path)
+  if (data.length < MIN_SIZE) continue
+
+  const variants = [
+    ['.br', brotliCompressSync(data, { params: { [constants.BROTLI_PARAM_QUALITY]: 11 } })],
+    ['.gz', gzipSync(data, { level: 9 })],
+  ]
+  for (const [suffix, compressed] of variants) {
+    // Only keep variants that actually save bytes.
+    if (compressed.length < data.length) {
+      writeFileSync(path + suffix, compressed)
+      written++
+    }
+  }
+}
+console.log(`compress: wrote ${written} precompressed files`)
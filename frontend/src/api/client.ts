@@ -86,6 +86,59 @@ async function request<T>(path: string, options: RequestInit = {}): Promise<T> {
   return resp.json();
 }
 
+// Follow a Server-Sent Events stream. EventSource can't send the bearer
+// token, so this reads the body over fetch. After a dropped connection it
+// reconnects with Last-Event-ID so missed changes are replayed; a `resync`
+// event means they couldn't be and the caller should refetch. Call the
+// returned function to stop.
+function subscribe(path: string, onEvent: (event: import('./types').LiveEvent) => void): () => void {
+  const controller = new AbortController();
+  let lastEventId: string | null = null;
+  const run = async () => {
+    while (!controller.signal.aborted) {
+      try {
+        const headers: Record<string, string> = { Authorization: `Bearer ${getToken()}` };
+        if (lastEventId) headers['Last-Event-ID'] = lastEventId;
+        const resp = await fetch(`${BASE}${path}`, { headers, signal: controller.signal });
+        if (resp.status === 401 || resp.status === 404 || !resp.body) return;
+        const reader = resp.body.pipeThrough(new TextDecoderStream()).getReader();
+        let buffer = '';
+        for (;;) {
+          const { value, done } = await reader.read();
+          if (done) break;
+          buffer += value;
+          let end: number;
+          while ((end = buffer.indexOf('\n\n')) >= 0) {
+            const block = buffer.slice(0, end);
+            buffer = buffer.slice(end + 2);
+            const name = block.match(/^event: ?(.*)$/m)?.[1];
+            const data = block.match(/^data: ?(.*)$/m)?.[1];
+            const id = block.match(/^id: ?(.*)$/m)?.[1];
+            if (id) lastEventId = id;
+            if (name === 'resync') onEvent({ type: 'resync' });
+            else if (name && data) onEvent(JSON.parse(data));
+          }
+        }
+      } catch {
+        if (controller.signal.aborted) return;
+      }
+      await new Promise(r => setTimeout(r, 3000));
+    }
+  };
+  run();
+  return () => controller.abort();
+}
+
+// Changes across the account (currently the rest timer of any session).
+export function subscribeEvents(onEvent: (event: import('./types').LiveEvent) => void): () => void {
+  return subscribe('/events', onEvent);
+}
+
+// Changes to one session, e.g. sets logged from another device.
+export function subscribeSessionEvents(sessionId: number, onEvent: (event: import('./types').LiveEvent) => void): () => void {
+  return subscribe(`/sessions/${sessionId}/events`, onEvent);
+}
+
 export const api = {
   // Auth
   login: (username: string, password: string) =>
@@ -200,6 +253,21 @@ export const api = {
     }),
   deleteSession: (id: number) =>
     request<void>(`/sessions/${id}`, { method: 'DELETE' }),
+  sessionTimeline: (id: number) =>
+    request<import('./types').SessionTimeline>(`/sessions/${id}/timeline`),
+  sessionReport: (id: number) =>
+    request<import('./types').SessionReport>(`/sessions/${id}/report`),
+  reopenSession: (id: number) =>
+    request<import('./types').Session>(`/sessions/${id}/reopen`, { method: 'POST' }),
+  restTimer: (id: number) =>
+    request<import('./types').RestTimer | null>(`/sessions/${id}/rest-timer`),
+  adjustRestTimer: (id: number, seconds: number) =>
+    request<import('./types').RestTimer>(`/sessions/${id}/rest-timer/adjust`, {
+      method: 'POST',
+      body: JSON.stringify({ seconds }),
+    }),
+  skipRestTimer: (id: number) =>
+    request<import('./types').RestTimer>(`/sessions/${id}/rest-timer/skip`, { method: 'POST' }),
 
   // Session exercises
   addSessionExercise: (sessionId: number, data: { exercise_id: number; position?: number }) =>
@@ -216,7 +284,7 @@ export const api = {
     request<void>(`/sessions/${sessionId}/exercises/${seId}`, { method: 'DELETE' }),
 
   // Sets
-  addSet: (sessionId: number, seId: number, data: { weight_kg?: number | null; reps: number; set_type?: string; rir?: number | null }) =>
+  addSet: (sessionId: number, seId: number, data: { weight_kg?: number | null; reps: number; set_type?: import('./types').SetType; rir?: number | null }) =>
     request<import('./types').WorkoutSet>(`/sessions/${sessionId}/exercises/${seId}/sets`, {
       method: 'POST',
       body: JSON.stringify(data),
@@ -235,6 +303,8 @@ export const api = {
   activityHeatmapPRs: () => request<import('./types').DayPR[]>('/analytics/heatmap-prs'),
   analyticsExercises: () => request<import('./types').ExerciseSummary[]>('/analytics/exercises'),
   e1rmProgression: (exerciseId: number) => request<import('./types').ExerciseE1rm>(`/analytics/e1rm/${exerciseId}`),
+  repMaxes: (exerciseId: number, rir?: number) =>
+    request<import('./types').RepMaxes>(`/analytics/rep-maxes/${exerciseId}${rir ? `?rir=${rir}` : ''}`),
   weeklyVolume: () => request<import('./types').WeeklyVolume[]>('/analytics/volume'),
   sessionFrequency: () => request<import('./types').WeeklyFrequency[]>('/analytics/frequency'),
   e1rmSpider: (exerciseIds: number[], weeks: number) =>
@@ -243,12 +313,47 @@ export const api = {
     request<import('./types').E1rmMover[]>(`/analytics/e1rm-movers${days ? `?days=${days}` : ''}`),
   staleExercises: (days?: number) =>
     request<import('./types').StaleExercise[]>(`/analytics/stale-exercises${days ? `?days=${days}` : ''}`),
+  strengthScores: () => request<import('./types').StrengthScores>('/analytics/strength-scores'),
+  plateaus: (exposures?: number) =>
+    request<import('./types').StalledExercise[]>(`/analytics/plateaus${exposures ? `?exposures=${exposures}` : ''}`),
+  restIntervals: (exerciseId?: number) =>
+    request<import('./types').RestAnalytics>(`/analytics/rest${exerciseId ? `?exercise_id=${exerciseId}` : ''}`),
+  restPerformance: (exerciseId?: number) =>
+    request<import('./types').RestPerformanceBucket[]>(`/analytics/rest-performance${exerciseId ? `?exercise_id=${exerciseId}` : ''}`),
   sessionPRs: (sessionId: number) =>
     request<import('./types').ExercisePRData[]>(`/analytics/session-prs/${sessionId}`),
   getE1rmSpiderPrefs: () => request<import('./types').E1rmSpiderPrefs>('/preferences/e1rm-spider'),
   setE1rmSpiderPrefs: (prefs: import('./types').E1rmSpiderPrefs) =>
     request<void>('/preferences/e1rm-spider', { method: 'PUT', body: JSON.stringify(prefs) }),
 
+  // Webhooks
+  listWebhooks: () => request<import('./types').Webhook[]>('/webhooks'),
+  createWebhook: (data: { url: string; events: import('./types').WebhookEvent[]; description?: string }) =>
+    request<import('./types').WebhookCreated>('/webhooks', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    }),
+  updateWebhook: (id: number, data: Partial<Pick<import('./types').Webhook, 'url' | 'events' | 'description' | 'active'>>) =>
+    request<import('./types').Webhook>(`/webhooks/${id}`, {
+      method: 'PUT',
+      body: JSON.stringify(data),
+    }),
+  deleteWebhook: (id: number) =>
+    request<void>(`/webhooks/${id}`, { method: 'DELETE' }),
+  webhookDeliveries: (id: number, limit?: number) =>
+    request<import('./types').WebhookDelivery[]>(`/webhooks/${id}/deliveries${limit ? `?limit=${limit}` : ''}`),
+  pingWebhook: (id: number) =>
+    request<void>(`/webhooks/${id}/ping`, { method: 'POST' }),
+
+  // Weekly digests
+  listDigests: (limit?: number) =>
+    request<import('./types').DigestRecord[]>(`/digests${limit ? `?limit=${limit}` : ''}`),
+  generateDigest: (weekStart?: string) =>
+    request<import('./types').DigestRecord>('/digests', {
+      method: 'POST',
+      body: JSON.stringify({ week_start: weekStart }),
+    }),
+
   // Preferences (generic)
   getPreference: (key: string) =>
     request<{ key: string; value: string }>(`/preferences/${key}`).then(r => r.value).catch(e => {
@@ -258,6 +363,15 @@ export const api = {
   setPreference: (key: string, value: string) =>
     request<void>(`/preferences/${key}`, { method: 'PUT', body: JSON.stringify({ value }) }),
 
+  // Bodyweight
+  listBodyweights: () => request<import('./types').Bodyweight[]>('/bodyweights'),
+  recordBodyweight: (date: string, weightKg: number) =>
+    request<import('./types').Bodyweight>(`/bodyweights/${date}`, {
+      method: 'PUT',
+      body: JSON.stringify({ weight_kg: weightKg }),
+    }),
+  deleteBodyweight: (date: string) => request<void>(`/bodyweights/${date}`, { method: 'DELETE' }),
+
   // Export
   exportMeta: () => request<import('./types').ExportMeta>('/export/meta'),
   exportSessions: async () => {
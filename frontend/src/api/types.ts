@@ -39,13 +39,29 @@ export interface TemplateSnapshot {
   created_at: string;
 }
 
+export type SessionStatus = 'active' | 'paused' | 'completed' | 'abandoned';
+
+/**
+ * Warmups are left out of analytics. Drop, cluster and myo-rep sets count
+ * toward volume but never estimate an e1RM or set a PR.
+ */
+export type SetType =
+  | 'warmup'
+  | 'working'
+  | 'drop'
+  | 'failure'
+  | 'amrap'
+  | 'backoff'
+  | 'cluster'
+  | 'myorep';
+
 export interface WorkoutSet {
   id: number;
   session_exercise_id: number;
   set_number: number;
   weight_kg: number | null;
   reps: number;
-  set_type: string;
+  set_type: SetType;
   rir: number | null;
   completed_at: string;
 }
@@ -68,8 +84,11 @@ export interface Session {
   ended_at: string | null;
   paused_duration: number;
   notes: string | null;
-  status: string;
+  status: SessionStatus;
   template_version: number | null;
+  /** Closed by the server's stale-session policy, not the user. */
+  auto_closed: boolean;
+  auto_close_reason: 'idle' | 'empty' | null;
   exercises: SessionExercise[];
 }
 
@@ -80,11 +99,177 @@ export interface SessionSummary {
   name: string | null;
   started_at: string;
   ended_at: string | null;
-  status: string;
+  status: SessionStatus;
   set_count: number;
   exercise_count: number;
   target_set_count: number | null;
   template_version: number | null;
+  auto_closed: boolean;
+  auto_close_reason: 'idle' | 'empty' | null;
+}
+
+export interface SessionEvent {
+  id: number;
+  kind: 'start' | 'pause' | 'resume' | 'exercise_added' | 'set_logged' | 'end' | 'reopen';
+  at: string;
+  session_exercise_id: number | null;
+  set_id: number | null;
+  detail: string | null;
+}
+
+export interface PauseInterval {
+  started_at: string;
+  ended_at: string | null;
+  seconds: number;
+}
+
+export interface SessionTimeline {
+  session_id: number;
+  started_at: string;
+  ended_at: string | null;
+  events: SessionEvent[];
+  pauses: PauseInterval[];
+  paused_duration: number;
+  active_duration: number;
+}
+
+export interface SessionPR {
+  exercise_id: number;
+  exercise_name: string;
+  set_id: number;
+  weight_kg: number;
+  reps: number;
+  e1rm: number;
+  previous_best: number;
+}
+
+export interface ExerciseReport {
+  exercise_id: number;
+  exercise_name: string;
+  working_sets: number;
+  volume_kg: number;
+  best_e1rm: number | null;
+  top_weight_kg: number | null;
+  previous_best_e1rm: number | null;
+  pr: boolean;
+  e1rm_gap: number | null;
+  reps_to_beat: number | null;
+  previous_volume_kg: number | null;
+  target_sets: number | null;
+  target_reps_min: number | null;
+  target_reps_max: number | null;
+  sets_in_range: number | null;
+}
+
+export interface SessionReport {
+  session_id: number;
+  name: string | null;
+  template_id: number | null;
+  template_name: string | null;
+  started_at: string;
+  ended_at: string | null;
+  status: SessionStatus;
+  working_sets: number;
+  volume_kg: number;
+  prs: SessionPR[];
+  exercises: ExerciseReport[];
+  volume_change: {
+    previous_session_id: number;
+    previous_started_at: string;
+    previous_volume_kg: number;
+    delta_kg: number;
+    delta_pct: number | null;
+  } | null;
+  adherence: {
+    template_version: number | null;
+    planned_exercises: number;
+    completed_exercises: number;
+    planned_sets: number;
+    completed_sets: number;
+    sets_in_range: number;
+    sets_with_range: number;
+    score: number | null;
+  } | null;
+  anomalies: {
+    kind: 'rep_drop_off' | 'e1rm_regression' | 'skipped_exercise';
+    exercise_id: number;
+    exercise_name: string;
+    set_id: number | null;
+    message: string;
+  }[];
+}
+
+export interface RestTimer {
+  session_id: number;
+  session_exercise_id: number | null;
+  set_id: number | null;
+  duration_seconds: number;
+  started_at: string;
+  ends_at: string;
+  remaining_seconds: number;
+  state: 'running' | 'done' | 'skipped';
+  server_time: string;
+}
+
+export type LiveEvent =
+  | { type: 'session_status'; session_id: number; status: string; ended_at: string | null }
+  | { type: 'exercise_added'; session_id: number; exercise: SessionExercise }
+  | { type: 'exercise_updated'; session_id: number; session_exercise_id: number; position: number; notes: string | null }
+  | { type: 'exercise_removed'; session_id: number; session_exercise_id: number }
+  | { type: 'set_added' | 'set_updated'; session_id: number; set: WorkoutSet }
+  | { type: 'set_deleted'; session_id: number; session_exercise_id: number; set_id: number }
+  | { type: 'rest_timer'; session_id: number; timer: RestTimer | null }
+  | { type: 'resync' };
+
+export type WebhookEvent = 'session.completed' | 'pr.achieved' | 'template.updated' | 'sync.completed' | 'digest.ready';
+
+export interface Webhook {
+  id: number;
+  url: string;
+  events: WebhookEvent[];
+  description: string | null;
+  active: boolean;
+  created_at: string;
+  pending: number;
+  failed: number;
+}
+
+export interface WebhookCreated extends Webhook {
+  secret: string;
+}
+
+export interface WebhookDelivery {
+  id: number;
+  outbox_id: number;
+  event: WebhookEvent | 'ping';
+  attempted_at: string;
+  status_code: number | null;
+  error: string | null;
+  duration_ms: number;
+  outbox_status: 'pending' | 'delivered' | 'failed';
+}
+
+export interface Digest {
+  week_start: string;
+  week_end: string;
+  timezone: string;
+  sessions: { session_id: number; name: string | null; date: string; working_sets: number; volume_kg: number }[];
+  working_sets: number;
+  volume_kg: number;
+  muscle_groups: { muscle_group: string; set_count: number }[];
+  prs: SessionPR[];
+  movers: E1rmMover[];
+  stale: StaleExercise[];
+  streak: { current_weeks: number; longest_weeks: number };
+}
+
+export interface DigestRecord {
+  id: number;
+  week_start: string;
+  timezone: string;
+  created_at: string;
+  emailed_at: string | null;
+  digest: Digest;
 }
 
 export interface ExerciseHistory {
@@ -175,6 +360,65 @@ export interface E1rmSpiderPrefs {
   exercise_ids: number[];
 }
 
+export interface CompetitionLifts {
+  squat: number | null;
+  bench: number | null;
+  deadlift: number | null;
+}
+
+export interface PlateCount {
+  weight_kg: number;
+  pairs: number;
+}
+
+export interface EquipmentProfile {
+  bar_kg: number;
+  plates: PlateCount[];
+  dumbbells_kg: number[];
+}
+
+export interface Bodyweight {
+  measured_on: string;
+  weight_kg: number;
+}
+
+export interface StrengthScorePoint {
+  date: string;
+  bodyweight_kg: number;
+  squat_e1rm: number | null;
+  bench_e1rm: number | null;
+  deadlift_e1rm: number | null;
+  total: number | null;
+  wilks: number | null;
+  dots: number | null;
+  ipf_gl: number | null;
+  bench_ipf_gl: number | null;
+}
+
+export interface StrengthScores {
+  sex: 'male' | 'female';
+  equipment: 'raw' | 'equipped';
+  lifts: CompetitionLifts;
+  points: StrengthScorePoint[];
+}
+
+export interface RepMax {
+  reps: number;
+  weight_kg: number;
+  estimate_kg: number;
+  plates_per_side: number[] | null;
+  pct_e1rm: number;
+}
+
+export interface RepMaxes {
+  exercise_id: number;
+  exercise_name: string;
+  e1rm: number;
+  date: string;
+  rir: number;
+  rep_maxes: RepMax[];
+}
+
 export interface E1rmMover {
   exercise_id: number;
   exercise_name: string;
@@ -193,6 +437,53 @@ export interface StaleExercise {
   total_sets: number;
 }
 
+export type Intervention = 'deload' | 'rep_range' | 'variation_swap';
+
+export interface StalledExercise {
+  exercise_id: number;
+  exercise_name: string;
+  muscle_group: string | null;
+  best_e1rm: number;
+  best_date: string;
+  last_trained: string;
+  exposures_since_best: number;
+  days_stalled: number;
+  slope_per_week: number;
+  effort_change: number | null;
+  intervention: Intervention;
+  suggestion: string;
+}
+
+export interface ExerciseRest {
+  exercise_id: number;
+  exercise_name: string;
+  intervals: number;
+  avg_rest_seconds: number;
+  prescribed_rest_seconds: number | null;
+  avg_deviation_seconds: number | null;
+}
+
+export interface SessionRest {
+  session_id: number;
+  started_at: string;
+  template_id: number | null;
+  exercises: ExerciseRest[];
+}
+
+export interface RestAnalytics {
+  sessions: SessionRest[];
+  exercises: ExerciseRest[];
+}
+
+export interface RestPerformanceBucket {
+  min_rest_seconds: number;
+  max_rest_seconds: number | null;
+  intervals: number;
+  avg_rest_seconds: number | null;
+  avg_rep_drop: number | null;
+  avg_rep_drop_pct: number | null;
+}
+
 export interface ExercisePreviousSets {
   exercise_id: number;
   sets: WorkoutSet[];
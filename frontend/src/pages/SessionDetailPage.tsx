@@ -45,6 +45,15 @@ export function SessionDetailPage() {
     navigate('/history');
   };
 
+  const handleReopen = async () => {
+    try {
+      await api.reopenSession(session.id);
+      navigate('/workout');
+    } catch (e) {
+      alert(e instanceof Error ? e.message : 'Could not reopen session');
+    }
+  };
+
   const getTemplateExercise = (exerciseId: number): TemplateExercise | undefined =>
     template?.exercises.find(te => te.exercise_id === exerciseId);
 
@@ -99,6 +108,26 @@ export function SessionDetailPage() {
         </button>
       </div>
 
+      {session.auto_closed && (
+        <div className="card" style={{ fontSize: 13, color: 'var(--text-secondary)' }}>
+          {session.auto_close_reason === 'empty'
+            ? 'This workout was auto-ended because no sets were logged.'
+            : 'This workout was auto-ended after a long gap since the last set.'}
+          <div style={{ display: 'flex', gap: 8, marginTop: 8 }}>
+            <button className="btn btn-ghost" style={{ fontSize: 13 }} onClick={handleReopen}>
+              Reopen
+            </button>
+            <button
+              className="btn btn-ghost"
+              style={{ color: 'var(--accent-red)', fontSize: 13 }}
+              onClick={handleDelete}
+            >
+              Discard
+            </button>
+          </div>
+        </div>
+      )}
+
       {session.notes && (
         <div className="card" style={{ color: 'var(--text-primary)', fontSize: 13 }}>
           {session.notes}
@@ -80,6 +80,9 @@ function Toggle({ on, onToggle, disabled }: { on: boolean; onToggle: () => void;
 export function SettingsPage() {
   const { theme, toggleTheme } = useTheme();
   const [showWhatsNew, setShowWhatsNew] = useState(true);
+  const [staleTimeout, setStaleTimeout] = useState('120');
+  const [timezone, setTimezone] = useState('UTC');
+  const [weekStart, setWeekStart] = useState('monday');
   const [saving, setSaving] = useState(false);
   const [exportMeta, setExportMeta] = useState<ExportMeta | null>(null);
   const [exporting, setExporting] = useState(false);
@@ -96,6 +99,18 @@ export function SettingsPage() {
       if (val === 'false') setShowWhatsNew(false);
     }).catch(() => {});
 
+    api.getPreference('stale_session_timeout_minutes').then(val => {
+      if (val) setStaleTimeout(val);
+    }).catch(() => {});
+
+    api.getPreference('timezone').then(val => {
+      if (val) setTimezone(val);
+    }).catch(() => {});
+
+    api.getPreference('week_start').then(val => {
+      if (val) setWeekStart(val);
+    }).catch(() => {});
+
     api.exportMeta().then(setExportMeta).catch(() => {});
     api.listInvites().then(setInviteData).catch(() => {});
 
@@ -124,6 +139,23 @@ export function SettingsPage() {
     setSaving(false);
   };
 
+  const changeStaleTimeout = async (value: string) => {
+    setStaleTimeout(value);
+    try {
+      await api.setPreference('stale_session_timeout_minutes', value);
+    } catch { /* best effort */ }
+  };
+
+  const changeCalendarPref = async (key: 'timezone' | 'week_start', value: string) => {
+    (key === 'timezone' ? setTimezone : setWeekStart)(value);
+    try {
+      await api.setPreference(key, value);
+    } catch { /* best effort */ }
+  };
+
+  const deviceTimezone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+  const timezoneOptions = [...new Set([timezone, deviceTimezone, 'UTC'].filter(Boolean))];
+
   const handleExport = async () => {
     if (exporting || exportDone || exportCooldown) return;
     setExporting(true);
@@ -251,6 +283,58 @@ export function SettingsPage() {
         />
       </div>
 
+      {/* Workouts */}
+      <div style={{ marginBottom: 24 }}>
+        <div className="label" style={{ marginBottom: 12 }}>WORKOUTS</div>
+        <SettingsRow
+          label="AUTO-END WORKOUT"
+          description="End an open workout after this long without a logged set"
+          right={
+            <select
+              value={staleTimeout}
+              onChange={e => changeStaleTimeout(e.target.value)}
+              style={{ fontFamily: 'var(--font-data)', fontSize: 12 }}
+            >
+              <option value="60">1H</option>
+              <option value="120">2H</option>
+              <option value="240">4H</option>
+              <option value="480">8H</option>
+              <option value="0">NEVER</option>
+            </select>
+          }
+        />
+        <SettingsRow
+          label="TIMEZONE"
+          description="Days and weeks in analytics and the weekly digest"
+          right={
+            <select
+              value={timezone}
+              onChange={e => changeCalendarPref('timezone', e.target.value)}
+              style={{ fontFamily: 'var(--font-data)', fontSize: 12 }}
+            >
+              {timezoneOptions.map(tz => (
+                <option key={tz} value={tz}>{tz === deviceTimezone && tz !== timezone ? `${tz} (DEVICE)` : tz}</option>
+              ))}
+            </select>
+          }
+        />
+        <SettingsRow
+          label="WEEK STARTS ON"
+          description="First day of each week in charts and digests"
+          right={
+            <select
+              value={weekStart}
+              onChange={e => changeCalendarPref('week_start', e.target.value)}
+              style={{ fontFamily: 'var(--font-data)', fontSize: 12 }}
+            >
+              <option value="monday">MON</option>
+              <option value="sunday">SUN</option>
+              <option value="saturday">SAT</option>
+            </select>
+          }
+        />
+      </div>
+
       {/* Data */}
       <div style={{ marginBottom: 24 }}>
         <div className="label" style={{ marginBottom: 12 }}>DATA</div>
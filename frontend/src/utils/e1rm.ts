@@ -1,4 +1,4 @@
-import type { ExercisePRData } from '../api/types';
+import type { ExercisePRData, SetType } from '../api/types';
 
 // e1RM policy (decision 2026-07-13): RAW REPS ONLY.
 // RIR is logged as context but NEVER folded into reps — a subjective
@@ -110,12 +110,15 @@ export function absoluteProgressionTargets(
   return progressionTargets({ targetE1rm: prData.best_e1rm_ever, currentWeight, ...opts });
 }
 
+/** Set types that estimate an e1RM; mirrors the server's `SetType`. */
+const E1RM_SET_TYPES: SetType[] = ['working', 'failure', 'amrap', 'backoff'];
+
 export function getPRBadge(
-  set: { weight_kg: number | null; reps: number; set_number: number; set_type?: string },
+  set: { weight_kg: number | null; reps: number; set_number: number; set_type?: SetType },
   prData: ExercisePRData | undefined,
 ): PRBadge {
   if (!prData || !set.weight_kg || set.weight_kg <= 0 || set.reps <= 0) return null;
-  if (set.set_type && set.set_type !== 'working') return null;
+  if (set.set_type && !E1RM_SET_TYPES.includes(set.set_type)) return null;
 
   const e1rm = calcE1rm(set.weight_kg, set.reps);
 
@@ -0,0 +1,17 @@
+-- Background job state, owned by the server's scheduler.
+-- One row per job; lw-admin reads it and sets run_requested to trigger a run.
+
+CREATE TABLE jobs (
+    name                 TEXT PRIMARY KEY,
+    interval_secs        INTEGER NOT NULL,
+    next_run_at          TEXT NOT NULL DEFAULT (datetime('now')),
+    last_started_at      TEXT,
+    last_finished_at     TEXT,
+    last_status          TEXT,                 -- 'ok' | 'failed'
+    last_message         TEXT,                 -- summary on success, error on failure
+    last_duration_ms     INTEGER,
+    run_count            INTEGER NOT NULL DEFAULT 0,
+    failure_count        INTEGER NOT NULL DEFAULT 0,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    run_requested        INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,7 @@
+-- Auto-close audit trail. `auto_close_reason` is 'idle' (no set logged within
+-- the user's stale-session timeout) or 'empty' (no sets at all within the
+-- empty-session timeout). `reopened_at` restarts the idle clock after a
+-- user reopens an auto-closed session.
+ALTER TABLE sessions ADD COLUMN auto_closed INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE sessions ADD COLUMN auto_close_reason TEXT;
+ALTER TABLE sessions ADD COLUMN reopened_at TEXT;
@@ -0,0 +1,26 @@
+-- Append-only session timeline. `kind` is one of: start, pause, resume,
+-- exercise_added, set_logged, end, reopen. Pause totals are derived from it.
+
+CREATE TABLE session_events (
+    id                  INTEGER PRIMARY KEY,
+    session_id          INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    kind                TEXT NOT NULL,
+    at                  TEXT NOT NULL DEFAULT (datetime('now')),
+    session_exercise_id INTEGER,
+    set_id              INTEGER,
+    detail              TEXT
+);
+
+CREATE INDEX idx_session_events_session ON session_events(session_id, at);
+
+-- Backfill what existing rows can tell us. Pauses were never recorded, so
+-- older sessions keep their stored paused_duration.
+INSERT INTO session_events (session_id, kind, at)
+SELECT id, 'start', started_at FROM sessions;
+
+INSERT INTO session_events (session_id, kind, at, session_exercise_id, set_id)
+SELECT se.session_id, 'set_logged', st.completed_at, se.id, st.id
+FROM sets st JOIN session_exercises se ON se.id = st.session_exercise_id;
+
+INSERT INTO session_events (session_id, kind, at, detail)
+SELECT id, 'end', ended_at, status FROM sessions WHERE ended_at IS NOT NULL;
@@ -0,0 +1,11 @@
+-- Server-held rest timer, at most one per session. Started when a set is
+-- logged; the countdown ends at started_at + duration_seconds.
+
+CREATE TABLE rest_timers (
+    session_id          INTEGER PRIMARY KEY REFERENCES sessions(id) ON DELETE CASCADE,
+    session_exercise_id INTEGER REFERENCES session_exercises(id) ON DELETE SET NULL,
+    set_id              INTEGER REFERENCES sets(id) ON DELETE SET NULL,
+    duration_seconds    INTEGER NOT NULL,
+    started_at          TEXT NOT NULL DEFAULT (datetime('now')),
+    skipped_at          TEXT
+);
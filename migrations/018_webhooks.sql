@@ -0,0 +1,43 @@
+-- Outgoing webhooks. `events` is a comma-separated list of event names
+-- (session.completed, pr.achieved, template.updated, sync.completed).
+CREATE TABLE webhooks (
+    id          INTEGER PRIMARY KEY,
+    user_id     INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url         TEXT NOT NULL,
+    secret      TEXT NOT NULL,
+    events      TEXT NOT NULL,
+    description TEXT,
+    active      INTEGER NOT NULL DEFAULT 1,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_webhooks_user ON webhooks(user_id);
+
+-- One row per event per subscription, written by the change that caused it.
+-- `status` is pending, delivered or failed (gave up after too many attempts).
+CREATE TABLE webhook_outbox (
+    id              INTEGER PRIMARY KEY,
+    webhook_id      INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    event           TEXT NOT NULL,
+    data            TEXT NOT NULL,
+    status          TEXT NOT NULL DEFAULT 'pending',
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TEXT NOT NULL DEFAULT (datetime('now')),
+    created_at      TEXT NOT NULL DEFAULT (datetime('now')),
+    delivered_at    TEXT
+);
+
+CREATE INDEX idx_webhook_outbox_due ON webhook_outbox(status, next_attempt_at);
+
+-- Every delivery attempt, for the subscription's delivery log.
+CREATE TABLE webhook_deliveries (
+    id            INTEGER PRIMARY KEY,
+    outbox_id     INTEGER NOT NULL REFERENCES webhook_outbox(id) ON DELETE CASCADE,
+    webhook_id    INTEGER NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    attempted_at  TEXT NOT NULL DEFAULT (datetime('now')),
+    status_code   INTEGER,
+    error         TEXT,
+    duration_ms   INTEGER NOT NULL
+);
+
+CREATE INDEX idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, attempted_at);
@@ -0,0 +1,15 @@
+-- Weekly training digests, one per user per week. `week_start` is the Monday
+-- of the week in the user's timezone at generation time; `summary` is the
+-- structured digest as JSON, rendered alongside as Markdown and HTML.
+CREATE TABLE digests (
+    id          INTEGER PRIMARY KEY,
+    user_id     INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    week_start  TEXT NOT NULL,
+    timezone    TEXT NOT NULL,
+    summary     TEXT NOT NULL,
+    markdown    TEXT NOT NULL,
+    html        TEXT NOT NULL,
+    created_at  TEXT NOT NULL DEFAULT (datetime('now')),
+    emailed_at  TEXT,
+    UNIQUE(user_id, week_start)
+);
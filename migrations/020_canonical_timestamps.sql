@@ -0,0 +1,20 @@
+-- Rewrite session and set timestamps into the one stored format, UTC
+-- `YYYY-MM-DD HH:MM:SS` as `datetime('now')` writes it. Older clients sent
+-- ISO 8601 (`T` separator, `Z` or offset, fractional seconds), which sorts
+-- and compares wrongly against the rest. `datetime()` understands all of
+-- those; values it can't parse are left alone.
+
+UPDATE sessions SET started_at = datetime(started_at)
+WHERE datetime(started_at) IS NOT NULL AND started_at != datetime(started_at);
+
+UPDATE sessions SET ended_at = datetime(ended_at)
+WHERE datetime(ended_at) IS NOT NULL AND ended_at != datetime(ended_at);
+
+UPDATE sessions SET reopened_at = datetime(reopened_at)
+WHERE datetime(reopened_at) IS NOT NULL AND reopened_at != datetime(reopened_at);
+
+UPDATE sets SET completed_at = datetime(completed_at)
+WHERE datetime(completed_at) IS NOT NULL AND completed_at != datetime(completed_at);
+
+UPDATE session_events SET at = datetime(at)
+WHERE datetime(at) IS NOT NULL AND at != datetime(at);
@@ -0,0 +1,77 @@
+-- Restrict sessions.status and sets.set_type to the values the server
+-- understands. Existing values are trimmed and lowercased first, with common
+-- spellings folded in ('Warm-up', 'drop set', 'myo-reps'). A status that still
+-- isn't known becomes 'completed' if the session ended, else 'active'; an
+-- unknown set type becomes 'working', so it keeps counting in analytics.
+PRAGMA foreign_keys=OFF;
+BEGIN;
+
+CREATE TABLE sessions_new (
+    id                INTEGER PRIMARY KEY,
+    user_id           INTEGER NOT NULL REFERENCES users(id),
+    template_id       INTEGER REFERENCES templates(id),
+    name              TEXT,
+    started_at        TEXT NOT NULL DEFAULT (datetime('now')),
+    ended_at          TEXT,
+    paused_duration   INTEGER NOT NULL DEFAULT 0,
+    notes             TEXT,
+    status            TEXT NOT NULL DEFAULT 'active'
+                      CHECK (status IN ('active', 'paused', 'completed', 'abandoned')),
+    template_version  INTEGER,
+    auto_closed       INTEGER NOT NULL DEFAULT 0,
+    auto_close_reason TEXT,
+    reopened_at       TEXT
+);
+INSERT INTO sessions_new (id, user_id, template_id, name, started_at, ended_at, paused_duration, notes,
+                          status, template_version, auto_closed, auto_close_reason, reopened_at)
+    SELECT id, user_id, template_id, name, started_at, ended_at, paused_duration, notes,
+           CASE
+               WHEN lower(trim(status)) IN ('active', 'paused', 'completed', 'abandoned') THEN lower(trim(status))
+               WHEN ended_at IS NOT NULL THEN 'completed'
+               ELSE 'active'
+           END,
+           template_version, auto_closed, auto_close_reason, reopened_at
+    FROM sessions;
+DROP TABLE sessions;
+ALTER TABLE sessions_new RENAME TO sessions;
+
+CREATE INDEX idx_sessions_template ON sessions(template_id);
+CREATE INDEX idx_sessions_started ON sessions(started_at);
+CREATE INDEX idx_sessions_user ON sessions(user_id);
+
+CREATE TABLE sets_new (
+    id                  INTEGER PRIMARY KEY,
+    session_exercise_id INTEGER NOT NULL REFERENCES session_exercises(id) ON DELETE CASCADE,
+    set_number          INTEGER NOT NULL,
+    weight_kg           REAL,
+    reps                INTEGER NOT NULL,
+    set_type            TEXT NOT NULL DEFAULT 'working'
+                        CHECK (set_type IN ('warmup', 'working', 'drop', 'failure', 'amrap',
+                                            'backoff', 'cluster', 'myorep')),
+    completed_at        TEXT NOT NULL DEFAULT (datetime('now')),
+    rir                 INTEGER,
+    UNIQUE(session_exercise_id, set_number)
+);
+INSERT INTO sets_new (id, session_exercise_id, set_number, weight_kg, reps, set_type, completed_at, rir)
+    SELECT id, session_exercise_id, set_number, weight_kg, reps,
+           CASE replace(replace(replace(lower(trim(set_type)), '-', ''), ' ', ''), '_', '')
+               WHEN 'warmup' THEN 'warmup'
+               WHEN 'drop' THEN 'drop'
+               WHEN 'dropset' THEN 'drop'
+               WHEN 'failure' THEN 'failure'
+               WHEN 'amrap' THEN 'amrap'
+               WHEN 'backoff' THEN 'backoff'
+               WHEN 'cluster' THEN 'cluster'
+               WHEN 'myorep' THEN 'myorep'
+               WHEN 'myoreps' THEN 'myorep'
+               ELSE 'working'
+           END,
+           completed_at, rir
+    FROM sets;
+DROP TABLE sets;
+ALTER TABLE sets_new RENAME TO sets;
+
+CREATE INDEX idx_sets_session_exercise ON sets(session_exercise_id);
+
+COMMIT;
+PRAGMA foreign_keys=ON;
@@ -0,0 +1,9 @@
+-- Bodyweight log, at most one entry per user per local date. Relative-strength
+-- scores take the entry on or before each training day.
+CREATE TABLE bodyweights (
+    id          INTEGER PRIMARY KEY,
+    user_id     INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    measured_on TEXT NOT NULL,
+    weight_kg   REAL NOT NULL CHECK (weight_kg > 0),
+    UNIQUE(user_id, measured_on)
+);